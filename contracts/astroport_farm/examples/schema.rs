@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use spectrum::astroport_farm::{
-    InstantiateMsg, CallbackMsg, Cw20HookMsg, ExecuteMsg, QueryMsg, RewardInfoResponse,
+    InstantiateMsg, CallbackMsg, Cw20HookMsg, ExecuteMsg, PerformanceStatsResponse, PoolInfoResponse, QueryMsg, RewardInfoResponse, SimulateBondAssetsResponse,
 };
 
 fn main() {
@@ -18,4 +18,7 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(RewardInfoResponse), &out_dir);
+    export_schema(&schema_for!(PerformanceStatsResponse), &out_dir);
+    export_schema(&schema_for!(PoolInfoResponse), &out_dir);
+    export_schema(&schema_for!(SimulateBondAssetsResponse), &out_dir);
 }
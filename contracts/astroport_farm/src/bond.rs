@@ -1,14 +1,14 @@
-use astroport::asset::{Asset, token_asset};
-use astroport::querier::query_token_balance;
-use cosmwasm_std::{attr, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Coin, Decimal};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use cosmwasm_std::{attr, Addr, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Storage, Uint128, Coin, Decimal};
+use astroport::pair::PoolResponse;
 
 use crate::error::ContractError;
-use crate::state::{ScalingOperation, CONFIG, REWARD, STATE, Config};
+use crate::state::{ScalingOperation, CONFIG, REWARD, STATE, Config, RewardInfo, State};
 
 use cw20::{Expiration};
 
 use spectrum::adapters::asset::AssetEx;
-use spectrum::astroport_farm::{RewardInfoResponse, RewardInfoResponseItem, CallbackMsg};
+use spectrum::astroport_farm::{PoolInfoResponse, RewardInfoResponse, RewardInfoResponseItem, CallbackMsg, SimulateBondAssetsResponse};
 use spectrum::helper::{ScalingUint128};
 
 /// ## Description
@@ -23,19 +23,48 @@ pub fn bond_assets(
     slippage_tolerance: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
+    let staking_token = config.liquidity_token_info();
+    let slippage_tolerance = config.resolve_slippage(slippage_tolerance)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut funds: Vec<Coin> = vec![];
     
-    let mut low_asset = &assets[0];
-    for asset in assets[1..].iter() {
-        if asset.eq(low_asset) {
-            return Err(ContractError::DuplicatedAsset {});
+    if has_duplicated_asset(&assets) {
+        return Err(ContractError::DuplicatedAsset {});
+    }
+
+    // declared native amounts must match attached funds exactly
+    for coin in info.funds.iter() {
+        let declared = assets
+            .iter()
+            .find(|asset| matches!(&asset.info, AssetInfo::NativeToken { denom } if denom == &coin.denom))
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        if declared != coin.amount {
+            return Err(ContractError::NativeAmountMismatch {
+                denom: coin.denom.clone(),
+                declared,
+                sent: coin.amount,
+            });
         }
-        low_asset = &asset;
     }
-    
+    for asset in assets.iter() {
+        if let AssetInfo::NativeToken { denom } = &asset.info {
+            let sent = info.funds
+                .iter()
+                .find(|coin| &coin.denom == denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            if sent != asset.amount {
+                return Err(ContractError::NativeAmountMismatch {
+                    denom: denom.clone(),
+                    declared: asset.amount,
+                    sent,
+                });
+            }
+        }
+    }
+
     for asset in assets.iter() {
         asset.deposit_asset(&info, &env.contract.address, &mut messages)?;
         if !asset.amount.is_zero() {
@@ -56,7 +85,7 @@ pub fn bond_assets(
     let compound = config.compound_proxy.compound_msg(assets, funds, no_swap, slippage_tolerance)?;
     messages.push(compound);
 
-    let prev_balance = query_token_balance(&deps.querier, staking_token, &env.contract.address)?;
+    let prev_balance = staking_token.query_pool(&deps.querier, &env.contract.address)?;
     messages.push(
         CallbackMsg::BondTo {
             to: info.sender,
@@ -71,6 +100,57 @@ pub fn bond_assets(
         .add_attribute("action", "bond_assets"))
 }
 
+fn has_duplicated_asset(assets: &[Asset]) -> bool {
+    let mut low_asset = match assets.first() {
+        Some(asset) => asset,
+        None => return false,
+    };
+    for asset in assets[1..].iter() {
+        if asset.eq(low_asset) {
+            return true;
+        }
+        low_asset = asset;
+    }
+    false
+}
+
+/// ## Description
+/// Simulates the LP amount from the compound proxy and the bond share it would receive.
+pub fn query_simulate_bond_assets(
+    deps: Deps,
+    env: Env,
+    assets: Vec<Asset>,
+    no_swap: Option<bool>,
+    slippage_tolerance: Option<Decimal>,
+) -> StdResult<SimulateBondAssetsResponse> {
+    if assets.is_empty() {
+        return Err(StdError::generic_err("assets must not be empty"));
+    }
+    if has_duplicated_asset(&assets) {
+        return Err(StdError::generic_err("Duplicated asset"));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let slippage_tolerance = config.resolve_slippage(slippage_tolerance)?;
+
+    let simulation = config.compound_proxy
+        .query_compound_simulation(&deps.querier, assets, no_swap, slippage_tolerance)?;
+    let lp_amount = simulation.lp_amount;
+    let lp_balance = config.staking_contract.query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+    let bond_share = state.calc_bond_share(lp_amount, lp_balance, ScalingOperation::Truncate);
+
+    Ok(SimulateBondAssetsResponse {
+        lp_amount,
+        bond_share,
+        minimum_receive: simulation.minimum_receive,
+    })
+}
+
 /// ## Description
 /// Bond available LP token on the contract on behalf of the user.
 pub fn bond_to(
@@ -83,7 +163,7 @@ pub fn bond_to(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let balance = query_token_balance(&deps.querier, &config.liquidity_token, &env.contract.address)?;
+    let balance = config.liquidity_token_info().query_pool(&deps.querier, &env.contract.address)?;
     let amount = balance - prev_balance;
 
     if let Some(minimum_receive) = minimum_receive {
@@ -101,24 +181,26 @@ pub fn bond_to(
         config,
         to,
         amount,
+        None,
     )
 }
 
 /// ## Description
-/// Bond received LP token on behalf of the user.
+/// Bond received LP token on behalf of the user, optionally locking the position until `lock_until`.
 pub fn bond(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     sender_addr: String,
     amount: Uint128,
+    lock_until: Option<u64>,
 ) -> Result<Response, ContractError> {
     let staker_addr = deps.api.addr_validate(&sender_addr)?;
 
     let config = CONFIG.load(deps.storage)?;
 
     // only staking token contract can execute this message
-    if config.liquidity_token != info.sender {
+    if config.native_lp || config.liquidity_token != info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -128,9 +210,151 @@ pub fn bond(
         config,
         staker_addr,
         amount,
+        lock_until,
     )
 }
 
+/// ## Description
+/// Bond native LP token attached to the message on behalf of the user.
+pub fn bond_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: String,
+) -> Result<Response, ContractError> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the configured LP denom can be bonded
+    if !config.native_lp {
+        return Err(ContractError::InvalidMessage {});
+    }
+    let amount = match info.funds.iter().find(|coin| coin.denom == config.liquidity_token.as_str()) {
+        Some(coin) => coin.amount,
+        None => return Err(ContractError::InvalidMessage {}),
+    };
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    // other denoms attached by mistake are sent back to the sender
+    let refunds: Vec<Coin> = info.funds.into_iter()
+        .filter(|coin| coin.denom != config.liquidity_token.as_str() && !coin.amount.is_zero())
+        .collect();
+
+    let mut response = bond_internal(
+        deps,
+        env,
+        config,
+        staker_addr,
+        amount,
+        None,
+    )?;
+    if !refunds.is_empty() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: refunds,
+        });
+    }
+
+    Ok(response)
+}
+
+/// ## Description
+/// Bond received LP token on behalf of many stakers, split by the amounts in `entries`.
+pub fn batch_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entries: Vec<(String, Uint128)>,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only staking token contract can execute this message
+    if config.native_lp || config.liquidity_token != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    batch_bond_internal(deps, env, config, entries, amount)
+}
+
+/// ## Description
+/// Bond native LP token attached to the message on behalf of many stakers, split by the amounts in `entries`.
+pub fn batch_bond_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entries: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the configured LP denom can be bonded
+    if !config.native_lp {
+        return Err(ContractError::InvalidMessage {});
+    }
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == config.liquidity_token.as_str() => coin.amount,
+        _ => return Err(ContractError::InvalidMessage {}),
+    };
+
+    batch_bond_internal(deps, env, config, entries, amount)
+}
+
+/// Internal batch bond function, validates the entries add up to the received amount
+fn batch_bond_internal(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    entries: Vec<(String, Uint128)>,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut stakers: Vec<(Addr, Uint128)> = vec![];
+    let mut total_amount = Uint128::zero();
+    for (staker_addr, staker_amount) in entries {
+        let staker_addr = deps.api.addr_validate(&staker_addr)?;
+        if staker_amount.is_zero() {
+            return Err(ContractError::InvalidZeroAmount {});
+        }
+        if stakers.iter().any(|(addr, _)| addr == &staker_addr) {
+            return Err(ContractError::DuplicatedStaker { staker_addr: staker_addr.to_string() });
+        }
+        total_amount = total_amount.checked_add(staker_amount)?;
+        stakers.push((staker_addr, staker_amount));
+    }
+    if total_amount != amount {
+        return Err(ContractError::BatchAmountMismatch { total_amount, amount });
+    }
+
+    let mut lp_balance = config.staking_contract.query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+    let pool_info = config.pair.query_pool_info(&deps.querier)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // later stakers' shares are priced with the LP of earlier ones included
+    let staker_count = stakers.len();
+    for (staker_addr, staker_amount) in stakers {
+        bond_share_internal(deps.storage, &env, &mut state, &pool_info, lp_balance, &staker_addr, staker_amount, None)?;
+        lp_balance += staker_amount;
+    }
+
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_message(config.deposit_msg(amount)?).add_attributes(vec![
+        attr("action", "batch_bond"),
+        attr("amount", amount),
+        attr("staker_count", staker_count.to_string()),
+    ]))
+}
+
 /// Internal bond function used by bond and bond_to
 fn bond_internal(
     deps: DepsMut,
@@ -138,6 +362,7 @@ fn bond_internal(
     config: Config,
     staker_addr: Addr,
     amount: Uint128,
+    lock_until: Option<u64>,
 ) -> Result<Response, ContractError>{
 
     let lp_balance = config.staking_contract.query_deposit(
@@ -150,9 +375,35 @@ fn bond_internal(
 
     let mut state = STATE.load(deps.storage)?;
 
+    let pool_info = config.pair.query_pool_info(&deps.querier)?;
+    bond_share_internal(deps.storage, &env, &mut state, &pool_info, lp_balance, &staker_addr, amount, lock_until)?;
+
+    STATE.save(deps.storage, &state)?;
+
+    messages.push(config.deposit_msg(amount)?);
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "bond"),
+        attr("amount", amount),
+        attr("bond_amount", amount),
+    ]))
+}
+
+/// Converts the LP amount to bond share at the given LP balance and adds it to the staker
+#[allow(clippy::too_many_arguments)]
+fn bond_share_internal(
+    storage: &mut dyn Storage,
+    env: &Env,
+    state: &mut State,
+    pool_info: &PoolResponse,
+    lp_balance: Uint128,
+    staker_addr: &Addr,
+    amount: Uint128,
+    lock_until: Option<u64>,
+) -> StdResult<()> {
+
     // withdraw reward to pending reward; before changing share
     let mut reward_info = REWARD
-        .may_load(deps.storage, &staker_addr)?
+        .may_load(storage, staker_addr)?
         .unwrap_or_default();
 
     // convert amount to share & update
@@ -164,19 +415,19 @@ fn bond_internal(
         bond_share,
     );
 
-    let pool_info = config.pair.query_pool_info(&deps.querier)?;
-    reward_info.ensure_deposit_costs(deps.storage)?;
-    reward_info.bond(bond_share, deposit_amount, env.block.time.seconds(), &pool_info)?;
+    reward_info.ensure_deposit_costs(storage)?;
+    reward_info.bond(bond_share, deposit_amount, env.block.time.seconds(), pool_info)?;
+    reward_info.lock(lock_until);
 
-    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
-    STATE.save(deps.storage, &state)?;
+    REWARD.save(storage, staker_addr, &reward_info)
+}
 
-    messages.push(config.staking_contract.deposit_msg(config.liquidity_token.to_string(), amount)?);
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "bond"),
-        attr("amount", amount),
-        attr("bond_amount", amount),
-    ]))
+/// Returns an error if the position is still locked
+pub(crate) fn assert_unlocked(reward_info: &RewardInfo, time: u64) -> Result<(), ContractError> {
+    match reward_info.lock_until {
+        Some(lock_until) if time < lock_until => Err(ContractError::PositionLocked { lock_until }),
+        _ => Ok(()),
+    }
 }
 
 /// ## Description
@@ -187,6 +438,29 @@ pub fn unbond(
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    unbond_internal(deps, env, info, amount, true)
+}
+
+/// ## Description
+/// Unbonds LP token directly from the generator, bypassing deposit time penalty.
+/// Only allowed while emergency mode is enabled by the owner.
+pub fn emergency_unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    unbond_internal(deps, env, info, amount, false)
+}
+
+/// Internal unbond function used by unbond and emergency_unbond
+fn unbond_internal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    apply_penalty: bool,
+) -> Result<Response, ContractError> {
 
     if amount.is_zero() {
         return Err(ContractError::InvalidZeroAmount {});
@@ -195,7 +469,11 @@ pub fn unbond(
     let staker_addr = info.sender;
 
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
+    if !apply_penalty && !config.emergency {
+        return Err(ContractError::NotEmergency {});
+    }
+
+    let staking_token = config.liquidity_token.clone();
 
     let lp_balance = config.staking_contract.query_deposit(
         &deps.querier,
@@ -205,12 +483,19 @@ pub fn unbond(
 
     let mut state = STATE.load(deps.storage)?;
     let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
-
-    let user_balance = reward_info.calc_user_balance(
-        &state,
-        lp_balance,
-        env.block.time.seconds(),
-    );
+    assert_unlocked(&reward_info, env.block.time.seconds())?;
+
+    let user_balance = if apply_penalty {
+        reward_info.calc_user_balance(
+            &state,
+            lp_balance,
+            env.block.time.seconds(),
+            config.deposit_penalty_period,
+        )
+    } else {
+        // full share value, without deposit time penalty
+        state.calc_bond_amount(lp_balance, reward_info.bond_share)
+    };
 
     if user_balance < amount {
         return Err(ContractError::UnbondExceedBalance {});
@@ -227,10 +512,10 @@ pub fn unbond(
     Ok(Response::new()
         .add_messages(vec![
             config.staking_contract.withdraw_msg(staking_token.to_string(), amount)?,
-            token_asset(staking_token, amount).transfer_msg(&staker_addr)?,
+            config.liquidity_token_info().with_balance(amount).transfer_msg(&staker_addr)?,
         ])
         .add_attributes(vec![
-            attr("action", "unbond"),
+            attr("action", if apply_penalty { "unbond" } else { "emergency_unbond" }),
             attr("staker_addr", staker_addr),
             attr("amount", amount),
         ]))
@@ -252,6 +537,36 @@ pub fn query_reward_info(
     })
 }
 
+/// ## Description
+/// Returns the total bond share and the LP token amount staked in the generator.
+pub fn query_pool_info(deps: Deps, env: Env) -> StdResult<PoolInfoResponse> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let total_bond_amount = config.staking_contract.query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+
+    Ok(PoolInfoResponse {
+        total_bond_share: state.total_bond_share,
+        total_bond_amount,
+    })
+}
+
+/// ## Description
+/// Returns the LP token amount staked per bond share, the ratio used when bonding and unbonding.
+/// Returns one when no share is issued.
+pub fn query_exchange_rate(deps: Deps, env: Env) -> StdResult<Decimal> {
+    let pool_info = query_pool_info(deps, env)?;
+    if pool_info.total_bond_share.is_zero() {
+        return Ok(Decimal::one());
+    }
+
+    Ok(Decimal::from_ratio(pool_info.total_bond_amount, pool_info.total_bond_share))
+}
+
 /// Loads reward info from the storage
 fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<RewardInfoResponseItem> {
     let mut reward_info = REWARD
@@ -271,13 +586,18 @@ fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<Rewar
         &state,
         lp_balance,
         env.block.time.seconds(),
+        config.deposit_penalty_period,
     );
+    let unvested_bond_amount = state.calc_bond_amount(lp_balance, reward_info.bond_share)
+        .saturating_sub(bond_amount);
     let total_share = reward_info.bond_share + reward_info.transfer_share;
     reward_info.ensure_deposit_costs(deps.storage)?;
     Ok(RewardInfoResponseItem {
         staking_token: staking_token.to_string(),
         bond_share: reward_info.bond_share,
         bond_amount,
+        vested_bond_amount: bond_amount,
+        unvested_bond_amount,
         deposit_amount: if total_share.is_zero() {
             Uint128::zero()
         } else {
@@ -291,6 +611,7 @@ fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<Rewar
             reward_info.deposit_costs.iter()
                 .map(|it| it.multiply_ratio(reward_info.bond_share, total_share))
                 .collect()
-        }
+        },
+        lock_until: reward_info.lock_until,
     })
 }
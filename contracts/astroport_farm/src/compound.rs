@@ -1,29 +1,67 @@
 use astroport::{
     asset::{Asset},
 };
-use cosmwasm_std::{attr, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128, Decimal};
+use cosmwasm_std::{attr, Addr, Attribute, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, Decimal};
 
 use crate::{
     error::ContractError,
-    state::CONFIG,
+    state::{CompoundRecord, Config, COMPOUND_HISTORY, CONFIG, DAY, DEFERRED_REWARDS, FEE_STATS, HELD_REWARDS},
 };
 
 use cw20::{Expiration};
 use astroport::asset::{AssetInfo, AssetInfoExt, token_asset};
 
-use astroport::querier::query_token_balance;
 use spectrum::adapters::asset::AssetEx;
 
-use spectrum::astroport_farm::CallbackMsg;
+use spectrum::astroport_farm::{CallbackMsg, PerformanceStatsResponse};
+
+const YEAR: u64 = 365 * DAY;
+
+/// ## Description
+/// Returns the pending rewards of the farm to be compounded, sorted by asset.
+pub fn query_pending_rewards(deps: Deps, env: Env) -> StdResult<Vec<Asset>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let pending_token = config.staking_contract.query_pending_token(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+
+    let mut rewards: Vec<Asset> = vec![
+        token_asset(config.base_reward_token, pending_token.pending),
+    ];
+    if let Some(pending_on_proxy) = pending_token.pending_on_proxy {
+        rewards.extend(pending_on_proxy);
+    }
+    rewards.retain(|it| !it.amount.is_zero());
+    rewards.sort_by_key(|it| it.info.to_string());
+
+    Ok(rewards)
+}
 
 /// ## Description
-/// Performs compound by sending LP rewards to compound proxy and reinvest received LP token
+/// Caps the slippage tolerance at the configured protection, which is used when no tolerance is given.
+fn apply_slippage_protection(slippage_tolerance: Option<Decimal>, min_slippage_protection: Decimal) -> Option<Decimal> {
+    if min_slippage_protection.is_zero() {
+        return slippage_tolerance;
+    }
+    Some(slippage_tolerance.map_or(min_slippage_protection, |it| it.min(min_slippage_protection)))
+}
+
+/// ## Description
+/// Performs compound by sending LP rewards to compound proxy and reinvest received LP token.
+/// When `skip_provide` is set, rewards are claimed and fee is taken, the rest is held for a later compound.
+/// When `reward_assets` is set, only those tokens are compounded, the rest are deferred without fee.
+#[allow(clippy::too_many_arguments)]
 pub fn compound(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     minimum_receive: Option<Uint128>,
     slippage_tolerance: Option<Decimal>,
+    skip_provide: bool,
+    reward_assets: Option<Vec<AssetInfo>>,
 ) -> Result<Response, ContractError> {
 
     let config = CONFIG.load(deps.storage)?;
@@ -33,7 +71,62 @@ pub fn compound(
         return Err(ContractError::Unauthorized {});
     }
 
-    let staking_token = config.liquidity_token;
+    compound_internal(
+        deps,
+        env,
+        config,
+        minimum_receive,
+        slippage_tolerance,
+        skip_provide,
+        reward_assets,
+        None,
+    )
+}
+
+/// ## Description
+/// Performs compound on behalf of anyone, the bounty fraction of the rewards is sent to the caller.
+pub fn public_compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // public compound is enabled by setting the bounty
+    if config.bounty.is_zero() {
+        return Err(StdError::generic_err("public compound is disabled").into());
+    }
+
+    let bounty = Some((info.sender, config.bounty));
+    compound_internal(
+        deps,
+        env,
+        config,
+        None,
+        None,
+        false,
+        None,
+        bounty,
+    )
+}
+
+/// Internal compound function, pays the bounty fraction of the rewards to the recipient when set
+#[allow(clippy::too_many_arguments)]
+fn compound_internal(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+    skip_provide: bool,
+    reward_assets: Option<Vec<AssetInfo>>,
+    bounty: Option<(Addr, Decimal)>,
+) -> Result<Response, ContractError> {
+
+    let slippage_tolerance = config.resolve_slippage(slippage_tolerance)?;
+    let slippage_tolerance = apply_slippage_protection(slippage_tolerance, config.min_slippage_protection);
+    let staking_token = config.liquidity_token.clone();
 
     let pending_token = config.staking_contract.query_pending_token(
         &deps.querier,
@@ -55,28 +148,89 @@ pub fn compound(
     let mut rewards: Vec<Asset> = vec![];
     let mut compound_rewards: Vec<Asset> = vec![];
 
-    let claim_rewards = config.staking_contract.claim_rewards_msg(
-        vec![staking_token.to_string()],
-    )?;
-    messages.push(claim_rewards);
+    // skip the claim when the generator has nothing pending for this lp token
+    let has_pending = !pending_token.pending.is_zero()
+        || pending_token.pending_on_proxy.iter().flatten().any(|it| !it.amount.is_zero());
+    if has_pending {
+        let claim_rewards = config.staking_contract.claim_rewards_msg(
+            vec![staking_token.to_string()],
+        )?;
+        messages.push(claim_rewards);
+    }
 
     rewards.push(
-        token_asset(config.base_reward_token, pending_token.pending),
+        token_asset(config.base_reward_token.clone(), pending_token.pending),
     );
     if let Some(pending_on_proxy) = pending_token.pending_on_proxy {
         rewards.extend(pending_on_proxy);
     }
 
+    // rewards deferred from previous calls are charged along with pending rewards
+    let deferred_rewards = DEFERRED_REWARDS.may_load(deps.storage)?.unwrap_or_default();
+    for deferred in deferred_rewards {
+        match rewards.iter_mut().find(|it| it.info == deferred.info) {
+            Some(reward) => reward.amount += deferred.amount,
+            None => rewards.push(deferred),
+        }
+    }
+
+    // rewards held from previous calls are compounded along with pending rewards
+    let held_rewards = HELD_REWARDS.may_load(deps.storage)?.unwrap_or_default();
+    for held in held_rewards.iter() {
+        if !rewards.iter().any(|it| it.info == held.info) {
+            rewards.push(held.info.with_balance(Uint128::zero()));
+        }
+    }
+
+    // sort by asset to keep messages order deterministic
+    rewards.sort_by_key(|it| it.info.to_string());
+
     let mut compound_funds: Vec<Coin> = vec![];
+    let mut next_held_rewards: Vec<Asset> = vec![];
+    let mut next_deferred_rewards: Vec<Asset> = vec![];
+    let mut fee_amounts: Vec<Asset> = vec![];
     for asset in rewards {
         let reward_amount = asset.amount;
-        if !reward_amount.is_zero() && !lp_balance.is_zero() {
+        let held_amount = held_rewards.iter()
+            .find(|it| it.info == asset.info)
+            .map(|it| it.amount)
+            .unwrap_or_default();
+        let is_selected = reward_assets.as_ref()
+            .map(|it| it.contains(&asset.info))
+            .unwrap_or(true);
+        if !is_selected {
+            if !reward_amount.is_zero() {
+                next_deferred_rewards.push(asset.clone());
+            }
+            if !held_amount.is_zero() {
+                next_held_rewards.push(asset.info.with_balance(held_amount));
+            }
+            continue;
+        }
+        // nothing is bonded to compound into, rewards are kept for the next call
+        if lp_balance.is_zero() {
+            if !reward_amount.is_zero() {
+                next_deferred_rewards.push(asset.clone());
+            }
+            if !held_amount.is_zero() {
+                next_held_rewards.push(asset.info.with_balance(held_amount));
+            }
+            continue;
+        }
+        if !reward_amount.is_zero() || !held_amount.is_zero() {
             let commission_amount = reward_amount * total_fee;
-            let compound_amount = reward_amount.checked_sub(commission_amount)?;
-            if !compound_amount.is_zero() {
+            let bounty_amount = bounty.as_ref()
+                .map(|(_, bounty)| reward_amount * *bounty)
+                .unwrap_or_default();
+            let compound_amount = reward_amount.checked_sub(commission_amount)?.checked_sub(bounty_amount)? + held_amount;
+            if skip_provide {
+                if !compound_amount.is_zero() {
+                    next_held_rewards.push(asset.info.with_balance(compound_amount));
+                }
+            } else if !compound_amount.is_zero() {
                 let compound_asset = asset.info.with_balance(compound_amount);
                 if let AssetInfo::NativeToken { denom } = &asset.info {
-                    compound_funds.push(Coin { denom: denom.clone(), amount: asset.amount });
+                    compound_funds.push(Coin { denom: denom.clone(), amount: compound_amount });
                 } else {
                     let increase_allowance = compound_asset.increase_allowance_msg(
                         config.compound_proxy.0.to_string(),
@@ -91,19 +245,64 @@ pub fn compound(
                 let commission_asset = asset.info.with_balance(commission_amount);
                 let transfer_fee = commission_asset.transfer_msg(&config.fee_collector)?;
                 messages.push(transfer_fee);
+                fee_amounts.push(commission_asset);
+            }
+
+            match &bounty {
+                Some((bounty_recipient, _)) if !bounty_amount.is_zero() => {
+                    let bounty_asset = asset.info.with_balance(bounty_amount);
+                    messages.push(bounty_asset.transfer_msg(bounty_recipient)?);
+                }
+                _ => {}
             }
 
             attributes.push(attr("token", asset.info.to_string()));
-            attributes.push(attr("compound_amount", compound_amount));
+            if skip_provide {
+                attributes.push(attr("held_amount", compound_amount));
+            } else {
+                attributes.push(attr("compound_amount", compound_amount));
+            }
             attributes.push(attr("commission_amount", commission_amount));
+            if bounty.is_some() {
+                attributes.push(attr("bounty_amount", bounty_amount));
+            }
+        }
+    }
+
+    // dust rewards are left pending, so the compound costs no claim, swap or fee
+    if !compound_rewards.is_empty() && !config.min_compound_value.is_zero() {
+        let lp_amount = config.compound_proxy
+            .query_compound_simulation(&deps.querier, compound_rewards.clone(), None, None)?
+            .lp_amount;
+        if lp_amount < config.min_compound_value {
+            return Ok(Response::new()
+                .add_attribute("action", "compound_skipped")
+                .add_attribute("lp_amount", lp_amount));
         }
     }
 
+    for fee in fee_amounts {
+        FEE_STATS.update(deps.storage, &fee.info.to_string(), |it| -> StdResult<_> {
+            Ok(it.unwrap_or_default() + fee.amount)
+        })?;
+    }
+
+    if next_held_rewards.is_empty() {
+        HELD_REWARDS.remove(deps.storage);
+    } else {
+        HELD_REWARDS.save(deps.storage, &next_held_rewards)?;
+    }
+    if next_deferred_rewards.is_empty() {
+        DEFERRED_REWARDS.remove(deps.storage);
+    } else {
+        DEFERRED_REWARDS.save(deps.storage, &next_deferred_rewards)?;
+    }
+
     if !compound_rewards.is_empty() {
         let compound = config.compound_proxy.compound_msg(compound_rewards, compound_funds, None, slippage_tolerance)?;
         messages.push(compound);
 
-        let prev_balance = query_token_balance(&deps.querier, staking_token, &env.contract.address)?;
+        let prev_balance = config.liquidity_token_info().query_pool(&deps.querier, &env.contract.address)?;
         messages.push(
             CallbackMsg::Stake {
                 prev_balance,
@@ -130,9 +329,9 @@ pub fn stake(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let staking_token = config.liquidity_token;
+    let staking_token = config.liquidity_token.clone();
 
-    let balance = query_token_balance(&deps.querier, &staking_token, &env.contract.address)?;
+    let balance = config.liquidity_token_info().query_pool(&deps.querier, &env.contract.address)?;
     let amount = balance - prev_balance;
 
     if let Some(minimum_receive) = minimum_receive {
@@ -144,9 +343,18 @@ pub fn stake(
         }
     }
 
+    // record compound, keep the last one before the day window as the window start
+    let time = env.block.time.seconds();
+    let mut history = COMPOUND_HISTORY.may_load(deps.storage)?.unwrap_or_default();
+    history.push(CompoundRecord { time, amount });
+    while history.len() > 2 && history[1].time + DAY <= time {
+        history.remove(0);
+    }
+    COMPOUND_HISTORY.save(deps.storage, &history)?;
+
     Ok(Response::new()
         .add_message(
-            config.staking_contract.deposit_msg(staking_token.to_string(), amount)?
+            config.deposit_msg(amount)?
         )
         .add_attributes(vec![
             attr("action", "stake"),
@@ -154,3 +362,47 @@ pub fn stake(
             attr("amount", amount),
         ]))
 }
+
+/// ## Description
+/// Returns the cumulative fee sent to the fee collector by reward asset, sorted by asset.
+pub fn query_fee_stats(deps: Deps) -> StdResult<Vec<(String, Uint128)>> {
+    FEE_STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// ## Description
+/// Returns the realized yield from recent compounds.
+/// The rate is zero when there are less than 2 compounds in history.
+pub fn query_performance_stats(deps: Deps, env: Env) -> StdResult<PerformanceStatsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let history = COMPOUND_HISTORY.may_load(deps.storage)?.unwrap_or_default();
+
+    let total_bond_amount = config.staking_contract.query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+
+    let time = env.block.time.seconds();
+    let compounded_amount_24h = history.iter()
+        .filter(|it| it.time + DAY > time)
+        .map(|it| it.amount)
+        .sum();
+
+    let apr = match (history.first(), history.last()) {
+        (Some(first), Some(last)) if last.time > first.time && !total_bond_amount.is_zero() => {
+            let window_amount: Uint128 = history[1..].iter().map(|it| it.amount).sum();
+            Decimal::from_ratio(window_amount, total_bond_amount)
+                * Decimal::from_ratio(YEAR, last.time - first.time)
+        },
+        _ => Decimal::zero(),
+    };
+
+    Ok(PerformanceStatsResponse {
+        last_compound_time: history.last().map(|it| it.time).unwrap_or_default(),
+        compounded_amount_24h,
+        total_bond_amount,
+        apr,
+    })
+}
@@ -1,11 +1,11 @@
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    attr, entry_point, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
     Response, StdError, StdResult, Uint128,
 };
 
 use crate::{
-    bond::{bond, bond_assets, bond_to},
-    compound::{compound, stake},
+    bond::{batch_bond, batch_bond_native, bond, bond_assets, bond_native, bond_to},
+    compound::{compound, public_compound, query_fee_stats, query_pending_rewards, query_performance_stats, stake},
     error::ContractError,
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner},
     state::{Config, State, CONFIG, OWNERSHIP_PROPOSAL},
@@ -15,14 +15,17 @@ use cw20::{Cw20ReceiveMsg, MarketingInfoResponse, MinterResponse};
 use spectrum::adapters::generator::Generator;
 use spectrum::adapters::pair::Pair;
 
-use crate::bond::{query_reward_info, unbond};
-use crate::state::{STATE};
+use crate::bond::{emergency_unbond, query_exchange_rate, query_pool_info, query_reward_info, query_simulate_bond_assets, unbond};
+use crate::state::{default_max_bounty, DAY, STATE};
 use spectrum::astroport_farm::{
     CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
 };
 use spectrum::compound_proxy::Compounder;
 use crate::cw20::{execute_burn, execute_burn_from, execute_decrease_allowance, execute_increase_allowance, execute_send, execute_send_from, execute_transfer, execute_transfer_from, query_all_accounts, query_all_allowances, query_allowance, query_balance, query_token_info};
 
+/// Contract version that is stored in config
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// ## Description
 /// Validates that decimal value is in the range 0 to 1
 fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
@@ -55,11 +58,25 @@ pub fn instantiate(
             controller: deps.api.addr_validate(&msg.controller)?,
             fee: msg.fee,
             fee_collector: deps.api.addr_validate(&msg.fee_collector)?,
-            liquidity_token: deps.api.addr_validate(&msg.liquidity_token)?,
+            liquidity_token: if msg.native_lp {
+                Addr::unchecked(msg.liquidity_token)
+            } else {
+                deps.api.addr_validate(&msg.liquidity_token)?
+            },
             base_reward_token: deps.api.addr_validate(&msg.base_reward_token)?,
             pair: Pair(deps.api.addr_validate(&msg.pair)?),
             name: msg.name,
             symbol: msg.symbol,
+            version: CONTRACT_VERSION.to_string(),
+            min_slippage_protection: Decimal::zero(),
+            deposit_penalty_period: DAY,
+            min_compound_value: Uint128::zero(),
+            native_lp: msg.native_lp,
+            bounty: Decimal::zero(),
+            default_slippage: Decimal::zero(),
+            max_slippage: Decimal::one(),
+            emergency: false,
+            max_bounty: default_max_bounty(),
         },
     )?;
 
@@ -89,8 +106,22 @@ pub fn execute(
             controller,
             fee,
             fee_collector,
-        } => update_config(deps, info, compound_proxy, controller, fee, fee_collector),
+            min_slippage_protection,
+            deposit_penalty_period,
+            min_compound_value,
+            bounty,
+            default_slippage,
+            max_slippage,
+            emergency,
+            max_bounty,
+        } => update_config(deps, info, compound_proxy, controller, fee, fee_collector, min_slippage_protection, deposit_penalty_period, min_compound_value, bounty, default_slippage, max_slippage, emergency, max_bounty),
+        ExecuteMsg::BondNative { staker_addr } => {
+            let staker_addr = staker_addr.unwrap_or_else(|| info.sender.to_string());
+            bond_native(deps, env, info, staker_addr)
+        }
+        ExecuteMsg::BatchBond { entries } => batch_bond_native(deps, env, info, entries),
         ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+        ExecuteMsg::EmergencyUnbond { amount } => emergency_unbond(deps, env, info, amount),
         ExecuteMsg::BondAssets {
             assets,
             minimum_receive,
@@ -108,7 +139,18 @@ pub fn execute(
         ExecuteMsg::Compound {
             minimum_receive,
             slippage_tolerance,
-        } => compound(deps, env, info, minimum_receive, slippage_tolerance),
+            skip_provide,
+            reward_assets,
+        } => compound(
+            deps,
+            env,
+            info,
+            minimum_receive,
+            slippage_tolerance,
+            skip_provide.unwrap_or(false),
+            reward_assets,
+        ),
+        ExecuteMsg::PublicCompound {} => public_compound(deps, env, info),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -168,11 +210,26 @@ fn receive_cw20(
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond { staker_addr }) => bond(
+        Ok(Cw20HookMsg::Bond { staker_addr, lock_until }) => {
+            // only the staker can lock its own position
+            let staker_addr = staker_addr.unwrap_or_else(|| cw20_msg.sender.clone());
+            if lock_until.is_some() && staker_addr != cw20_msg.sender {
+                return Err(ContractError::Unauthorized {});
+            }
+            bond(
+                deps,
+                env,
+                info,
+                staker_addr,
+                cw20_msg.amount,
+                lock_until,
+            )
+        },
+        Ok(Cw20HookMsg::BatchBond { entries }) => batch_bond(
             deps,
             env,
             info,
-            staker_addr.unwrap_or(cw20_msg.sender),
+            entries,
             cw20_msg.amount,
         ),
         Err(_) => Err(ContractError::InvalidMessage {}),
@@ -189,6 +246,14 @@ pub fn update_config(
     controller: Option<String>,
     fee: Option<Decimal>,
     fee_collector: Option<String>,
+    min_slippage_protection: Option<Decimal>,
+    deposit_penalty_period: Option<u64>,
+    min_compound_value: Option<Uint128>,
+    bounty: Option<Decimal>,
+    default_slippage: Option<Decimal>,
+    max_slippage: Option<Decimal>,
+    emergency: Option<bool>,
+    max_bounty: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -213,6 +278,54 @@ pub fn update_config(
         config.fee_collector = deps.api.addr_validate(&fee_collector)?;
     }
 
+    if let Some(min_slippage_protection) = min_slippage_protection {
+        validate_percentage(min_slippage_protection, "min_slippage_protection")?;
+        config.min_slippage_protection = min_slippage_protection;
+    }
+
+    if let Some(deposit_penalty_period) = deposit_penalty_period {
+        config.deposit_penalty_period = deposit_penalty_period;
+    }
+
+    if let Some(min_compound_value) = min_compound_value {
+        config.min_compound_value = min_compound_value;
+    }
+
+    if let Some(max_bounty) = max_bounty {
+        validate_percentage(max_bounty, "max_bounty")?;
+        config.max_bounty = max_bounty;
+    }
+
+    if let Some(bounty) = bounty {
+        config.bounty = bounty;
+    }
+
+    if config.bounty > config.max_bounty {
+        return Err(StdError::generic_err("bounty must not exceed max_bounty").into());
+    }
+
+    if config.fee + config.bounty > Decimal::one() {
+        return Err(StdError::generic_err("fee and bounty must not exceed 1").into());
+    }
+
+    if let Some(default_slippage) = default_slippage {
+        validate_percentage(default_slippage, "default_slippage")?;
+        config.default_slippage = default_slippage;
+    }
+
+    if let Some(max_slippage) = max_slippage {
+        validate_percentage(max_slippage, "max_slippage")?;
+        config.max_slippage = max_slippage;
+    }
+
+    if config.default_slippage > config.max_slippage {
+        return Err(StdError::generic_err("default_slippage must not exceed max_slippage").into());
+    }
+
+    if let Some(emergency) = emergency {
+        config.emergency = emergency;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
@@ -228,7 +341,10 @@ pub fn handle_callback(
 ) -> Result<Response, ContractError> {
     // Callback functions can only be called by this contract itself
     if info.sender != env.contract.address {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::CallbackUnauthorized {
+            contract_addr: env.contract.address.to_string(),
+            sender: info.sender.to_string(),
+        });
     }
     match msg {
         CallbackMsg::Stake {
@@ -253,6 +369,15 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_reward_info(deps, env, staker_addr)?)
         }
         QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::PendingRewards {} => to_binary(&query_pending_rewards(deps, env)?),
+        QueryMsg::PerformanceStats {} => to_binary(&query_performance_stats(deps, env)?),
+        QueryMsg::FeeStats {} => to_binary(&query_fee_stats(deps)?),
+        QueryMsg::PoolInfo {} => to_binary(&query_pool_info(deps, env)?),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::SimulateBondAssets { assets, no_swap, slippage_tolerance } => {
+            to_binary(&query_simulate_bond_assets(deps, env, assets, no_swap, slippage_tolerance)?)
+        }
+        QueryMsg::Version {} => to_binary(&query_config(deps)?.version),
 
         // cw20
         QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
@@ -281,8 +406,17 @@ fn query_state(deps: Deps) -> StdResult<State> {
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Used for contract migration. Bumps the version stored in config.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let prev_version = config.version;
+    config.version = CONTRACT_VERSION.to_string();
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("prev_version", prev_version),
+        attr("version", CONTRACT_VERSION),
+    ]))
 }
\ No newline at end of file
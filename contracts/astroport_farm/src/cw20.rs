@@ -48,7 +48,8 @@ fn transfer_internal(
         receiver.bond_share += share;
         receiver.transfer_share -= share;
     }
-
+    // the transferred share stays locked, the receiver keeps the later lock
+    receiver.lock(sender.lock_until);
     REWARD.save(deps.storage, sender_addr, &sender)?;
     REWARD.save(deps.storage, &rcpt_addr, &receiver)?;
 
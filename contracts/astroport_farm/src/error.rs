@@ -11,6 +11,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Unauthorized; callbacks can only be invoked by {contract_addr}, sender: {sender}")]
+    CallbackUnauthorized { contract_addr: String, sender: String },
+
     #[error("Invalid message")]
     InvalidMessage {},
 
@@ -34,6 +37,21 @@ pub enum ContractError {
 
     #[error("Duplicated asset")]
     DuplicatedAsset {},
+
+    #[error("Native token amount mismatch for {denom}; declared amount: {declared}, sent amount: {sent}")]
+    NativeAmountMismatch { denom: String, declared: Uint128, sent: Uint128 },
+
+    #[error("Emergency unbond is not enabled")]
+    NotEmergency {},
+
+    #[error("Position is locked until {lock_until}")]
+    PositionLocked { lock_until: u64 },
+
+    #[error("Duplicated staker {staker_addr}")]
+    DuplicatedStaker { staker_addr: String },
+
+    #[error("Batch amount mismatch; total of entries: {total_amount}, received amount: {amount}")]
+    BatchAmountMismatch { total_amount: Uint128, amount: Uint128 },
 }
 
 impl From<OverflowError> for ContractError {
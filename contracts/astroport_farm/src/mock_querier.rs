@@ -1,12 +1,13 @@
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::asset::{native_asset, token_asset};
-use astroport::generator::{PendingTokenResponse};
+use astroport::asset::{native_asset, token_asset, Asset};
+use astroport::generator::{PendingTokenResponse, RewardInfoResponse};
 use astroport::pair::PoolResponse;
+use spectrum::compound_proxy::CompoundSimulationResponse;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -21,6 +22,7 @@ pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
+const LP_TOKEN: &str = "lp_token";
 
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
@@ -104,6 +106,24 @@ impl WasmMockQuerier {
                     ]),
                 })
             },
+            MockQueryMsg::RewardInfo { .. } => {
+                to_binary(&RewardInfoResponse {
+                    base_reward_token: Addr::unchecked(ASTRO_TOKEN),
+                    proxy_reward_token: Some(Addr::unchecked(REWARD_TOKEN)),
+                })
+            },
+            MockQueryMsg::CompoundSimulation { slippage_tolerance, .. } => {
+                let lp_amount = self.get_balance(contract_addr.clone(), LP_TOKEN.to_string());
+                let slippage_tolerance = slippage_tolerance.unwrap_or_default();
+                to_binary(&CompoundSimulationResponse {
+                    lp_amount,
+                    swap_asset_a_amount: Uint128::zero(),
+                    swap_asset_b_amount: Uint128::zero(),
+                    return_a_amount: Uint128::zero(),
+                    return_b_amount: Uint128::zero(),
+                    minimum_receive: lp_amount * (Decimal::one() - slippage_tolerance),
+                })
+            },
             MockQueryMsg::Pool {} => {
                 to_binary(&PoolResponse {
                     total_share: Uint128::from(1_000_000u128),
@@ -131,6 +151,13 @@ enum MockQueryMsg {
         lp_token: String,
         user: String
     },
+    RewardInfo {
+        lp_token: String,
+    },
+    CompoundSimulation {
+        rewards: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+    },
     Pool {},
 }
 
@@ -2,8 +2,9 @@ use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdError, StdResult, Storage, Uint128};
 use cw20::AllowanceResponse;
+use astroport::asset::{native_asset_info, token_asset_info, Asset, AssetInfo};
 use astroport::pair::PoolResponse;
 use spectrum::adapters::generator::Generator;
 use spectrum::adapters::pair::Pair;
@@ -31,6 +32,85 @@ pub struct Config {
     #[serde(default)] pub name: String,
     #[serde(default)] pub symbol: String,
     #[serde(default = "default_pair")] pub pair: Pair,
+
+    /// contract version, set on instantiate and migrate
+    #[serde(default)] pub version: String,
+
+    /// maximum slippage tolerance applied to compound, zero means no protection is enforced
+    #[serde(default)] pub min_slippage_protection: Decimal,
+
+    /// period over which a new deposit accrues its share of compounded rewards
+    #[serde(default = "default_deposit_penalty_period")] pub deposit_penalty_period: u64,
+
+    /// minimum LP amount simulated from rewards to compound, zero means compound is never skipped
+    #[serde(default)] pub min_compound_value: Uint128,
+
+    /// whether liquidity_token holds a native LP denom instead of a cw20 address
+    #[serde(default)] pub native_lp: bool,
+
+    /// fraction of rewards paid to the caller of public compound, zero means public compound is disabled
+    #[serde(default)] pub bounty: Decimal,
+
+    /// slippage tolerance used for bond assets and compound when not given, zero means the compound proxy default
+    #[serde(default)] pub default_slippage: Decimal,
+
+    /// maximum slippage tolerance accepted for bond assets and compound
+    #[serde(default = "default_max_slippage")] pub max_slippage: Decimal,
+
+    /// whether emergency unbond is enabled, set by the owner during a compound outage
+    #[serde(default)] pub emergency: bool,
+
+    /// maximum bounty the owner can set for public compound
+    #[serde(default = "default_max_bounty")] pub max_bounty: Decimal,
+}
+
+impl Config {
+    /// Returns the asset info of the LP token
+    pub fn liquidity_token_info(&self) -> AssetInfo {
+        if self.native_lp {
+            native_asset_info(self.liquidity_token.to_string())
+        } else {
+            token_asset_info(self.liquidity_token.clone())
+        }
+    }
+
+    /// Returns the given slippage tolerance or the default, rejecting a tolerance above the maximum
+    pub fn resolve_slippage(&self, slippage_tolerance: Option<Decimal>) -> StdResult<Option<Decimal>> {
+        let slippage_tolerance = match slippage_tolerance {
+            Some(slippage_tolerance) => Some(slippage_tolerance),
+            None if self.default_slippage.is_zero() => None,
+            None => Some(self.default_slippage),
+        };
+        if let Some(slippage_tolerance) = slippage_tolerance {
+            if slippage_tolerance > self.max_slippage {
+                return Err(StdError::generic_err(format!(
+                    "slippage_tolerance must be at most {}", self.max_slippage
+                )));
+            }
+        }
+        Ok(slippage_tolerance)
+    }
+
+    /// Returns the message depositing the LP token to the generator
+    pub fn deposit_msg(&self, amount: Uint128) -> StdResult<CosmosMsg> {
+        if self.native_lp {
+            self.staking_contract.deposit_native_msg(self.liquidity_token.to_string(), amount)
+        } else {
+            self.staking_contract.deposit_msg(self.liquidity_token.to_string(), amount)
+        }
+    }
+}
+
+fn default_deposit_penalty_period() -> u64 {
+    DAY
+}
+
+fn default_max_slippage() -> Decimal {
+    Decimal::one()
+}
+
+pub fn default_max_bounty() -> Decimal {
+    Decimal::percent(5)
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -42,6 +122,24 @@ pub struct State {
 
 pub const STATE: Item<State> = Item::new("state");
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompoundRecord {
+    pub time: u64,
+    pub amount: Uint128,
+}
+
+/// Stores recent compounds, covering the last day plus the compound before it
+pub const COMPOUND_HISTORY: Item<Vec<CompoundRecord>> = Item::new("compound_history");
+
+/// Stores rewards claimed without providing liquidity, fee is already taken
+pub const HELD_REWARDS: Item<Vec<Asset>> = Item::new("held_rewards");
+
+/// Stores rewards claimed but left out of compound, fee is not taken yet
+pub const DEFERRED_REWARDS: Item<Vec<Asset>> = Item::new("deferred_rewards");
+
+/// Stores the cumulative fee sent to the fee collector by reward asset
+pub const FEE_STATS: Map<&str, Uint128> = Map::new("fee_stats");
+
 impl State {
     pub fn calc_bond_share(
         &self,
@@ -82,6 +180,7 @@ pub struct RewardInfo {
 
     #[serde(default)] pub transfer_share: Uint128,
     #[serde(default)] pub deposit_costs: Vec<Uint128>,
+    #[serde(default)] pub lock_until: Option<u64>,
 }
 
 impl RewardInfo {
@@ -131,14 +230,19 @@ impl RewardInfo {
 
 pub const REWARD: Map<&Addr, RewardInfo> = Map::new("reward");
 
-const DAY: u64 = 86400;
+pub const DAY: u64 = 86400;
 
 impl RewardInfo {
-    pub fn calc_user_balance(&self, state: &State, lp_balance: Uint128, time: u64) -> Uint128 {
+    /// extends the lock to the later of the current and the given lock time
+    pub fn lock(&mut self, lock_until: Option<u64>) {
+        self.lock_until = self.lock_until.max(lock_until);
+    }
+
+    pub fn calc_user_balance(&self, state: &State, lp_balance: Uint128, time: u64, penalty_period: u64) -> Uint128 {
         let amount = state.calc_bond_amount(lp_balance, self.bond_share);
         let deposit_time = time - self.deposit_time;
-        if deposit_time < DAY && amount > self.deposit_amount {
-            self.deposit_amount + (amount - self.deposit_amount).multiply_ratio(deposit_time, DAY)
+        if deposit_time < penalty_period && amount > self.deposit_amount {
+            self.deposit_amount + (amount - self.deposit_amount).multiply_ratio(deposit_time, penalty_period)
         } else {
             amount
         }
@@ -1,24 +1,24 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query, CONTRACT_VERSION};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::{Config, State};
+use crate::state::{Config, State, CONFIG, DAY};
 
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{token_asset, token_asset_info, Asset, AssetInfo};
 use astroport::generator::{
     Cw20HookMsg as GeneratorCw20HookMsg, ExecuteMsg as GeneratorExecuteMsg,
 };
 
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
+    attr, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
     Timestamp, Uint128, WasmMsg,
 };
 use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo, AllowanceResponse, BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration, Logo, MarketingInfoResponse, MinterResponse, TokenInfoResponse};
 use spectrum::adapters::generator::Generator;
 use spectrum::adapters::pair::Pair;
 use spectrum::astroport_farm::{
-    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem,
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PerformanceStatsResponse, PoolInfoResponse, QueryMsg,
+    RewardInfoResponse, RewardInfoResponseItem, SimulateBondAssetsResponse,
 };
 use spectrum::compound_proxy::{Compounder, ExecuteMsg as CompoundProxyExecuteMsg};
 
@@ -36,7 +36,9 @@ const COMPOUND_PROXY_2: &str = "compound_proxy_2";
 const CONTROLLER_2: &str = "controller_2";
 const FEE_COLLECTOR_2: &str = "fee_collector_2";
 const LP_TOKEN: &str = "lp_token";
+const LP_DENOM: &str = "factory/pair/astroport/share";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const XASTRO_TOKEN: &str = "xastro";
 
 #[test]
 fn test() -> Result<(), ContractError> {
@@ -53,6 +55,35 @@ fn test() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn test_version() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let res: String = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Version {})?)?;
+    assert_eq!(res, CONTRACT_VERSION);
+
+    // simulate an instance deployed with an older code
+    CONFIG.update::<_, StdError>(deps.as_mut().storage, |mut config| {
+        config.version = "0.1.0".to_string();
+        Ok(config)
+    })?;
+    let res: String = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Version {})?)?;
+    assert_eq!(res, "0.1.0");
+
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {})?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "migrate"),
+        attr("prev_version", "0.1.0"),
+        attr("version", CONTRACT_VERSION),
+    ]);
+    let res: String = from_binary(&query(deps.as_ref(), env, QueryMsg::Version {})?)?;
+    assert_eq!(res, CONTRACT_VERSION);
+
+    Ok(())
+}
+
 fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     match res {
         Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
@@ -80,6 +111,7 @@ fn create(
         name: "name".to_string(),
         symbol: "SYMBOL".to_string(),
         pair: "pair".to_string(),
+        native_lp: false,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "fee must be 0 to 1");
@@ -97,6 +129,7 @@ fn create(
         name: "name".to_string(),
         symbol: "SYMBOL".to_string(),
         pair: "pair".to_string(),
+        native_lp: false,
     };
 
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
@@ -119,6 +152,16 @@ fn create(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            version: CONTRACT_VERSION.to_string(),
+            min_slippage_protection: Decimal::zero(),
+            deposit_penalty_period: DAY,
+            min_compound_value: Uint128::zero(),
+            native_lp: false,
+            bounty: Decimal::zero(),
+            default_slippage: Decimal::zero(),
+            max_slippage: Decimal::one(),
+            emergency: false,
+            max_bounty: Decimal::percent(5),
         }
     );
 
@@ -136,6 +179,14 @@ fn config(
         controller: None,
         fee: Some(Decimal::percent(101)),
         fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -149,6 +200,14 @@ fn config(
         controller: None,
         fee: Some(Decimal::percent(3)),
         fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -158,6 +217,14 @@ fn config(
         controller: None,
         fee: None,
         fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -167,6 +234,14 @@ fn config(
         controller: Some(CONTROLLER_2.to_string()),
         fee: None,
         fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -176,6 +251,14 @@ fn config(
         controller: None,
         fee: None,
         fee_collector: Some(FEE_COLLECTOR_2.to_string()),
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -196,6 +279,16 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            version: CONTRACT_VERSION.to_string(),
+            min_slippage_protection: Decimal::zero(),
+            deposit_penalty_period: DAY,
+            min_compound_value: Uint128::zero(),
+            native_lp: false,
+            bounty: Decimal::zero(),
+            default_slippage: Decimal::zero(),
+            max_slippage: Decimal::one(),
+            emergency: false,
+            max_bounty: Decimal::percent(5),
         }
     );
 
@@ -204,6 +297,14 @@ fn config(
         controller: Some(CONTROLLER.to_string()),
         fee: Some(Decimal::percent(5)),
         fee_collector: Some(FEE_COLLECTOR.to_string()),
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -224,6 +325,16 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            version: CONTRACT_VERSION.to_string(),
+            min_slippage_protection: Decimal::zero(),
+            deposit_penalty_period: DAY,
+            min_compound_value: Uint128::zero(),
+            native_lp: false,
+            bounty: Decimal::zero(),
+            default_slippage: Decimal::zero(),
+            max_slippage: Decimal::one(),
+            emergency: false,
+            max_bounty: Decimal::percent(5),
         }
     );
 
@@ -336,7 +447,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "Unauthorized");
@@ -346,7 +457,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -388,10 +499,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(100000u128),
+                    vested_bond_amount: Uint128::from(100000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(100000u128),
                         Uint128::from(100000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -407,6 +521,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         amount: Uint128::from(50000u128),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: Some(USER_2.to_string()),
+            lock_until: None,
         })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
@@ -450,10 +565,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 102,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(50000u128),
+                    vested_bond_amount: Uint128::from(50000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(50000u128),
                         Uint128::from(50000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -492,10 +610,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(120000u128),
+                    vested_bond_amount: Uint128::from(120000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(100000u128),
                         Uint128::from(100000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -517,10 +638,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 102,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(60000u128),
+                    vested_bond_amount: Uint128::from(60000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(50000u128),
                         Uint128::from(50000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -594,10 +718,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(60000u128),
+                    vested_bond_amount: Uint128::from(60000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(50000u128),
                         Uint128::from(50000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -619,10 +746,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 102,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(60000u128),
+                    vested_bond_amount: Uint128::from(60000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(50000u128),
                         Uint128::from(50000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -682,7 +812,10 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 102,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(0u128),
+                    vested_bond_amount: Uint128::from(0u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![],
+                    lock_until: None,
                 }
             }
         }
@@ -704,10 +837,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(60000u128),
+                    vested_bond_amount: Uint128::from(60000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(50000u128),
                         Uint128::from(50000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -749,7 +885,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert_error(
         res,
-        "Native token balance mismatch between the argument and the transferred",
+        "Native token amount mismatch for ibc/stablecoin; declared amount: 40000, sent amount: 0",
     );
 
     let info = mock_info(
@@ -791,6 +927,10 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     to: None,
                     no_swap: None,
                     slippage_tolerance: Some(Decimal::percent(2)),
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
                 })?,
                 funds: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
@@ -844,10 +984,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: COMPOUND_PROXY.to_string(),
                 msg: to_binary(&CompoundProxyExecuteMsg::Compound {
-                    rewards: assets,
+                    rewards: assets.clone(),
                     to: None,
                     no_swap: Some(true),
                     slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
                 })?,
                 funds: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
@@ -866,6 +1010,44 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         ]
     );
 
+    // simulate the same deposit, compound proxy returns 10000 LP
+    deps.querier.set_balance(
+        COMPOUND_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    let msg = QueryMsg::SimulateBondAssets {
+        assets: assets.clone(),
+        no_swap: None,
+        slippage_tolerance: Some(Decimal::percent(2)),
+    };
+    let res: SimulateBondAssetsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        SimulateBondAssetsResponse {
+            lp_amount: Uint128::from(10000u128),
+            bond_share: Uint128::from(8333u128),
+            minimum_receive: Uint128::from(9800u128),
+        }
+    );
+
+    // duplicated assets are rejected as in bond assets
+    let msg = QueryMsg::SimulateBondAssets {
+        assets: vec![assets[0].clone(), assets[0].clone()],
+        no_swap: None,
+        slippage_tolerance: None,
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Duplicated asset")));
+
+    let msg = QueryMsg::SimulateBondAssets {
+        assets: vec![],
+        no_swap: None,
+        slippage_tolerance: None,
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("assets must not be empty")));
+
     // update time
     env.block.time = Timestamp::from_seconds(200201);
 
@@ -940,10 +1122,13 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     deposit_time: 33448,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(70000u128),
+                    vested_bond_amount: Uint128::from(70000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(59999u128),
                         Uint128::from(59999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -973,7 +1158,7 @@ fn deposit_time(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_3.to_string(),
         amount: Uint128::from(10000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -1001,10 +1186,13 @@ fn deposit_time(
                     deposit_time: 300000,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(9999u128),
+                    vested_bond_amount: Uint128::from(9999u128),
+                    unvested_bond_amount: Uint128::from(625u128),
                     deposit_costs: vec![
                         Uint128::from(9999u128),
                         Uint128::from(9999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1028,15 +1216,24 @@ fn deposit_time(
                     deposit_time: 300000,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(10311u128),
+                    vested_bond_amount: Uint128::from(10311u128),
+                    unvested_bond_amount: Uint128::from(313u128),
                     deposit_costs: vec![
                         Uint128::from(9999u128),
                         Uint128::from(9999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
     );
 
+    // vested and unvested amounts sum to the full value of the share
+    assert_eq!(
+        res.reward_info.vested_bond_amount + res.reward_info.unvested_bond_amount,
+        Uint128::from(10624u128)
+    );
+
     // query reward info for user_1, should be 74375
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
@@ -1053,10 +1250,13 @@ fn deposit_time(
                     deposit_time: 33448,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(74375u128),
+                    vested_bond_amount: Uint128::from(74375u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(59999u128),
                         Uint128::from(59999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1081,10 +1281,13 @@ fn deposit_time(
                     deposit_time: 300000,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(10624u128),
+                    vested_bond_amount: Uint128::from(10624u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(9999u128),
                         Uint128::from(9999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1147,10 +1350,13 @@ fn deposit_time(
                     deposit_time: 33448,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(74689u128),
+                    vested_bond_amount: Uint128::from(74689u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(59999u128),
                         Uint128::from(59999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1191,6 +1397,8 @@ fn compound(
     let msg = ExecuteMsg::Compound {
         minimum_receive: Some(Uint128::from(29900u128)),
         slippage_tolerance: Some(Decimal::percent(3)),
+        skip_provide: None,
+        reward_assets: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -1264,6 +1472,10 @@ fn compound(
                     to: None,
                     no_swap: None,
                     slippage_tolerance: Some(Decimal::percent(3)),
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
                 })?,
                 funds: vec![],
             }),
@@ -1337,7 +1549,7 @@ fn callback(
 
     // only contract itself can execute callback
     let res = execute(deps.as_mut(), env.clone(), info, msg);
-    assert_error(res, "Unauthorized");
+    assert_error(res, &format!("Unauthorized; callbacks can only be invoked by {}, sender: {}", MOCK_CONTRACT_ADDR, USER_1));
 
     let msg = ExecuteMsg::Callback(CallbackMsg::BondTo {
         to: Addr::unchecked(USER_1),
@@ -1348,7 +1560,7 @@ fn callback(
 
     // only contract itself can execute callback
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
-    assert_error(res, "Unauthorized");
+    assert_error(res, &format!("Unauthorized; callbacks can only be invoked by {}, sender: {}", MOCK_CONTRACT_ADDR, USER_1));
 
     Ok(())
 }
@@ -1372,7 +1584,7 @@ fn cw20(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_3.to_string(),
         amount: Uint128::from(200000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
     });
     execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
 
@@ -1397,10 +1609,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(199999u128),
+                    vested_bond_amount: Uint128::from(199999u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(199999u128),
                         Uint128::from(199999u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1426,6 +1641,7 @@ fn cw20(
         contract: FEE_COLLECTOR_2.to_string(),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: None,
+            lock_until: None,
         })?,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
@@ -1442,6 +1658,7 @@ fn cw20(
                     amount: Uint128::from(50000u128),
                     msg: to_binary(&Cw20HookMsg::Bond {
                         staker_addr: None,
+                        lock_until: None,
                     })?,
                 }))?,
                 funds: vec![],
@@ -1464,10 +1681,13 @@ fn cw20(
                     deposit_time: 811389522,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(138708u128),
+                    vested_bond_amount: Uint128::from(138708u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(124018u128),
                         Uint128::from(124018u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1489,10 +1709,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(64019u128),
+                    vested_bond_amount: Uint128::from(64019u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(64019u128),
                         Uint128::from(64019u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1513,10 +1736,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(71960u128),
+                    vested_bond_amount: Uint128::from(71960u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(71960u128),
                         Uint128::from(71960u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1633,10 +1859,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(135980u128),
+                    vested_bond_amount: Uint128::from(135980u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(135979u128),
                         Uint128::from(135979u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1683,10 +1912,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(32009u128),
+                    vested_bond_amount: Uint128::from(32009u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(32009u128),
                         Uint128::from(32009u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1796,6 +2028,7 @@ fn cw20(
         amount: Uint128::from(20000u128),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: None,
+            lock_until: None,
         })?,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
@@ -1812,6 +2045,7 @@ fn cw20(
                     amount: Uint128::from(20000u128),
                     msg: to_binary(&Cw20HookMsg::Bond {
                         staker_addr: None,
+                        lock_until: None,
                     })?,
                 }))?,
                 funds: vec![],
@@ -1833,10 +2067,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(84764u128),
+                    vested_bond_amount: Uint128::from(84764u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(84764u128),
                         Uint128::from(84764u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1875,10 +2112,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(83223u128),
+                    vested_bond_amount: Uint128::from(83223u128),
+                    unvested_bond_amount: Uint128::from(3u128),
                     deposit_costs: vec![
                         Uint128::from(83223u128),
                         Uint128::from(83223u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1899,10 +2139,13 @@ fn cw20(
                     deposit_time: 1571797419,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(59157u128),
+                    vested_bond_amount: Uint128::from(59157u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(59156u128),
                         Uint128::from(59156u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -1994,6 +2237,155 @@ fn poc_native_funds(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>)
     Ok(())
 }
 
+#[test]
+fn test_native_funds_mismatch() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    native_funds_mismatch(&mut deps)?;
+
+    Ok(())
+}
+
+fn native_funds_mismatch(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // declare 40_000 but send 30_000
+    let info = mock_info(USER_1, &[Coin {
+        denom: IBC_TOKEN.to_string(),
+        amount: Uint128::from(30_000u128),
+    }]);
+    let msg = ExecuteMsg::BondAssets {
+        assets: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: IBC_TOKEN.to_string(),
+                },
+                amount: Uint128::from(40_000u128),
+            },
+        ],
+        minimum_receive: None,
+        no_swap: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Native token amount mismatch for ibc/stablecoin; declared amount: 40000, sent amount: 30000");
+
+    // send funds without declaring them
+    let info = mock_info(USER_1, &[Coin {
+        denom: IBC_TOKEN.to_string(),
+        amount: Uint128::from(30_000u128),
+    }]);
+    let msg = ExecuteMsg::BondAssets {
+        assets: vec![
+            Asset {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(ASTRO_TOKEN),
+                },
+                amount: Uint128::from(40_000u128),
+            },
+        ],
+        minimum_receive: None,
+        no_swap: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_error(res, "Native token amount mismatch for ibc/stablecoin; declared amount: 0, sent amount: 30000");
+
+    Ok(())
+}
+
+#[test]
+fn test_slippage_limits() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let owner_info = mock_info(USER_1, &[]);
+    let update_slippage = |default_slippage: Option<Decimal>, max_slippage: Option<Decimal>| ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage,
+        max_slippage,
+        emergency: None,
+        max_bounty: None,
+    };
+
+    let msg = update_slippage(None, Some(Decimal::percent(101)));
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg);
+    assert_error(res, "max_slippage must be 0 to 1");
+
+    let msg = update_slippage(Some(Decimal::percent(2)), Some(Decimal::percent(1)));
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg);
+    assert_error(res, "default_slippage must not exceed max_slippage");
+
+    let msg = update_slippage(Some(Decimal::percent(1)), Some(Decimal::percent(3)));
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg)?;
+
+    // lowering the max below the default is rejected
+    let msg = update_slippage(None, Some(Decimal::percent(0)));
+    let res = execute(deps.as_mut(), env.clone(), owner_info, msg);
+    assert_error(res, "default_slippage must not exceed max_slippage");
+
+    let assets = vec![
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+            amount: Uint128::from(40_000u128),
+        },
+    ];
+    let funds = vec![Coin {
+        denom: IBC_TOKEN.to_string(),
+        amount: Uint128::from(40_000u128),
+    }];
+    let info = mock_info(USER_1, &funds);
+
+    // omitted slippage uses the default
+    let msg = ExecuteMsg::BondAssets {
+        assets: assets.clone(),
+        minimum_receive: None,
+        no_swap: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages[0].msg,
+        Compounder(Addr::unchecked(COMPOUND_PROXY)).compound_msg(
+            assets.clone(),
+            funds.clone(),
+            None,
+            Some(Decimal::percent(1)),
+        )?
+    );
+
+    // slippage above the max is rejected
+    let msg = ExecuteMsg::BondAssets {
+        assets,
+        minimum_receive: None,
+        no_swap: None,
+        slippage_tolerance: Some(Decimal::percent(5)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "slippage_tolerance must be at most 0.03");
+
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: Some(Decimal::percent(5)),
+        skip_provide: None,
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(CONTROLLER, &[]), msg);
+    assert_error(res, "slippage_tolerance must be at most 0.03");
+
+    Ok(())
+}
+
 #[test]
 fn test_self_transfer() -> Result<(), ContractError> {
     let mut deps = mock_dependencies();
@@ -2014,7 +2406,7 @@ fn poc_self_transfer(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
     });
     execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
 
@@ -2041,10 +2433,13 @@ fn poc_self_transfer(
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
                     bond_amount: Uint128::from(100000u128),
+                    vested_bond_amount: Uint128::from(100000u128),
+                    unvested_bond_amount: Uint128::from(0u128),
                     deposit_costs: vec![
                         Uint128::from(100000u128),
                         Uint128::from(100000u128),
                     ],
+                    lock_until: None,
                 }
             }
         }
@@ -2068,4 +2463,1713 @@ fn poc_self_transfer(
     assert_eq!(new_res, res);
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_compound_ordering() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    compound_ordering(&mut deps)?;
+
+    Ok(())
+}
+
+fn compound_ordering(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+
+    // base reward token is sorted after proxy reward token
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: XASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        native_lp: false,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(50000u128),
+    );
+    env.block.height = 700;
+
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(47500u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(2500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: XASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(9500u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: XASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(REWARD_TOKEN),
+                            },
+                            amount: Uint128::from(47500u128),
+                        },
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(XASTRO_TOKEN),
+                            },
+                            amount: Uint128::from(9500u128),
+                        },
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pending_rewards() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    pending_rewards(&mut deps)?;
+
+    Ok(())
+}
+
+fn pending_rewards(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        native_lp: false,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    // no pending rewards
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingRewards {})?)?;
+    assert_eq!(res, vec![]);
+
+    // only base reward is pending
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingRewards {})?)?;
+    assert_eq!(res, vec![
+        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(10000u128)),
+    ]);
+
+    // proxy reward is pending as well
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(50000u128),
+    );
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env, QueryMsg::PendingRewards {})?)?;
+    assert_eq!(res, vec![
+        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(10000u128)),
+        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(50000u128)),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_reward_tokens() -> Result<(), ContractError> {
+    let deps = mock_dependencies();
+
+    let generator = Generator(Addr::unchecked(GENERATOR_PROXY));
+    let res = generator.query_reward_tokens(&deps.as_ref().querier, &Addr::unchecked(LP_TOKEN))?;
+    assert_eq!(res, vec![
+        token_asset_info(Addr::unchecked(ASTRO_TOKEN)),
+        token_asset_info(Addr::unchecked(REWARD_TOKEN)),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_skip_provide() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(50000u128),
+    );
+
+    // provide is disabled, claim and fee still settle
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: Some(true),
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(2500u128)
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    assert_eq!(res.attributes, vec![
+        attr("action", "compound"),
+        attr("token", ASTRO_TOKEN),
+        attr("held_amount", "9500"),
+        attr("commission_amount", "500"),
+        attr("token", REWARD_TOKEN),
+        attr("held_amount", "47500"),
+        attr("commission_amount", "2500"),
+    ]);
+
+    // held rewards are compounded later without taking fee again
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::zero(),
+    );
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(19000u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(47500u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(19000u128)),
+                        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(47500u128)),
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // held rewards are cleared after compound
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::zero(),
+    );
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(res.messages.len(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_reward_assets() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(50000u128),
+    );
+
+    // only ASTRO is compounded, fee is not taken on reward token
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: Some(vec![
+            AssetInfo::Token { contract_addr: Addr::unchecked(ASTRO_TOKEN) },
+        ]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(9500u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(9500u128)),
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // claimed reward token is compounded in a later call, without claiming again
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::zero(),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::zero(),
+    );
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+
+    // everyone unbonded, the deferred reward token is kept for the next call
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::zero(),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert!(res.messages.is_empty());
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(47500u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(2500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(47500u128)),
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_public_compound() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+
+    // public compound is disabled without bounty
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::PublicCompound {});
+    assert_error(res, "public compound is disabled");
+
+    let owner_info = mock_info(USER_1, &[]);
+    let update_bounty = |bounty: Option<Decimal>, max_bounty: Option<Decimal>| ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty,
+    };
+
+    // bounty is capped by max_bounty, 5% by default
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), update_bounty(Some(Decimal::percent(6)), None));
+    assert_error(res, "bounty must not exceed max_bounty");
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), update_bounty(None, Some(Decimal::percent(101))));
+    assert_error(res, "max_bounty must be 0 to 1");
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), update_bounty(Some(Decimal::percent(6)), Some(Decimal::percent(10))))?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.max_bounty, Decimal::percent(10));
+
+    // max_bounty cannot be lowered below the current bounty
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), update_bounty(None, Some(Decimal::percent(2))));
+    assert_error(res, "bounty must not exceed max_bounty");
+
+    // fee is 5%, so bounty cannot exceed 95%
+    let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), update_bounty(Some(Decimal::percent(96)), Some(Decimal::percent(100))));
+    assert_error(res, "fee and bounty must not exceed 1");
+    execute(deps.as_mut(), env.clone(), owner_info, update_bounty(Some(Decimal::percent(1)), Some(Decimal::percent(2))))?;
+
+    // caller receives 1% of rewards, fee is 5%, the rest is compounded
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::PublicCompound {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(9400u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_2.to_string(),
+                    amount: Uint128::from(100u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(9400u128)),
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    dust_to: None,
+                    slippage_overrides: None,
+                    refund_dust: None,
+                    routes: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_zero_pending() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    // nothing pending, no claim, fee or compound
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(res.messages, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_min_compound_value() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: Some(Uint128::from(1000u128)),
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // rewards simulate below the threshold, nothing is claimed, charged or compounded
+    deps.querier.set_balance(
+        COMPOUND_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(999u128),
+    );
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(res.messages, vec![]);
+    assert_eq!(res.attributes, vec![
+        attr("action", "compound_skipped"),
+        attr("lp_amount", "999"),
+    ]);
+    let res: Vec<(String, Uint128)> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::FeeStats {})?)?;
+    assert_eq!(res, vec![]);
+
+    // rewards reach the threshold
+    deps.querier.set_balance(
+        COMPOUND_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(1000u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let stats: Vec<(String, Uint128)> = from_binary(&query(deps.as_ref(), env, QueryMsg::FeeStats {})?)?;
+    assert_eq!(stats, vec![(ASTRO_TOKEN.to_string(), Uint128::from(500u128))]);
+    assert_eq!(res.attributes[0], attr("action", "compound"));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR_PROXY.to_string(),
+            msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                lp_tokens: vec![LP_TOKEN.to_string()]
+            })?,
+            funds: vec![],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_stats() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.height = 700;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+        skip_provide: None,
+        reward_assets: None,
+    };
+    for (astro_amount, reward_amount) in [(10000u128, 50000u128), (20000u128, 10000u128)] {
+        deps.querier.set_balance(
+            GENERATOR_PROXY.to_string(),
+            ASTRO_TOKEN.to_string(),
+            Uint128::from(astro_amount),
+        );
+        deps.querier.set_balance(
+            GENERATOR_PROXY.to_string(),
+            REWARD_TOKEN.to_string(),
+            Uint128::from(reward_amount),
+        );
+        execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    }
+
+    // 5% of each compound, 500 + 1000 astro and 2500 + 500 reward
+    let res: Vec<(String, Uint128)> = from_binary(&query(deps.as_ref(), env, QueryMsg::FeeStats {})?)?;
+    assert_eq!(res, vec![
+        (ASTRO_TOKEN.to_string(), Uint128::from(1500u128)),
+        (REWARD_TOKEN.to_string(), Uint128::from(3000u128)),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_emergency_unbond() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    let info = mock_info(LP_TOKEN, &[]);
+    for (time, user, balance) in [(101u64, USER_1, 100000u128), (102u64, USER_2, 200000u128)] {
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: user.to_string(),
+            amount: Uint128::from(100000u128),
+            msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(
+            GENERATOR_PROXY.to_string(),
+            LP_TOKEN.to_string(),
+            Uint128::from(balance),
+        );
+    }
+
+    // compound within deposit time, user_1 share is 150000 but vested balance is lower
+    env.block.time = Timestamp::from_seconds(43302);
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(300000u128),
+    );
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Unbond {
+        amount: Uint128::from(150000u128),
+    });
+    assert_error(res, "Cannot unbond more than balance");
+
+    // emergency unbond is only allowed once the owner enables emergency mode
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnbond {
+        amount: Uint128::from(150000u128),
+    });
+    assert_error(res, "Emergency unbond is not enabled");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: Some(true),
+        max_bounty: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnbond {
+        amount: Uint128::from(150001u128),
+    });
+    assert_error(res, "Cannot unbond more than balance");
+
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_2.to_string(),
+    };
+    let user_2_before: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::EmergencyUnbond {
+        amount: Uint128::from(150000u128),
+    })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(150000u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_1.to_string(),
+                    amount: Uint128::from(150000u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    assert_eq!(res.attributes, vec![
+        attr("action", "emergency_unbond"),
+        attr("staker_addr", USER_1),
+        attr("amount", "150000"),
+    ]);
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(150000u128),
+    );
+
+    // user_1 is fully unbonded, user_2 is unaffected
+    let res: State = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(res.total_bond_share, Uint128::from(100000u128));
+    let user_2_after: RewardInfoResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(user_2_after, user_2_before);
+
+    Ok(())
+}
+
+#[test]
+fn test_deposit_penalty_period() -> Result<(), ContractError> {
+    for (period, full_accrual) in [(None, DAY), (Some(DAY / 2), DAY / 2)] {
+        deposit_penalty_period(period, full_accrual)?;
+    }
+
+    Ok(())
+}
+
+fn deposit_penalty_period(period: Option<u64>, full_accrual: u64) -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(100);
+
+    if period.is_some() {
+        let info = mock_info(USER_1, &[]);
+        let msg = ExecuteMsg::UpdateConfig {
+            compound_proxy: None,
+            controller: None,
+            fee: None,
+            fee_collector: None,
+            min_slippage_protection: None,
+            deposit_penalty_period: period,
+            min_compound_value: None,
+            bounty: None,
+            default_slippage: None,
+            max_slippage: None,
+            emergency: None,
+            max_bounty: None,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg)?;
+    }
+
+    // user_1 bond 100000 LP, then compound doubles the generator balance
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(200000u128),
+    );
+
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let mut bond_amount_at = |elapsed: u64| -> Result<Uint128, ContractError> {
+        env.block.time = Timestamp::from_seconds(100 + elapsed);
+        let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+        Ok(res.reward_info.bond_amount)
+    };
+
+    // half of the compounded amount accrues halfway through the window
+    assert_eq!(bond_amount_at(full_accrual / 2)?, Uint128::from(150000u128));
+    assert!(bond_amount_at(full_accrual - 1)? < Uint128::from(200000u128));
+    assert_eq!(bond_amount_at(full_accrual)?, Uint128::from(200000u128));
+
+    Ok(())
+}
+
+#[test]
+fn test_performance_stats() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    // bond 100000 LP
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    // no compound yet
+    let res: PerformanceStatsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PerformanceStats {})?)?;
+    assert_eq!(res, PerformanceStatsResponse {
+        last_compound_time: 0,
+        compounded_amount_24h: Uint128::zero(),
+        total_bond_amount: Uint128::from(100000u128),
+        apr: Decimal::zero(),
+    });
+
+    // first compound, insufficient history for rate
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    env.block.time = Timestamp::from_seconds(2000);
+    deps.querier.set_balance(
+        LP_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(500u128),
+    );
+    let msg = ExecuteMsg::Callback(CallbackMsg::Stake {
+        prev_balance: Uint128::zero(),
+        minimum_receive: None,
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let res: PerformanceStatsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PerformanceStats {})?)?;
+    assert_eq!(res, PerformanceStatsResponse {
+        last_compound_time: 2000,
+        compounded_amount_24h: Uint128::from(500u128),
+        total_bond_amount: Uint128::from(100000u128),
+        apr: Decimal::zero(),
+    });
+
+    // second compound an hour later, 100 LP per hour on 100000 LP is 0.1% per hour
+    env.block.time = Timestamp::from_seconds(5600);
+    deps.querier.set_balance(
+        LP_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(600u128),
+    );
+    let msg = ExecuteMsg::Callback(CallbackMsg::Stake {
+        prev_balance: Uint128::from(500u128),
+        minimum_receive: None,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let res: PerformanceStatsResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PerformanceStats {})?)?;
+    assert_eq!(res, PerformanceStatsResponse {
+        last_compound_time: 5600,
+        compounded_amount_24h: Uint128::from(600u128),
+        total_bond_amount: Uint128::from(100000u128),
+        apr: Decimal::permille(8760),
+    });
+
+    // compounds older than a day are excluded from 24h amount
+    env.block.time = Timestamp::from_seconds(2000 + 86400);
+    let res: PerformanceStatsResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::PerformanceStats {})?)?;
+    assert_eq!(res.compounded_amount_24h, Uint128::from(100u128));
+
+    Ok(())
+}
+
+#[test]
+fn test_compound_slippage_protection() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: Some(Decimal::percent(101)),
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "min_slippage_protection must be 0 to 1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: Some(Decimal::percent(1)),
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: None,
+        max_bounty: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+
+    // none and looser slippage are capped by the protection, tighter slippage is kept
+    let info = mock_info(CONTROLLER, &[]);
+    for (slippage_tolerance, expected) in [
+        (None, Decimal::percent(1)),
+        (Some(Decimal::percent(3)), Decimal::percent(1)),
+        (Some(Decimal::permille(5)), Decimal::permille(5)),
+    ] {
+        let msg = ExecuteMsg::Compound {
+            minimum_receive: None,
+            slippage_tolerance,
+            skip_provide: None,
+            reward_assets: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        let compound_msg = res.messages
+            .into_iter()
+            .find_map(|it| match it.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) if contract_addr == COMPOUND_PROXY => Some(msg),
+                _ => None,
+            })
+            .unwrap();
+        let compound_msg: CompoundProxyExecuteMsg = from_binary(&compound_msg)?;
+        assert_eq!(
+            compound_msg,
+            CompoundProxyExecuteMsg::Compound {
+                rewards: vec![Asset {
+                    info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked(ASTRO_TOKEN),
+                    },
+                    amount: Uint128::from(9500u128),
+                }],
+                to: None,
+                no_swap: None,
+                slippage_tolerance: Some(expected),
+                dust_to: None,
+                slippage_overrides: None,
+                refund_dust: None,
+                routes: None,
+            }
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bond_lock() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    // user_1 bonds with lock, user_2 bonds without lock
+    let mut env = mock_env();
+    let info = mock_info(LP_TOKEN, &[]);
+    for (time, user, lock_until, balance) in [
+        (101u64, USER_1, Some(1000u64), 100000u128),
+        (102u64, USER_2, None, 200000u128),
+    ] {
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: user.to_string(),
+            amount: Uint128::from(100000u128),
+            msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until })?,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(
+            GENERATOR_PROXY.to_string(),
+            LP_TOKEN.to_string(),
+            Uint128::from(balance),
+        );
+    }
+
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, Some(1000u64));
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_2.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, None);
+
+    // locked position cannot be unbonded, even in emergency mode
+    env.block.time = Timestamp::from_seconds(999);
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        min_slippage_protection: None,
+        deposit_penalty_period: None,
+        min_compound_value: None,
+        bounty: None,
+        default_slippage: None,
+        max_slippage: None,
+        emergency: Some(true),
+        max_bounty: None,
+    })?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    });
+    assert_error(res, "Position is locked until 1000");
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnbond {
+        amount: Uint128::from(10000u128),
+    });
+    assert_error(res, "Position is locked until 1000");
+
+    // transferred share carries the lock, the receiver inherits the later lock
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Transfer {
+        recipient: USER_3.to_string(),
+        amount: Uint128::from(10000u128),
+    })?;
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_3.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, Some(1000u64));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::Unbond {
+        amount: Uint128::from(1000u128),
+    });
+    assert_error(res, "Position is locked until 1000");
+
+    // receiver with the later lock keeps it
+    execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), ExecuteMsg::Transfer {
+        recipient: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+    })?;
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, Some(1000u64));
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_2.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, None);
+
+    // cannot lock the position of another staker
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker_addr: Some(USER_2.to_string()),
+            lock_until: Some(5000u64),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    // staker can extend its own lock, the later lock is kept
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker_addr: Some(USER_1.to_string()),
+            lock_until: Some(500u64),
+        })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(300000u128),
+    );
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    })?)?;
+    assert_eq!(res.reward_info.lock_until, Some(1000u64));
+
+    // unlocked position is not affected
+    let info = mock_info(USER_2, &[]);
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    })?;
+
+    // unbond is allowed once the lock has passed
+    env.block.time = Timestamp::from_seconds(1000);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    })?;
+    execute(deps.as_mut(), env, mock_info(USER_1, &[]), ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    })?;
+
+    Ok(())
+}
+
+#[test]
+fn test_pool_info() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    let info = mock_info(LP_TOKEN, &[]);
+    for (time, user, amount, balance) in [
+        (101u64, USER_1, 100000u128, 100000u128),
+        (102u64, USER_2, 50000u128, 150000u128),
+    ] {
+        env.block.time = Timestamp::from_seconds(time);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: user.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(
+            GENERATOR_PROXY.to_string(),
+            LP_TOKEN.to_string(),
+            Uint128::from(balance),
+        );
+    }
+
+    // compound adds 30000 LP, after deposit lock
+    env.block.time = Timestamp::from_seconds(102 + 86400);
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(180000u128),
+    );
+
+    let res: PoolInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PoolInfo {})?)?;
+    assert_eq!(
+        res,
+        PoolInfoResponse {
+            total_bond_share: Uint128::from(150000u128),
+            total_bond_amount: Uint128::from(180000u128),
+        }
+    );
+
+    // exchange rate from pool info matches each staker bond amount
+    for (user, bond_amount) in [(USER_1, 120000u128), (USER_2, 60000u128)] {
+        let reward_info: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+            staker_addr: user.to_string(),
+        })?)?;
+        assert_eq!(reward_info.reward_info.bond_amount, Uint128::from(bond_amount));
+        assert_eq!(
+            res.total_bond_amount.multiply_ratio(reward_info.reward_info.bond_share, res.total_bond_share),
+            reward_info.reward_info.bond_amount
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bond_native() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_DENOM.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        native_lp: true,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    // cw20 bond is not available
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    // only the LP denom can be bonded
+    let info = mock_info(USER_1, &[Coin {
+        denom: IBC_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    let msg = ExecuteMsg::BondNative { staker_addr: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Invalid message");
+
+    let info = mock_info(USER_1, &[Coin {
+        denom: LP_DENOM.to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR_PROXY.to_string(),
+            funds: vec![Coin {
+                denom: LP_DENOM.to_string(),
+                amount: Uint128::from(100u128),
+            }],
+            msg: to_binary(&GeneratorExecuteMsg::Deposit { recipient: None })?,
+        })]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_DENOM.to_string(),
+        Uint128::from(100u128),
+    );
+
+    let msg = QueryMsg::PoolInfo {};
+    let res: PoolInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.total_bond_share, Uint128::from(100u128));
+
+    // unbond returns the native LP denom
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(100u128) };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                funds: vec![],
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_DENOM.to_string(),
+                    amount: Uint128::from(100u128),
+                })?,
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin {
+                    denom: LP_DENOM.to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_bond_native_refund() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_DENOM.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        native_lp: true,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    // stray coin is refunded to the sender, LP is bonded for the staker
+    let info = mock_info(USER_1, &[
+        Coin {
+            denom: IBC_TOKEN.to_string(),
+            amount: Uint128::from(50u128),
+        },
+        Coin {
+            denom: LP_DENOM.to_string(),
+            amount: Uint128::from(100u128),
+        },
+    ]);
+    let msg = ExecuteMsg::BondNative { staker_addr: Some(USER_2.to_string()) };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                funds: vec![Coin {
+                    denom: LP_DENOM.to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+                msg: to_binary(&GeneratorExecuteMsg::Deposit { recipient: None })?,
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(50u128),
+                }],
+            }),
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_DENOM.to_string(),
+        Uint128::from(100u128),
+    );
+
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_2.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.reward_info.bond_share, Uint128::from(100u128));
+
+    Ok(())
+}
+
+#[test]
+fn test_batch_bond() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+
+    // user_1 bonds first, then the LP per share doubles
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(200000u128),
+    );
+
+    let batch_bond_msg = |amount: u128, entries: Vec<(&str, u128)>| -> Result<ExecuteMsg, ContractError> {
+        Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: OWNER.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::BatchBond {
+                entries: entries.into_iter()
+                    .map(|(staker_addr, amount)| (staker_addr.to_string(), Uint128::from(amount)))
+                    .collect(),
+            })?,
+        }))
+    };
+
+    let msg = batch_bond_msg(120000, vec![(USER_1, 20000), (USER_2, 40000), (USER_3, 60000)])?;
+    let res = execute(deps.as_mut(), env.clone(), mock_info(ASTRO_TOKEN, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    let msg = batch_bond_msg(100000, vec![(USER_1, 20000), (USER_2, 40000), (USER_3, 60000)])?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Batch amount mismatch; total of entries: 120000, received amount: 100000");
+
+    let msg = batch_bond_msg(120000, vec![(USER_1, 20000), (USER_2, 40000), (USER_1, 60000)])?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Duplicated staker user_1");
+
+    // the native batch bond is only for a native LP denom
+    let msg = ExecuteMsg::BatchBond {
+        entries: vec![(USER_1.to_string(), Uint128::from(100u128))],
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg);
+    assert_error(res, "Invalid message");
+
+    let msg = batch_bond_msg(120000, vec![(USER_1, 20000), (USER_2, 40000), (USER_3, 60000)])?;
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: GENERATOR_PROXY.to_string(),
+                amount: Uint128::from(120000u128),
+                msg: to_binary(&GeneratorCw20HookMsg::Deposit {})?,
+            })?,
+            funds: vec![],
+        }),]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(320000u128),
+    );
+
+    let msg = QueryMsg::PoolInfo {};
+    let res: PoolInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.total_bond_share, Uint128::from(160000u128));
+
+    for (user, bond_share, deposit_amount) in [
+        (USER_1, 110000u128, 120000u128),
+        (USER_2, 20000u128, 40000u128),
+        (USER_3, 30000u128, 60000u128),
+    ] {
+        let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::RewardInfo {
+            staker_addr: user.to_string(),
+        })?)?;
+        assert_eq!(res.reward_info.bond_share, Uint128::from(bond_share));
+        assert_eq!(res.reward_info.deposit_amount, Uint128::from(deposit_amount));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_exchange_rate() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    // no share is issued
+    let mut env = mock_env();
+    let rate: Decimal = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ExchangeRate {})?)?;
+    assert_eq!(rate, Decimal::one());
+
+    env.block.time = Timestamp::from_seconds(101);
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_until: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    let rate: Decimal = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ExchangeRate {})?)?;
+    assert_eq!(rate, Decimal::one());
+
+    // compound adds 25000 LP, after deposit lock
+    env.block.time = Timestamp::from_seconds(101 + 86400);
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(125000u128),
+    );
+
+    let rate: Decimal = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ExchangeRate {})?)?;
+    assert_eq!(rate, Decimal::percent(125));
+    assert!(rate > Decimal::one());
+
+    // the same rate converts the share of the staker to LP
+    let reward_info: RewardInfoResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    })?)?;
+    assert_eq!(reward_info.reward_info.bond_amount, reward_info.reward_info.bond_share * rate);
+
+    Ok(())
+}
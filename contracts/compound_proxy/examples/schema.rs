@@ -4,7 +4,7 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use spectrum::compound_proxy::{
     ExecuteMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg, CallbackMsg, CompoundSimulationResponse
+    QueryMsg, CallbackMsg, CompoundSimulationResponse, RouteResponse, SwapOperationsSimulationResponse
 };
 
 fn main() {
@@ -18,5 +18,7 @@ fn main() {
     export_schema(&schema_for!(CallbackMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(CompoundSimulationResponse), &out_dir);
+    export_schema(&schema_for!(RouteResponse), &out_dir);
+    export_schema(&schema_for!(SwapOperationsSimulationResponse), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
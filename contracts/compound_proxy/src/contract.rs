@@ -1,20 +1,22 @@
 use crate::error::ContractError;
-use crate::simulation::query_compound_simulation;
-use crate::state::{Config, CONFIG, PAIR_PROXY};
+use crate::simulation::{query_compound_simulation, query_simulate_swap_operations};
+use crate::state::{Config, CONFIG, PAIR_PROXY, ROUTES};
 use std::collections::HashMap;
 use std::convert::TryInto;
 
 use astroport::factory::PairType;
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
-    Isqrt, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
+    attr, entry_point, to_binary, Addr, Api, Attribute, Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
+    Isqrt, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
 };
 use cw20::Expiration;
-use spectrum::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw_storage_plus::Bound;
+use spectrum::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RouteResponse};
 
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
-use spectrum::adapters::asset::AssetEx;
+use spectrum::adapters::asset::{transfer_multi_msg, AssetEx};
 use spectrum::adapters::pair::Pair;
+use spectrum::adapters::router::{Router, SwapOperation};
 
 /// Scaling denominator for commission
 const COMMISSION_DENOM: u64 = 10000u64;
@@ -22,6 +24,12 @@ const COMMISSION_DENOM: u64 = 10000u64;
 /// Maximum spread percentage when swapping
 const MAX_SPREAD: u64 = 50; // 50%
 
+/// Custom pair type of the concentrated liquidity pair
+const CONCENTRATED_PAIR_TYPE: &str = "concentrated";
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 /// ## Description
 /// Validates that commission bps must be less than or equal 10000
 fn validate_commission(commission_bps: u64) -> StdResult<u64> {
@@ -34,7 +42,7 @@ fn validate_commission(commission_bps: u64) -> StdResult<u64> {
 
 /// ## Description
 /// Validates that decimal value is in the range 0 to 1
-fn validate_percentage(value: Decimal, field: &str) -> StdResult<Decimal> {
+pub(crate) fn validate_percentage(value: Decimal, field: &str) -> StdResult<Decimal> {
     if value > Decimal::one() {
         Err(StdError::generic_err(field.to_string() + " must be 0 to 1"))
     } else {
@@ -54,13 +62,29 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     let commission_bps = validate_commission(msg.commission_bps)?;
     let slippage_tolerance = validate_percentage(msg.slippage_tolerance, "slippage_tolerance")?;
+    if msg.max_swaps == 0 {
+        return Err(StdError::generic_err("max_swaps must be greater than 0").into());
+    }
     let pair_contract = deps.api.addr_validate(&msg.pair_contract)?;
     let pair_info = Pair(pair_contract).query_pair_info(&deps.querier)?;
 
+    let router = match msg.router {
+        Some(router) => Some(Router(deps.api.addr_validate(&router)?)),
+        None => None,
+    };
+    let allowed_recipients = match msg.allowed_recipients {
+        Some(allowed_recipients) => validate_recipients(deps.api, allowed_recipients)?,
+        None => None,
+    };
+
     let config = Config {
         pair_info,
         commission_bps,
         slippage_tolerance,
+        max_swaps: msg.max_swaps,
+        owner: deps.api.addr_validate(&msg.owner)?,
+        router,
+        allowed_recipients,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -88,6 +112,10 @@ pub fn execute(
             to,
             no_swap,
             slippage_tolerance,
+            dust_to,
+            slippage_overrides,
+            refund_dust,
+            routes,
         } => {
             let to_addr = if let Some(to_addr) = to {
                 Some(deps.api.addr_validate(&to_addr)?)
@@ -102,14 +130,154 @@ pub fn execute(
                 to_addr,
                 no_swap,
                 slippage_tolerance,
+                dust_to,
+                slippage_overrides,
+                refund_dust,
+                routes,
+            )
+        }
+        ExecuteMsg::SwapTo {
+            rewards,
+            target,
+            to,
+            minimum_receive,
+            slippage_tolerance,
+        } => {
+            let to_addr = match to {
+                Some(to_addr) => Some(deps.api.addr_validate(&to_addr)?),
+                None => None,
+            };
+            swap_to(
+                deps,
+                env,
+                info,
+                rewards,
+                target,
+                to_addr,
+                minimum_receive,
+                slippage_tolerance,
             )
         }
+        ExecuteMsg::UpdateConfig {
+            owner,
+            router,
+            allowed_recipients,
+        } => update_config(deps, info, owner, router, allowed_recipients),
+        ExecuteMsg::UpdateRoute { asset_info, operations } => {
+            update_route(deps, info, asset_info, operations)
+        }
         ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
     }
 }
 
+/// ## Description
+/// Updates contract config. Only the owner can execute this.
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Option<String>,
+    router: Option<String>,
+    allowed_recipients: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+
+    if let Some(router) = router {
+        config.router = Some(Router(deps.api.addr_validate(&router)?));
+    }
+
+    if let Some(allowed_recipients) = allowed_recipients {
+        config.allowed_recipients = validate_recipients(deps.api, allowed_recipients)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Validates the allowed LP receivers, an empty list allows any address
+fn validate_recipients(api: &dyn Api, recipients: Vec<String>) -> StdResult<Option<Vec<Addr>>> {
+    if recipients.is_empty() {
+        return Ok(None);
+    }
+    recipients
+        .iter()
+        .map(|recipient| api.addr_validate(recipient))
+        .collect::<StdResult<Vec<_>>>()
+        .map(Some)
+}
+
+/// ## Description
+/// Sets or removes the multi-hop route of a reward asset. Only the owner can execute this.
+/// The route must start from the reward asset, be connected, and end at an asset in the pair.
+pub fn update_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    operations: Option<Vec<SwapOperation>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    asset_info.check(deps.api)?;
+    let key = asset_info.to_string();
+    match operations {
+        Some(operations) => {
+            if config.router.is_none() {
+                return Err(StdError::generic_err("router is not set").into());
+            }
+            validate_route(&config, &asset_info, &operations)?;
+            ROUTES.save(deps.storage, key, &operations)?;
+        }
+        None => ROUTES.remove(deps.storage, key),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_route")
+        .add_attribute("asset_info", asset_info.to_string()))
+}
+
+/// ## Description
+/// Validates that swap operations are connected from the reward asset to an asset in the pair
+pub(crate) fn validate_route(
+    config: &Config,
+    asset_info: &AssetInfo,
+    operations: &[SwapOperation],
+) -> StdResult<()> {
+    let (first, last) = match (operations.first(), operations.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(StdError::generic_err("operations must not be empty")),
+    };
+    if !first.get_offer_asset_info().equal(asset_info) {
+        return Err(StdError::generic_err("route must start from the reward asset"));
+    }
+    for window in operations.windows(2) {
+        if !window[0].get_ask_asset_info().equal(&window[1].get_offer_asset_info()) {
+            return Err(StdError::generic_err("route operations must be connected"));
+        }
+    }
+    let ask_asset_info = last.get_ask_asset_info();
+    if !config.pair_info.asset_infos.iter().any(|it| it.equal(&ask_asset_info)) {
+        return Err(StdError::generic_err("route must end at an asset in the pair"));
+    }
+    Ok(())
+}
+
 /// ## Description
 /// Performs rewards compounding to LP token. Sender must do token approval upon calling this function.
+/// Reward swaps use the max spread in **slippage_overrides** for their asset, otherwise [`MAX_SPREAD`].
+/// A route in **routes** is validated and used verbatim instead of the stored route or pair proxy.
 #[allow(clippy::too_many_arguments)]
 pub fn compound(
     deps: DepsMut,
@@ -119,20 +287,229 @@ pub fn compound(
     to: Option<Addr>,
     no_swap: Option<bool>,
     slippage_tolerance: Option<Decimal>,
+    dust_to: Option<u8>,
+    slippage_overrides: Option<Vec<(AssetInfo, Decimal)>>,
+    refund_dust: Option<bool>,
+    routes: Option<Vec<(AssetInfo, Vec<SwapOperation>)>>,
 ) -> Result<Response, ContractError> {
     let no_swap = no_swap.unwrap_or(false);
+    if let Some(dust_to) = dust_to {
+        if dust_to > 1 {
+            return Err(StdError::generic_err("dust_to must be 0 or 1").into());
+        }
+    }
+    let slippage_overrides = slippage_overrides
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(asset_info, slippage)| {
+            Ok((asset_info, validate_percentage(slippage, "slippage_overrides")?))
+        })
+        .collect::<StdResult<HashMap<_, _>>>()?;
+
+    let config = CONFIG.load(deps.storage)?;
+    validate_receiver(&config, &to)?;
+
+    let routes = routes.unwrap_or_default()
+        .into_iter()
+        .map(|(asset_info, operations)| {
+            validate_route(&config, &asset_info, &operations)?;
+            Ok((asset_info, operations))
+        })
+        .collect::<StdResult<HashMap<_, _>>>()?;
+    if !routes.is_empty() && config.router.is_none() {
+        return Err(StdError::generic_err("router is not set").into());
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![attr("action", "compound")];
+    let (native_reward_map, mut swaps) = swap_rewards(
+        deps.as_ref(),
+        &env,
+        &info,
+        &config,
+        rewards,
+        &slippage_overrides,
+        routes,
+        &mut messages,
+        &mut attributes,
+    )?;
+
+    if !no_swap {
+        messages.push(CallbackMsg::OptimalSwap { dust_to }.into_cosmos_msg(&env.contract.address)?);
+        swaps += 1;
+    }
+
+    if swaps > config.max_swaps {
+        return Err(ContractError::TooManySwaps {
+            max_swaps: config.max_swaps,
+            swaps,
+        });
+    }
+
+    let prev_balances = query_prev_balances(&deps.querier, &env, &config, &native_reward_map)?;
+
+    let receiver = to.unwrap_or(info.sender);
+    messages.push(
+        CallbackMsg::ProvideLiquidity {
+            prev_balances: prev_balances.clone(),
+            slippage_tolerance,
+            receiver: receiver.to_string(),
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+    if refund_dust.unwrap_or(false) {
+        messages.push(
+            CallbackMsg::RefundDust {
+                prev_balances,
+                receiver: receiver.to_string(),
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Swaps rewards to the target asset in the pair and transfers it to the receiver without providing liquidity.
+/// Sender must do token approval upon calling this function.
+/// Rewards are swapped to the pair by the same routes as compound, then the other pair asset is swapped to the target.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_to(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rewards: Vec<Asset>,
+    target: AssetInfo,
+    to: Option<Addr>,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    if let Some(slippage_tolerance) = slippage_tolerance {
+        validate_percentage(slippage_tolerance, "slippage_tolerance")?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    validate_receiver(&config, &to)?;
+    if !config.pair_info.asset_infos.iter().any(|it| it.equal(&target)) {
+        return Err(StdError::generic_err("target must be an asset in the pair").into());
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![attr("action", "swap_to")];
+    let (native_reward_map, swaps) = swap_rewards(
+        deps.as_ref(),
+        &env,
+        &info,
+        &config,
+        rewards,
+        &HashMap::new(),
+        HashMap::new(),
+        &mut messages,
+        &mut attributes,
+    )?;
+
+    // the swap from the other pair asset to the target
+    let swaps = swaps + 1;
+    if swaps > config.max_swaps {
+        return Err(ContractError::TooManySwaps {
+            max_swaps: config.max_swaps,
+            swaps,
+        });
+    }
+
+    let prev_balances = query_prev_balances(&deps.querier, &env, &config, &native_reward_map)?;
+    let prev_balance = prev_balances
+        .iter()
+        .find(|it| it.info.equal(&target))
+        .cloned()
+        .unwrap_or_else(|| target.with_balance(Uint128::zero()));
+
+    let receiver = to.unwrap_or(info.sender);
+    messages.push(
+        CallbackMsg::SwapToTarget {
+            prev_balances,
+            target: target.clone(),
+            slippage_tolerance,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+    messages.push(
+        CallbackMsg::TransferTarget {
+            prev_balance,
+            receiver: receiver.to_string(),
+            minimum_receive,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+    attributes.push(attr("target", target.to_string()));
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Validates that the receiver is allowed by config
+fn validate_receiver(config: &Config, to: &Option<Addr>) -> StdResult<()> {
+    if let (Some(to), Some(allowed_recipients)) = (to, &config.allowed_recipients) {
+        if !allowed_recipients.contains(to) {
+            return Err(StdError::generic_err(format!("recipient is not allowed: {}", to)));
+        }
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Deposits rewards and swaps each reward to an asset in the pair by the supplied route, the stored route or the pair proxy.
+/// Returns the native rewards deposited and the number of swap operations.
+#[allow(clippy::too_many_arguments)]
+fn swap_rewards(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    config: &Config,
+    rewards: Vec<Asset>,
+    slippage_overrides: &HashMap<AssetInfo, Decimal>,
+    mut routes: HashMap<AssetInfo, Vec<SwapOperation>>,
+    messages: &mut Vec<CosmosMsg>,
+    attributes: &mut Vec<Attribute>,
+) -> StdResult<(HashMap<AssetInfo, Uint128>, u64)> {
     let mut native_reward_map: HashMap<AssetInfo, Uint128> = HashMap::new();
+    let mut swaps = 0u64;
     // Swap reward to asset in the pair
     for reward in rewards {
-        reward.deposit_asset(&info, &env.contract.address, &mut messages)?;
+        reward.deposit_asset(info, &env.contract.address, messages)?;
 
+        // an override is checked against the pool price, instead of the unbounded belief price
+        let (belief_price, max_spread) = match slippage_overrides.get(&reward.info) {
+            Some(slippage) => (None, *slippage),
+            None => (Some(Decimal::MAX), Decimal::percent(MAX_SPREAD)),
+        };
+        let route = match routes.remove(&reward.info) {
+            Some(operations) => Some(operations),
+            None => ROUTES.may_load(deps.storage, reward.info.to_string())?,
+        };
         let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
-        if let Some(pair_proxy) = pair_proxy {
+        if let (Some(operations), Some(router)) = (route, &config.router) {
+            swaps += operations.len() as u64;
+            let swap_reward = router.execute_swap_operations_msg(
+                reward.clone(),
+                operations,
+                None,
+                None,
+                Some(max_spread),
+            )?;
+            messages.push(swap_reward);
+            attributes.push(attr("swap_slippage_tolerance", format!("{}:{}", reward.info, max_spread)));
+        } else if let Some(pair_proxy) = pair_proxy {
             let swap_reward =
-                pair_proxy.swap_msg(&reward, Some(Decimal::MAX), Some(Decimal::percent(MAX_SPREAD)), None)?;
+                pair_proxy.swap_msg(&reward, belief_price, Some(max_spread), None)?;
             messages.push(swap_reward);
+            attributes.push(attr("swap_slippage_tolerance", format!("{}:{}", reward.info, max_spread)));
+            swaps += 1;
         }
 
         if reward.is_native_token() {
@@ -140,15 +517,21 @@ pub fn compound(
         }
     }
 
-    if !no_swap {
-        messages.push(CallbackMsg::OptimalSwap {}.into_cosmos_msg(&env.contract.address)?);
-    }
+    Ok((native_reward_map, swaps))
+}
 
-    let config = CONFIG.load(deps.storage)?;
+/// ## Description
+/// Returns the balances of the pair assets before the native rewards were sent
+fn query_prev_balances(
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    native_reward_map: &HashMap<AssetInfo, Uint128>,
+) -> StdResult<Vec<Asset>> {
     let assets = config
         .pair_info
-        .query_pools(&deps.querier, &env.contract.address)?;
-    let prev_balances = assets
+        .query_pools(querier, &env.contract.address)?;
+    assets
         .iter()
         .map(|a| {
             let balance = a
@@ -156,21 +539,7 @@ pub fn compound(
                 .checked_sub(*native_reward_map.get(&a.info).unwrap_or(&Uint128::zero()))?;
             Ok(a.info.with_balance(balance))
         })
-        .collect::<StdResult<_>>()?;
-
-    let receiver = to.unwrap_or(info.sender);
-    messages.push(
-        CallbackMsg::ProvideLiquidity {
-            prev_balances,
-            slippage_tolerance,
-            receiver: receiver.to_string(),
-        }
-        .into_cosmos_msg(&env.contract.address)?,
-    );
-
-    Ok(Response::new()
-        .add_messages(messages)
-        .add_attribute("action", "compound"))
+        .collect()
 }
 
 /// # Description
@@ -186,26 +555,50 @@ pub fn handle_callback(
         return Err(ContractError::Unauthorized {});
     }
     match msg {
-        CallbackMsg::OptimalSwap {} => optimal_swap(deps, env, info),
+        CallbackMsg::OptimalSwap { dust_to } => optimal_swap(deps, env, info, dust_to),
         CallbackMsg::ProvideLiquidity {
             prev_balances,
             slippage_tolerance,
             receiver,
         } => provide_liquidity(deps, env, info, prev_balances, receiver, slippage_tolerance),
+        CallbackMsg::RefundDust {
+            prev_balances,
+            receiver,
+        } => refund_dust(deps, env, prev_balances, receiver),
+        CallbackMsg::SwapToTarget {
+            prev_balances,
+            target,
+            slippage_tolerance,
+        } => swap_to_target(deps, env, prev_balances, target, slippage_tolerance),
+        CallbackMsg::TransferTarget {
+            prev_balance,
+            receiver,
+            minimum_receive,
+        } => transfer_target(deps, env, prev_balance, receiver, minimum_receive),
     }
 }
 
 /// # Description
 /// Performs optimal swap of assets in the pair contract.
-fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+fn optimal_swap(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    dust_to: Option<u8>,
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    match config.pair_info.pair_type {
+    // the optimal swap assumes a constant product pool, the pool may have been migrated since instantiate
+    let pair_type = Pair(config.pair_info.contract_addr.clone()).query_pair_type(&deps.querier)?;
+    match pair_type {
         PairType::Stable {} => {
             //Do nothing for stable pair
         }
+        PairType::Custom(pair_type) if pair_type == CONCENTRATED_PAIR_TYPE => {
+            //Do nothing for concentrated pair
+        }
         _ => {
             let assets = config
                 .pair_info
@@ -218,6 +611,7 @@ fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response,
                     &config,
                     asset_a,
                     asset_b,
+                    dust_to,
                     &mut messages,
                 )?;
             }
@@ -232,11 +626,13 @@ fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response,
 /// # Description
 /// Calculates the amount of asset in the pair contract that need to be swapped before providing liquidity.
 /// The swap messages will be added to **messages**.
+/// If **dust_to** is set, the swap amount is adjusted so the remainder is left on that side, see [`adjust_swap_for_dust`].
 pub fn calculate_optimal_swap(
     querier: &QuerierWrapper,
     config: &Config,
     asset_a: Asset,
     asset_b: Asset,
+    dust_to: Option<u8>,
     messages: &mut Vec<CosmosMsg>,
 ) -> StdResult<(Uint128, Uint128, Uint128, Uint128)> {
     let mut swap_asset_a_amount = Uint128::zero();
@@ -257,13 +653,24 @@ pub fn calculate_optimal_swap(
 
     #[allow(clippy::comparison_chain)]
     if provide_a_area > provide_b_area {
-        let swap_amount = get_swap_amount(
+        let mut swap_amount = get_swap_amount(
             provide_a_amount,
             provide_b_amount,
             pool_a_amount,
             pool_b_amount,
             config.commission_bps,
         )?;
+        if let (Some(dust_to), false) = (dust_to, swap_amount.is_zero()) {
+            swap_amount = adjust_swap_for_dust(
+                provide_a_amount,
+                provide_b_amount,
+                pool_a_amount,
+                pool_b_amount,
+                swap_amount,
+                config.commission_bps,
+                dust_to == 1,
+            )?;
+        }
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
                 info: asset_a.info,
@@ -286,13 +693,24 @@ pub fn calculate_optimal_swap(
             }
         }
     } else if provide_a_area < provide_b_area {
-        let swap_amount = get_swap_amount(
+        let mut swap_amount = get_swap_amount(
             provide_b_amount,
             provide_a_amount,
             pool_b_amount,
             pool_a_amount,
             config.commission_bps,
         )?;
+        if let (Some(dust_to), false) = (dust_to, swap_amount.is_zero()) {
+            swap_amount = adjust_swap_for_dust(
+                provide_b_amount,
+                provide_a_amount,
+                pool_b_amount,
+                pool_a_amount,
+                swap_amount,
+                config.commission_bps,
+                dust_to == 0,
+            )?;
+        }
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
                 info: asset_b.info,
@@ -387,6 +805,122 @@ pub fn provide_liquidity(
         .add_attribute("receiver", receiver))
 }
 
+/// ## Description
+/// Returns the pair assets received during compound and left after providing liquidity.
+fn refund_dust(
+    deps: DepsMut,
+    env: Env,
+    prev_balances: Vec<Asset>,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    let assets = config
+        .pair_info
+        .query_pools(&deps.querier, env.contract.address)?;
+
+    let prev_balance_map: HashMap<_, _> = prev_balances
+        .into_iter()
+        .map(|a| (a.info, a.amount))
+        .collect();
+
+    let mut dust: Vec<Asset> = vec![];
+    for asset in assets {
+        let prev_balance = *prev_balance_map
+            .get(&asset.info)
+            .unwrap_or(&Uint128::zero());
+        let amount = asset.amount.checked_sub(prev_balance)?;
+        dust.push(asset.info.with_balance(amount));
+    }
+    let messages = transfer_multi_msg(&dust, &receiver)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "refund_dust")
+        .add_attribute("receiver", receiver))
+}
+
+/// ## Description
+/// Swaps the other pair asset received during swap_to to the target asset in the pair contract.
+fn swap_to_target(
+    deps: DepsMut,
+    env: Env,
+    prev_balances: Vec<Asset>,
+    target: AssetInfo,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    let assets = config
+        .pair_info
+        .query_pools(&deps.querier, env.contract.address)?;
+
+    let prev_balance_map: HashMap<_, _> = prev_balances
+        .into_iter()
+        .map(|a| (a.info, a.amount))
+        .collect();
+
+    let max_spread = slippage_tolerance.unwrap_or(config.slippage_tolerance);
+    let pair = Pair(config.pair_info.contract_addr);
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for asset in assets {
+        if asset.info.equal(&target) {
+            continue;
+        }
+        let prev_balance = *prev_balance_map
+            .get(&asset.info)
+            .unwrap_or(&Uint128::zero());
+        let amount = asset.amount.checked_sub(prev_balance)?;
+        if !amount.is_zero() {
+            messages.push(pair.swap_msg(
+                &asset.info.with_balance(amount),
+                None,
+                Some(max_spread),
+                None,
+            )?);
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "swap_to_target"))
+}
+
+/// ## Description
+/// Transfers the target asset received during swap_to to the receiver.
+/// Returns a [`ContractError::MinimumReceive`] if the amount is less than **minimum_receive**.
+fn transfer_target(
+    deps: DepsMut,
+    env: Env,
+    prev_balance: Asset,
+    receiver: String,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let receiver = deps.api.addr_validate(&receiver)?;
+
+    let balance = prev_balance
+        .info
+        .query_pool(&deps.querier, env.contract.address)?;
+    let amount = balance.checked_sub(prev_balance.amount)?;
+    if let Some(minimum_receive) = minimum_receive {
+        if amount < minimum_receive {
+            return Err(ContractError::MinimumReceive {
+                minimum_receive,
+                amount,
+            });
+        }
+    }
+
+    let messages = transfer_multi_msg(&[prev_balance.info.with_balance(amount)], &receiver)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "transfer_target")
+        .add_attribute("receiver", receiver)
+        .add_attribute("amount", amount))
+}
+
 /// Calculate swap amount
 pub(crate) fn get_swap_amount(
     amount_a: Uint256,
@@ -410,6 +944,57 @@ pub(crate) fn get_swap_amount(
         .map_err(|_| StdError::generic_err("overflow"))
 }
 
+/// ## Description
+/// Returns the swap amount adjusted so the remainder of providing the balances left after the
+/// swap is on the ask side when **dust_to_ask** is set, or on the offer side otherwise.
+/// The remainder is checked against the balances and the pool after the swap, and the closest
+/// such amount is taken. The remainder only comes from rounding, so the swap amount is moved
+/// one unit at a time.
+fn adjust_swap_for_dust(
+    offer_amount: Uint256,
+    ask_amount: Uint256,
+    offer_pool: Uint256,
+    ask_pool: Uint256,
+    swap_amount: Uint128,
+    commission_bps: u64,
+    dust_to_ask: bool,
+) -> StdResult<Uint128> {
+    let commission_rate = Decimal256::from_ratio(commission_bps, COMMISSION_DENOM);
+    let offer_limit: Uint128 = offer_amount
+        .try_into()
+        .map_err(|_| StdError::generic_err("overflow"))?;
+
+    // returns (offer remainder exists, ask remainder exists) after swapping swap_amount
+    let remainder = |swap_amount: Uint128| -> StdResult<(bool, bool)> {
+        let return_amount: Uint256 =
+            simulate(offer_pool, ask_pool, swap_amount.into(), commission_rate)?.into();
+        let swap_amount: Uint256 = swap_amount.into();
+        let remaining_offer = (offer_amount - swap_amount) * (ask_pool - return_amount);
+        let remaining_ask = (ask_amount + return_amount) * (offer_pool + swap_amount);
+        Ok((remaining_offer > remaining_ask, remaining_ask > remaining_offer))
+    };
+
+    let one = Uint128::from(1u128);
+    let mut swap_amount = swap_amount;
+    if dust_to_ask {
+        while swap_amount < offer_limit && remainder(swap_amount)?.0 {
+            swap_amount += one;
+        }
+        while !swap_amount.is_zero() && !remainder(swap_amount - one)?.0 {
+            swap_amount -= one;
+        }
+    } else {
+        while !swap_amount.is_zero() && remainder(swap_amount)?.1 {
+            swap_amount -= one;
+        }
+        while swap_amount < offer_limit && !remainder(swap_amount + one)?.1 {
+            swap_amount += one;
+        }
+    }
+
+    Ok(swap_amount)
+}
+
 /// Simulates return amount from the swap
 fn simulate(
     offer_pool: Uint256,
@@ -441,15 +1026,54 @@ fn simulate(
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::CompoundSimulation { rewards } => {
-            to_binary(&query_compound_simulation(deps, rewards)?)
+        QueryMsg::CompoundSimulation { rewards, slippage_tolerance, no_swap } => {
+            to_binary(&query_compound_simulation(deps, rewards, slippage_tolerance, no_swap)?)
+        }
+        QueryMsg::Routes { start_after, limit } => {
+            to_binary(&query_routes(deps, start_after, limit)?)
+        }
+        QueryMsg::Route { asset_info } => to_binary(&query_route(deps, asset_info)?),
+        QueryMsg::SimulateSwapOperations { offer_amount, operations } => {
+            to_binary(&query_simulate_swap_operations(deps, offer_amount, operations)?)
         }
     }
 }
 
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
+/// Returns the multi-hop route of a reward asset
+fn query_route(deps: Deps, asset_info: AssetInfo) -> StdResult<RouteResponse> {
+    let key = asset_info.to_string();
+    Ok(RouteResponse {
+        operations: ROUTES.load(deps.storage, key.clone())?,
+        asset_info: key,
+    })
+}
+
+/// ## Description
+/// Returns the multi-hop routes by reward asset
+fn query_routes(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Vec<SwapOperation>)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    ROUTES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+/// ## Description
+/// Used for migration of contract. Sets the owner if given. Returns the default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if let Some(owner) = msg.owner {
+        let mut config = CONFIG.load(deps.storage)?;
+        config.owner = deps.api.addr_validate(&owner)?;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
     Ok(Response::default())
 }
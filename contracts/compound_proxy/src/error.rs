@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -10,6 +10,12 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Too many swap operations; maximum: {max_swaps}, actual: {swaps}")]
+    TooManySwaps { max_swaps: u64, swaps: u64 },
+
+    #[error("Received less than minimum_receive; minimum: {minimum_receive}, actual: {amount}")]
+    MinimumReceive { minimum_receive: Uint128, amount: Uint128 },
 }
 
 impl From<OverflowError> for ContractError {
@@ -11,6 +11,7 @@ use astroport::factory::QueryMsg::FeeInfo;
 use astroport::pair::QueryMsg::{Pair, Simulation};
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 use astroport::pair::SimulationResponse;
+use spectrum::adapters::router::{QueryMsg as RouterQueryMsg, SimulateSwapOperationsResponse};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -114,6 +115,26 @@ impl WasmMockQuerier {
                         ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "pair_contract_concentrated" {
+                    match from_binary(msg).unwrap() {
+                        Pair { .. } => SystemResult::Ok(
+                            to_binary(&PairInfo {
+                                asset_infos: vec![
+                                    {
+                                        AssetInfo::Token { contract_addr: Addr::unchecked("token") }
+                                    },
+                                    {
+                                        AssetInfo::NativeToken { denom: "uluna".to_string() }
+                                    },
+                                ],
+                                contract_addr: Addr::unchecked("pair_contract_concentrated"),
+                                liquidity_token: Addr::unchecked("liquidity_token"),
+                                pair_type: astroport::factory::PairType::Custom("concentrated".to_string()),
+                            })
+                            .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else if contract_addr == "pair_contract_2" {
                     match from_binary(&msg).unwrap() {
                         Pair { .. } => SystemResult::Ok(
@@ -160,6 +181,16 @@ impl WasmMockQuerier {
                         ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "router" {
+                    match from_binary(msg).unwrap() {
+                        // each hop doubles the offer amount
+                        RouterQueryMsg::SimulateSwapOperations { offer_amount, operations } => SystemResult::Ok(
+                            to_binary(&SimulateSwapOperationsResponse {
+                                amount: offer_amount * Uint128::from(1u128 << operations.len()),
+                            }).into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else {
                     match from_binary(&msg).unwrap() {
                         Cw20QueryMsg::TokenInfo {} => {
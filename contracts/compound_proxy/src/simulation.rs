@@ -1,15 +1,16 @@
 use std::cmp::Ordering;
 
 use crate::{
-    contract::calculate_optimal_swap,
-    state::{Config, CONFIG, PAIR_PROXY},
+    contract::{calculate_optimal_swap, validate_percentage, validate_route},
+    state::{Config, CONFIG, PAIR_PROXY, ROUTES},
 };
 use astroport::{asset::AssetInfo, pair::StablePoolConfig, querier::query_token_precision, U256};
 
 use astroport::querier::query_supply;
-use cosmwasm_std::{from_binary, CosmosMsg, Deps, StdError, StdResult, Uint128};
+use cosmwasm_std::{from_binary, CosmosMsg, Decimal, Deps, StdError, StdResult, Uint128};
 
-use spectrum::compound_proxy::CompoundSimulationResponse;
+use spectrum::adapters::router::SwapOperation;
+use spectrum::compound_proxy::{CompoundSimulationResponse, SwapOperationsSimulationResponse};
 
 use astroport::asset::{Asset, AssetInfoExt};
 use astroport::factory::PairType;
@@ -20,21 +21,68 @@ const ITERATIONS: u8 = 32;
 const N_COINS: u8 = 2;
 const AMP_PRECISION: u64 = 100;
 
+/// ## Description
+/// Returns simulated amount of the swap operations in a [`SwapOperationsSimulationResponse`].
+/// The operations are validated the same as a stored route and simulated hop by hop on the router.
+pub fn query_simulate_swap_operations(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> StdResult<SwapOperationsSimulationResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let router = config
+        .router
+        .as_ref()
+        .ok_or_else(|| StdError::generic_err("router is not set"))?;
+    let offer_asset_info = operations
+        .first()
+        .ok_or_else(|| StdError::generic_err("operations must not be empty"))?
+        .get_offer_asset_info();
+    validate_route(&config, &offer_asset_info, &operations)?;
+
+    let mut amount = offer_amount;
+    let mut hop_amounts: Vec<Uint128> = vec![];
+    for operation in operations {
+        amount = router.simulate(&deps.querier, amount, vec![operation])?.amount;
+        hop_amounts.push(amount);
+    }
+
+    Ok(SwapOperationsSimulationResponse {
+        amount,
+        hop_amounts,
+    })
+}
+
 /// ## Description
 /// Returns simulated amount of LP token from given rewards in a [`CompoundSimulationResponse`].
+/// The swap amounts are planned by [`calculate_optimal_swap`], the same as compound, unless **no_swap** is set.
 pub fn query_compound_simulation(
     deps: Deps,
     rewards: Vec<Asset>,
+    slippage_tolerance: Option<Decimal>,
+    no_swap: Option<bool>,
 ) -> StdResult<CompoundSimulationResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let slippage_tolerance = validate_percentage(
+        slippage_tolerance.unwrap_or(config.slippage_tolerance),
+        "slippage_tolerance",
+    )?;
     let asset_a_info = config.pair_info.asset_infos[0].clone();
     let asset_b_info = config.pair_info.asset_infos[1].clone();
     let mut asset_a_amount = Uint128::zero();
     let mut asset_b_amount = Uint128::zero();
 
     for reward in rewards {
+        let route = ROUTES.may_load(deps.storage, reward.info.to_string())?;
         let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
-        let add_asset = if let Some(pair_proxy) = pair_proxy {
+        let add_asset = if let (Some(operations), Some(router)) = (route, &config.router) {
+            let return_asset_info = operations
+                .last()
+                .ok_or_else(|| StdError::generic_err("Invalid route"))?
+                .get_ask_asset_info();
+            let simulation_response = router.simulate(&deps.querier, reward.amount, operations)?;
+            return_asset_info.with_balance(simulation_response.amount)
+        } else if let Some(pair_proxy) = pair_proxy {
             let simulation_response = pair_proxy.simulate(&deps.querier, &reward, None)?;
             let pair_proxy_info = pair_proxy.query_pair_info(&deps.querier)?;
             let return_asset_info = if reward.info.equal(&pair_proxy_info.asset_infos[0]) {
@@ -64,8 +112,9 @@ pub fn query_compound_simulation(
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
+    // the pool may have been migrated since instantiate, same as the optimal swap
     let (lp_amount, swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
-        match config.pair_info.pair_type {
+        match pair.query_pair_type(&deps.querier)? {
             PairType::Xyk {} => {
                 let asset_a = Asset {
                     info: asset_a_info,
@@ -77,13 +126,18 @@ pub fn query_compound_simulation(
                 };
                 let mut _messages: Vec<CosmosMsg> = vec![];
                 let (swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
-                    calculate_optimal_swap(
-                        &deps.querier,
-                        &config,
-                        asset_a,
-                        asset_b,
-                        &mut _messages,
-                    )?;
+                    if no_swap.unwrap_or(false) {
+                        (Uint128::zero(), Uint128::zero(), Uint128::zero(), Uint128::zero())
+                    } else {
+                        calculate_optimal_swap(
+                            &deps.querier,
+                            &config,
+                            asset_a,
+                            asset_b,
+                            None,
+                            &mut _messages,
+                        )?
+                    };
 
                 if !swap_asset_a_amount.is_zero() {
                     asset_a_amount -= swap_asset_a_amount;
@@ -197,6 +251,7 @@ pub fn query_compound_simulation(
 
     Ok(CompoundSimulationResponse {
         lp_amount,
+        minimum_receive: lp_amount * (Decimal::one() - slippage_tolerance),
         swap_asset_a_amount,
         swap_asset_b_amount,
         return_a_amount,
@@ -1,9 +1,10 @@
 use astroport::asset::{PairInfo};
-use cosmwasm_std::{Decimal};
+use cosmwasm_std::{Addr, Decimal};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use spectrum::adapters::pair::Pair;
+use spectrum::adapters::router::{Router, SwapOperation};
 
 /// This structure describes the main control config of pair.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -14,6 +15,26 @@ pub struct Config {
     pub commission_bps: u64,
     /// The slippage tolerance when providing liquidity
     pub slippage_tolerance: Decimal,
+    /// The maximum number of swap operations per compound
+    #[serde(default = "default_max_swaps")]
+    pub max_swaps: u64,
+    /// The owner address to update routes
+    #[serde(default = "default_owner")]
+    pub owner: Addr,
+    /// The router contract address to execute multi-hop routes
+    #[serde(default)]
+    pub router: Option<Router>,
+    /// The only addresses allowed as the LP receiver of compound, any address if not set
+    #[serde(default)]
+    pub allowed_recipients: Option<Vec<Addr>>,
+}
+
+fn default_max_swaps() -> u64 {
+    10
+}
+
+fn default_owner() -> Addr {
+    Addr::unchecked("")
 }
 
 /// Stores config at the given key
@@ -21,3 +42,6 @@ pub const CONFIG: Item<Config> = Item::new("config");
 
 /// Stores pair proxy for the given reward
 pub const PAIR_PROXY: Map<String, Pair> = Map::new("pair_proxy");
+
+/// Stores multi-hop route for the given reward
+pub const ROUTES: Map<String, Vec<SwapOperation>> = Map::new("routes");
@@ -1,17 +1,19 @@
-use astroport::asset::{Asset, AssetInfo, PairInfo, native_asset, token_asset};
+use astroport::asset::{Asset, AssetInfo, PairInfo, native_asset, native_asset_info, token_asset, token_asset_info};
+use astroport::factory::PairType;
 use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg,
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{coin, to_binary, Addr, Coin, CosmosMsg, Decimal, Order, StdResult, Uint128, WasmMsg, from_binary, Uint256};
+use cosmwasm_std::{attr, coin, to_binary, BankMsg, Addr, Coin, CosmosMsg, Decimal, Order, StdError, StdResult, Uint128, WasmMsg, from_binary, Uint256};
 use cw20::{Cw20ExecuteMsg};
 use spectrum::adapters::pair::Pair;
-use spectrum::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use spectrum::adapters::router::{Router, SwapOperation};
+use spectrum::compound_proxy::{CallbackMsg, CompoundSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RouteResponse, SwapOperationsSimulationResponse};
 
-use crate::contract::{execute, get_swap_amount, instantiate, query};
+use crate::contract::{execute, get_swap_amount, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{Config, PAIR_PROXY};
+use crate::state::{Config, CONFIG, PAIR_PROXY};
 
 #[test]
 fn proper_initialization() -> StdResult<()> {
@@ -35,6 +37,10 @@ fn proper_initialization() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
     };
 
     let sender = "addr0000";
@@ -81,6 +87,46 @@ fn proper_initialization() -> StdResult<()> {
     Ok(())
 }
 
+#[test]
+fn migrate_owner() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg)?;
+
+    // config stored before the owner was added has no owner until migrate sets it
+    let mut config = CONFIG.load(deps.as_ref().storage)?;
+    config.owner = Addr::unchecked("");
+    CONFIG.save(deps.as_mut().storage, &config)?;
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        router: None,
+        allowed_recipients: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: Some("owner".to_string()) })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked("owner"));
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg)?;
+
+    // migrate without owner keeps the owner
+    migrate(deps.as_mut(), env, MigrateMsg { owner: None })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked("owner"));
+
+    Ok(())
+}
+
 #[test]
 fn compound() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
@@ -90,6 +136,10 @@ fn compound() -> Result<(), ContractError> {
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
     };
 
     let sender = "addr0000";
@@ -117,6 +167,10 @@ fn compound() -> Result<(), ContractError> {
         to: None,
         no_swap: None,
         slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
     };
 
     let env = mock_env();
@@ -139,7 +193,7 @@ fn compound() -> Result<(), ContractError> {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
                 msg: to_binary(&ExecuteMsg::Callback {
-                    0: CallbackMsg::OptimalSwap {}
+                    0: CallbackMsg::OptimalSwap { dust_to: None }
                 })?,
             }),
             CosmosMsg::Wasm(WasmMsg::Execute {
@@ -183,6 +237,10 @@ fn compound() -> Result<(), ContractError> {
         to: None,
         no_swap: Some(true),
         slippage_tolerance: Some(Decimal::percent(2)),
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg)?;
     assert_eq!(
@@ -211,6 +269,144 @@ fn compound() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn compound_max_swaps() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token0001"),
+                },
+                "pair0001".to_string(),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token0002"),
+                },
+                "pair0002".to_string(),
+            ),
+        ],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 0,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(StdError::generic_err("max_swaps must be greater than 0").into()));
+
+    let msg = InstantiateMsg {
+        max_swaps: 2,
+        ..msg
+    };
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    // 2 reward swaps + optimal swap
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![
+            token_asset(Addr::unchecked("token0001"), Uint128::from(100u128)),
+            token_asset(Addr::unchecked("token0002"), Uint128::from(100u128)),
+        ],
+        to: None,
+        no_swap: None,
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::TooManySwaps { max_swaps: 2, swaps: 3 }));
+
+    // without optimal swap
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![
+            token_asset(Addr::unchecked("token0001"), Uint128::from(100u128)),
+            token_asset(Addr::unchecked("token0002"), Uint128::from(100u128)),
+        ],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn compound_allowed_recipients() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token0001"),
+                },
+                "pair0001".to_string(),
+            ),
+        ],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: Some(vec!["farm".to_string()]),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let compound_msg = |to: Option<&str>| ExecuteMsg::Compound {
+        rewards: vec![token_asset(Addr::unchecked("token0001"), Uint128::from(100u128))],
+        to: to.map(|it| it.to_string()),
+        no_swap: None,
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
+    };
+
+    // rejected before any swap is built
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), compound_msg(Some("other")));
+    assert_eq!(res, Err(StdError::generic_err("recipient is not allowed: other").into()));
+
+    execute(deps.as_mut(), env.clone(), info.clone(), compound_msg(Some("farm")))?;
+    execute(deps.as_mut(), env.clone(), info.clone(), compound_msg(None))?;
+
+    // an empty list allows any address
+    let msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        router: None,
+        allowed_recipients: Some(vec![]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg)?;
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.allowed_recipients, None);
+
+    execute(deps.as_mut(), env, info, compound_msg(Some("other")))?;
+
+    Ok(())
+}
+
 #[test]
 fn optimal_swap() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
@@ -236,6 +432,10 @@ fn optimal_swap() -> Result<(), ContractError> {
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -244,7 +444,7 @@ fn optimal_swap() -> Result<(), ContractError> {
     assert!(res.is_ok());
 
     let msg = ExecuteMsg::Callback {
-        0: CallbackMsg::OptimalSwap {},
+        0: CallbackMsg::OptimalSwap { dust_to: None },
     };
 
     let res = execute(deps.as_mut(), env.clone().clone(), info, msg.clone());
@@ -277,6 +477,224 @@ fn optimal_swap() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn optimal_swap_concentrated() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        &String::from("pair_contract_concentrated"),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000000000),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("token"),
+            &[
+                (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+                (&String::from("pair_contract_concentrated"), &Uint128::new(1000000000)),
+            ],
+        ),
+        (
+            &String::from("liquidity_token"),
+            &[
+                (&String::from("xxxx"), &Uint128::new(1000000000)),
+            ],
+        ),
+    ]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract_concentrated".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    assert_eq!(
+        Pair(Addr::unchecked("pair_contract_concentrated")).query_pair_type(&deps.as_ref().querier)?,
+        PairType::Custom("concentrated".to_string())
+    );
+
+    // the xyk split is skipped
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { dust_to: None });
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert!(res.messages.is_empty());
+
+    // simulation follows the queried pair type, not the one cached at instantiate
+    let mut config = CONFIG.load(deps.as_ref().storage)?;
+    config.pair_info.pair_type = PairType::Xyk {};
+    CONFIG.save(deps.as_mut().storage, &config)?;
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::from(100u128))],
+        slippage_tolerance: None,
+        no_swap: None,
+    };
+    let res = query(deps.as_ref(), env, msg);
+    assert_eq!(res, Err(StdError::generic_err("Custom pair type not supported")));
+
+    Ok(())
+}
+
+#[test]
+fn optimal_swap_dust_to() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        &String::from("pair_contract"),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000000000),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+            (&String::from("pair_contract"), &Uint128::new(1000000000)),
+        ],
+    )]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    // invalid asset index
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![],
+        to: None,
+        no_swap: None,
+        slippage_tolerance: None,
+        dust_to: Some(2),
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(ContractError::Std(StdError::generic_err("dust_to must be 0 or 1"))));
+
+    // dust stays on the swapped side
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { dust_to: Some(0) });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_contract".to_string(),
+                amount: Uint128::new(500626),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::MAX),
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                })?
+            })?,
+        }),]
+    );
+
+    // dust is swapped to the other side
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { dust_to: Some(1) });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_contract".to_string(),
+                amount: Uint128::new(500627),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::MAX),
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                })?
+            })?,
+        }),]
+    );
+
+    // small pool, the rounded swap amount is off by more than one unit
+    deps.querier.with_balance(&[
+        (
+            &String::from("pair_contract"),
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1000000),
+            }],
+        ),
+        (
+            &String::from(MOCK_CONTRACT_ADDR),
+            &[Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        ),
+    ]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+            (&String::from("pair_contract"), &Uint128::new(1000000)),
+        ],
+    )]);
+    for (dust_to, amount) in [(0u8, 414385u128), (1u8, 414386u128)] {
+        let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { dust_to: Some(dust_to) });
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|it| it.msg)
+                .collect::<Vec<CosmosMsg>>(),
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair_contract".to_string(),
+                    amount: Uint128::new(amount),
+                    msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                        ask_asset_info: None,
+                        belief_price: Some(Decimal::MAX),
+                        max_spread: Some(Decimal::percent(50)),
+                        to: None,
+                    })?
+                })?,
+            }),]
+        );
+    }
+
+    Ok(())
+}
+
 #[test]
 fn provide_liquidity() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
@@ -316,6 +734,10 @@ fn provide_liquidity() -> Result<(), ContractError> {
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -497,6 +919,10 @@ fn test_compound_simulation() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
     };
 
     let sender = "addr0000";
@@ -510,9 +936,792 @@ fn test_compound_simulation() -> StdResult<()> {
         rewards: vec![
             token_asset(Addr::unchecked("astro"), Uint128::from(100u128)),
         ],
+        slippage_tolerance: None,
+        no_swap: None,
     };
     let res = query(deps.as_ref(), env.clone(), msg);
     assert!(res.is_ok());
 
     Ok(())
 }
+
+#[test]
+fn compound_simulation_matches_optimal_swap() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        &String::from("pair_contract"),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000000000),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("token"),
+            &[
+                (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+                (&String::from("pair_contract"), &Uint128::new(1000000000)),
+            ],
+        ),
+        (
+            &String::from("liquidity_token"),
+            &[
+                (&String::from("xxxx"), &Uint128::new(1000000000)),
+            ],
+        ),
+    ]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::new(1000000))],
+        slippage_tolerance: Some(Decimal::percent(101)),
+        no_swap: None,
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("slippage_tolerance must be 0 to 1")));
+
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::new(1000000))],
+        slippage_tolerance: Some(Decimal::percent(2)),
+        no_swap: None,
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+
+    // the simulated split is the swap executed by compound
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { dust_to: None });
+    let swap = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        swap.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_contract".to_string(),
+                amount: res.swap_asset_a_amount,
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::MAX),
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
+    // the remainder and the swap return are provided to the pool after the swap
+    assert_eq!(res.return_b_amount, Uint128::new(498874));
+    assert_eq!(res.lp_amount, Uint128::new(499122));
+    assert_eq!(res.minimum_receive, res.lp_amount * Decimal::percent(98));
+
+    // without the swap, the single sided reward provides no LP
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::new(1000000))],
+        slippage_tolerance: None,
+        no_swap: Some(true),
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.swap_asset_a_amount, Uint128::zero());
+    assert_eq!(res.swap_asset_b_amount, Uint128::zero());
+    assert_eq!(res.lp_amount, Uint128::zero());
+
+    Ok(())
+}
+
+#[test]
+fn compound_route() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![(
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("token0001"),
+            },
+            "pair0001".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: Some("router".to_string()),
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let reward_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0001"),
+    };
+    let mid_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0002"),
+    };
+    let pair_asset_info = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: reward_info.clone(),
+            ask_asset_info: mid_info.clone(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: mid_info.clone(),
+            ask_asset_info: pair_asset_info.clone(),
+        },
+    ];
+
+    // only owner can update route
+    let msg = ExecuteMsg::UpdateRoute {
+        asset_info: reward_info.clone(),
+        operations: Some(operations.clone()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // invalid routes
+    let owner_info = mock_info("owner", &[]);
+    for (operations, error) in [
+        (vec![], "operations must not be empty"),
+        (operations[1..].to_vec(), "route must start from the reward asset"),
+        (operations[..1].to_vec(), "route must end at an asset in the pair"),
+        (vec![operations[0].clone(), operations[0].clone()], "route operations must be connected"),
+    ] {
+        let msg = ExecuteMsg::UpdateRoute {
+            asset_info: reward_info.clone(),
+            operations: Some(operations),
+            };
+        let res = execute(deps.as_mut(), env.clone(), owner_info.clone(), msg);
+        assert_eq!(res, Err(StdError::generic_err(error).into()));
+    }
+
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg)?;
+
+    let msg = QueryMsg::Routes {
+        start_after: None,
+        limit: None,
+    };
+    let routes: Vec<(String, Vec<SwapOperation>)> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(routes, vec![("token0001".to_string(), operations.clone())]);
+
+    // paginate over a second route
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), ExecuteMsg::UpdateRoute {
+        asset_info: mid_info.clone(),
+        operations: Some(operations[1..].to_vec()),
+    })?;
+    let msg = QueryMsg::Routes {
+        start_after: None,
+        limit: Some(1),
+    };
+    let routes: Vec<(String, Vec<SwapOperation>)> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(routes, vec![("token0001".to_string(), operations.clone())]);
+    let msg = QueryMsg::Routes {
+        start_after: Some("token0001".to_string()),
+        limit: Some(1),
+    };
+    let routes: Vec<(String, Vec<SwapOperation>)> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(routes, vec![("token0002".to_string(), operations[1..].to_vec())]);
+
+    let msg = QueryMsg::Route {
+        asset_info: mid_info.clone(),
+    };
+    let route: RouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(route, RouteResponse {
+        asset_info: "token0002".to_string(),
+        operations: operations[1..].to_vec(),
+    });
+
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), ExecuteMsg::UpdateRoute {
+        asset_info: mid_info.clone(),
+        operations: None,
+    })?;
+
+    // route takes precedence over pair proxy, each hop counts as a swap
+    let reward = token_asset(Addr::unchecked("token0001"), Uint128::from(100u128));
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![reward.clone()],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages[1].msg,
+        Router(Addr::unchecked("router")).execute_swap_operations_msg(
+            reward.clone(),
+            operations,
+            None,
+            None,
+            Some(Decimal::percent(50)),
+        )?
+    );
+
+    // remove route, falls back to pair proxy
+    execute(deps.as_mut(), env.clone(), owner_info, ExecuteMsg::UpdateRoute {
+        asset_info: reward_info,
+        operations: None,
+    })?;
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages[1].msg,
+        Pair(Addr::unchecked("pair0001")).swap_msg(
+            &reward,
+            Some(Decimal::MAX),
+            Some(Decimal::percent(50)),
+            None,
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_supplied_routes() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![(
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("token0001"),
+            },
+            "pair0001".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: Some("router".to_string()),
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let reward_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0001"),
+    };
+    let mid_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0002"),
+    };
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: reward_info.clone(),
+            ask_asset_info: mid_info.clone(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: mid_info,
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+    ];
+
+    // supplied route is used instead of the pair proxy
+    let reward = token_asset(Addr::unchecked("token0001"), Uint128::from(100u128));
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![reward.clone()],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: Some(vec![(reward_info.clone(), operations.clone())]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages[1].msg,
+        Router(Addr::unchecked("router")).execute_swap_operations_msg(
+            reward.clone(),
+            operations.clone(),
+            None,
+            None,
+            Some(Decimal::percent(50)),
+        )?
+    );
+
+    // invalid supplied route errors before building swaps
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![reward],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: None,
+        routes: Some(vec![(reward_info, operations[..1].to_vec())]),
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(res, Err(StdError::generic_err("route must end at an asset in the pair").into()));
+
+    Ok(())
+}
+
+#[test]
+fn simulate_swap_operations() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: Some("router".to_string()),
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let reward_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0001"),
+    };
+    let mid_info = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token0002"),
+    };
+    let operations = vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: reward_info.clone(),
+            ask_asset_info: mid_info.clone(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info: mid_info,
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        },
+    ];
+
+    // two hops without a stored route
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(100u128),
+        operations: operations.clone(),
+    };
+    let res: SwapOperationsSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, SwapOperationsSimulationResponse {
+        amount: Uint128::from(400u128),
+        hop_amounts: vec![Uint128::from(200u128), Uint128::from(400u128)],
+    });
+
+    // same as simulating the stored route
+    let owner_info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), owner_info, ExecuteMsg::UpdateRoute {
+        asset_info: reward_info.clone(),
+        operations: Some(operations.clone()),
+    })?;
+    let msg = QueryMsg::Route {
+        asset_info: reward_info,
+    };
+    let route: RouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let stored = Router(Addr::unchecked("router")).simulate(&deps.as_ref().querier, Uint128::from(100u128), route.operations)?;
+    assert_eq!(res.amount, stored.amount);
+
+    // operations are validated as a route
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(100u128),
+        operations: operations[..1].to_vec(),
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("route must end at an asset in the pair")));
+
+    let msg = QueryMsg::SimulateSwapOperations {
+        offer_amount: Uint128::from(100u128),
+        operations: vec![operations[1].clone(), operations[0].clone()],
+    };
+    let res = query(deps.as_ref(), env, msg);
+    assert_eq!(res, Err(StdError::generic_err("route operations must be connected")));
+
+    Ok(())
+}
+
+#[test]
+fn compound_slippage_overrides() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token0001"),
+                },
+                "pair0001".to_string(),
+            ),
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token0002"),
+                },
+                "pair0002".to_string(),
+            ),
+        ],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let reward_1 = token_asset(Addr::unchecked("token0001"), Uint128::from(100u128));
+    let reward_2 = token_asset(Addr::unchecked("token0002"), Uint128::from(100u128));
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![reward_1.clone(), reward_2.clone()],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: Some(vec![(reward_1.info.clone(), Decimal::percent(101))]),
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("slippage_overrides must be 0 to 1").into()));
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![reward_1.clone(), reward_2.clone()],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: Some(vec![(reward_1.info.clone(), Decimal::percent(2))]),
+        refund_dust: None,
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+
+    // overridden asset is checked against the pool price, the other falls through to default
+    assert_eq!(
+        res.messages[1].msg,
+        Pair(Addr::unchecked("pair0001")).swap_msg(&reward_1, None, Some(Decimal::percent(2)), None)?
+    );
+    assert_eq!(
+        res.messages[3].msg,
+        Pair(Addr::unchecked("pair0002")).swap_msg(
+            &reward_2,
+            Some(Decimal::MAX),
+            Some(Decimal::percent(50)),
+            None,
+        )?
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("swap_slippage_tolerance", "token0001:0.02"),
+            attr("swap_slippage_tolerance", "token0002:0.5"),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_refund_dust() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::from(100u128))],
+        to: Some("receiver".to_string()),
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        dust_to: None,
+        slippage_overrides: None,
+        refund_dust: Some(true),
+        routes: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let prev_balances = vec![
+        token_asset(Addr::unchecked("token"), Uint128::zero()),
+        native_asset("uluna".to_string(), Uint128::zero()),
+    ];
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CallbackMsg::RefundDust {
+            prev_balances,
+            receiver: "receiver".to_string(),
+        }.into_cosmos_msg(&env.contract.address)?)
+    );
+
+    // dust left after providing liquidity
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000010),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(9))],
+    )]);
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::RefundDust {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("token"), Uint128::new(4)),
+            native_asset("uluna".to_string(), Uint128::new(1000000)),
+        ],
+        receiver: "receiver".to_string(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "receiver".to_string(),
+                    amount: Uint128::new(5),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "receiver".to_string(),
+                amount: vec![coin(10, "uluna")],
+            }),
+        ]
+    );
+
+    // no dust, no refund
+    let msg = ExecuteMsg::Callback(CallbackMsg::RefundDust {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("token"), Uint128::new(9)),
+            native_asset("uluna".to_string(), Uint128::new(1000010)),
+        ],
+        receiver: "receiver".to_string(),
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert!(res.messages.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn swap_to() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![
+            (token_asset_info(Addr::unchecked("astro")), "astro_pair".to_string()),
+            (native_asset_info("ibc/reward".to_string()), "reward_pair".to_string()),
+        ],
+        slippage_tolerance: Decimal::percent(1),
+        max_swaps: 10,
+        owner: "owner".to_string(),
+        router: None,
+        allowed_recipients: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(200, "ibc/reward")],
+    )]);
+
+    let astro_reward = token_asset(Addr::unchecked("astro"), Uint128::from(100u128));
+    let native_reward = native_asset("ibc/reward".to_string(), Uint128::from(200u128));
+    let msg = ExecuteMsg::SwapTo {
+        rewards: vec![astro_reward.clone(), native_reward.clone()],
+        target: native_asset_info("uluna".to_string()),
+        to: Some("receiver".to_string()),
+        minimum_receive: Some(Uint128::from(500u128)),
+        slippage_tolerance: None,
+    };
+    let info = mock_info("addr0000", &[coin(200, "ibc/reward")]);
+
+    // target must be in the pair
+    let mut invalid_msg = msg.clone();
+    if let ExecuteMsg::SwapTo { target, .. } = &mut invalid_msg {
+        *target = native_asset_info("ibc/reward".to_string());
+    }
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), invalid_msg);
+    assert_eq!(res, Err(StdError::generic_err("target must be an asset in the pair").into()));
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "astro".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(100u128),
+                })?,
+            }),
+            Pair(Addr::unchecked("astro_pair")).swap_msg(
+                &astro_reward,
+                Some(Decimal::MAX),
+                Some(Decimal::percent(50)),
+                None,
+            )?,
+            Pair(Addr::unchecked("reward_pair")).swap_msg(
+                &native_reward,
+                Some(Decimal::MAX),
+                Some(Decimal::percent(50)),
+                None,
+            )?,
+            CallbackMsg::SwapToTarget {
+                prev_balances: vec![
+                    token_asset(Addr::unchecked("token"), Uint128::zero()),
+                    native_asset("uluna".to_string(), Uint128::zero()),
+                ],
+                target: native_asset_info("uluna".to_string()),
+                slippage_tolerance: None,
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+            CallbackMsg::TransferTarget {
+                prev_balance: native_asset("uluna".to_string(), Uint128::zero()),
+                receiver: "receiver".to_string(),
+                minimum_receive: Some(Uint128::from(500u128)),
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        ]
+    );
+
+    // rewards swapped to both assets in the pair
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(400, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(300))],
+    )]);
+
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::SwapToTarget {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("token"), Uint128::zero()),
+            native_asset("uluna".to_string(), Uint128::zero()),
+        ],
+        target: native_asset_info("uluna".to_string()),
+        slippage_tolerance: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair_contract".to_string(),
+                    amount: Uint128::from(300u128),
+                    msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                        ask_asset_info: None,
+                        belief_price: None,
+                        max_spread: Some(Decimal::percent(1)),
+                        to: None,
+                    })?,
+                })?,
+            }),
+        ]
+    );
+
+    // the other pair asset swapped to the target
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(700, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+    )]);
+
+    let transfer_msg = |minimum_receive: u128| {
+        ExecuteMsg::Callback(CallbackMsg::TransferTarget {
+            prev_balance: native_asset("uluna".to_string(), Uint128::zero()),
+            receiver: "receiver".to_string(),
+            minimum_receive: Some(Uint128::from(minimum_receive)),
+        })
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), transfer_msg(800));
+    assert_eq!(
+        res,
+        Err(ContractError::MinimumReceive {
+            minimum_receive: Uint128::from(800u128),
+            amount: Uint128::from(700u128),
+        })
+    );
+
+    let res = execute(deps.as_mut(), env, info, transfer_msg(500))?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "receiver".to_string(),
+                amount: vec![coin(700, "uluna")],
+            }),
+        ]
+    );
+
+    Ok(())
+}
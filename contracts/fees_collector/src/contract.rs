@@ -1,14 +1,22 @@
 use crate::error::ContractError;
-use crate::state::{Config, BRIDGES, CONFIG, OWNERSHIP_PROPOSAL};
+use crate::state::{Config, BRIDGES, CONFIG, LEGACY_CONFIG, OPERATOR_PROPOSAL, OWNERSHIP_PROPOSAL};
 
 use crate::utils::{build_swap_bridge_msg, try_build_swap_msg, validate_bridge, BRIDGES_EXECUTION_MAX_DEPTH, BRIDGES_INITIAL_DEPTH, try_swap_simulation};
-use astroport::asset::{native_asset_info, Asset, AssetInfo, ULUNA_DENOM, AssetInfoExt};
+use astroport::asset::{native_asset_info, Asset, AssetInfo, PairInfo, ULUNA_DENOM, AssetInfoExt};
 
-use astroport::common::{propose_new_owner, drop_ownership_proposal, claim_ownership};
-use cosmwasm_std::{entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg, attr, Addr};
-use spectrum::fees_collector::{AssetWithLimit, BalancesResponse, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::ownership::{propose_new_owner, drop_ownership_proposal, claim_ownership, propose_new_operator, drop_operator_proposal, claim_operator};
+use astroport::querier::query_pair_info;
+use cosmwasm_std::{entry_point, to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg, attr, Addr, Reply, SubMsg, SubMsgResult, ContractInfoResponse, QueryRequest, WasmQuery};
+use spectrum::fees_collector::{AssetWithLimit, BalancesResponse, BridgeValidationResponse, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReverseCollectSimulationResponse, SwapPath};
 use std::collections::{HashMap, HashSet};
 use spectrum::adapters::asset::AssetEx;
+use spectrum::adapters::router::Router;
+
+/// Reply id of the best effort distribution queued by collect
+const DISTRIBUTE_FEES_REPLY_ID: u64 = 1;
+
+/// Rounding allowed on the sum of target_ratios, in Decimal atomics (1e-12)
+const TARGET_RATIOS_TOLERANCE: u128 = 1_000_000;
 
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
@@ -23,14 +31,27 @@ pub fn instantiate(
 
     msg.stablecoin.check(deps.api)?;
 
+    if msg.target_ratios.is_some() && !msg.target_list.is_empty() {
+        return Err(StdError::generic_err("target_list and target_ratios cannot be both set").into());
+    }
+    let use_target_ratios = msg.target_ratios.is_some();
+    let target_ratios = match msg.target_ratios {
+        Some(target_ratios) => validate_target_ratios(deps.as_ref(), target_ratios)?,
+        None => vec![],
+    };
+
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
         operator: deps.api.addr_validate(&msg.operator)?,
         factory_contract: deps.api.addr_validate(&msg.factory_contract)?,
         stablecoin: msg.stablecoin,
-        target_list: msg.target_list.into_iter()
-                                .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
-                                .collect::<StdResult<_>>()?,
+        target_list: validate_target_list(deps.as_ref(), msg.target_list)?,
+        max_bridge_depth: BRIDGES_EXECUTION_MAX_DEPTH,
+        min_distribution: None,
+        min_swap_amounts: vec![],
+        target_ratios,
+        use_target_ratios,
+        router: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -48,24 +69,44 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Collect { assets, minimum_receive } => collect(deps, env, info, assets, minimum_receive),
+        ExecuteMsg::Collect { assets, minimum_receive, best_effort } => collect(deps, env, info, assets, minimum_receive, best_effort),
+        ExecuteMsg::CollectAll { minimum_receive } => collect_all(deps, env, info, minimum_receive),
         ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, info, add, remove),
         ExecuteMsg::UpdateConfig {
             operator,
             factory_contract,
             target_list,
+            max_bridge_depth,
+            min_distribution,
+            min_swap_amounts,
+            target_ratios,
+            router,
         } => update_config(
             deps,
             info,
             operator,
             factory_contract,
             target_list,
+            max_bridge_depth,
+            min_distribution,
+            min_swap_amounts,
+            target_ratios,
+            router,
         ),
         ExecuteMsg::SwapBridgeAssets { assets, depth } => {
             swap_bridge_assets(deps, env, info, assets, depth)
         }
         ExecuteMsg::DistributeFees { minimum_receive } => distribute_fees(deps, env, info, minimum_receive),
-        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+        ExecuteMsg::AssertMinimumReceive { minimum_receive } => {
+            // Only the contract itself can call this function
+            if info.sender != env.contract.address {
+                return Err(ContractError::Unauthorized {});
+            }
+            let config = CONFIG.load(deps.storage)?;
+            assert_minimum_receive(deps.as_ref(), &env, &config, minimum_receive)?;
+            Ok(Response::new().add_attribute("action", "assert_minimum_receive"))
+        }
+        ExecuteMsg::ProposeNewOwner { owner, expires_in, timelock } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
             propose_new_owner(
@@ -74,6 +115,7 @@ pub fn execute(
                 env,
                 owner,
                 expires_in,
+                timelock,
                 config.owner,
                 OWNERSHIP_PROPOSAL,
             )
@@ -96,6 +138,37 @@ pub fn execute(
             })
             .map_err(|e| e.into())
         },
+        ExecuteMsg::ProposeNewOperator { operator, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_operator(
+                deps,
+                info,
+                env,
+                operator,
+                expires_in,
+                config.operator,
+                OPERATOR_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOperatorProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_operator_proposal(deps, info, config.operator, OPERATOR_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOperator {} => {
+            claim_operator(deps, info, env, OPERATOR_PROPOSAL, |deps, new_operator| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.operator = new_operator;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
     }
 }
 
@@ -109,6 +182,7 @@ fn collect(
     info: MessageInfo,
     assets: Vec<AssetWithLimit>,
     minimum_receive: Option<Uint128>,
+    best_effort: bool,
 ) -> Result<Response, ContractError> {
 
     let config = CONFIG.load(deps.storage)?;
@@ -127,7 +201,7 @@ fn collect(
     }
     let response = Response::default();
     // Swap all non stablecoin tokens
-    let (mut messages, bridge_assets) = swap_assets(
+    let (mut messages, bridge_assets, _) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &config,
@@ -135,6 +209,7 @@ fn collect(
             .into_iter()
             .filter(|a| a.info.ne(&config.stablecoin))
             .collect(),
+        true,
     )?;
 
     // If no swap messages - send stablecoin directly to beneficiary
@@ -146,18 +221,96 @@ fn collect(
         )?);
     }
 
+    // In best effort mode, a failed distribution is caught in reply, so the swaps still settle.
+    // The minimum receive is then checked by a plain message, so a missed floor still reverts the swaps.
+    if best_effort {
+        if let Some(minimum_receive) = minimum_receive {
+            messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive { minimum_receive })?,
+                funds: vec![],
+            }));
+        }
+    }
     let distribute_fee = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: env.contract.address.to_string(),
         msg: to_binary(&ExecuteMsg::DistributeFees {
-            minimum_receive,
+            minimum_receive: if best_effort { None } else { minimum_receive },
         })?,
         funds: vec![],
     });
-    messages.push(distribute_fee);
+    let response = response.add_messages(messages);
 
-    Ok(response
-        .add_messages(messages)
-        .add_attribute("action", "collect"))
+    let response = if best_effort {
+        response.add_submessage(SubMsg::reply_on_error(distribute_fee, DISTRIBUTE_FEES_REPLY_ID))
+    } else {
+        response.add_message(distribute_fee)
+    };
+
+    Ok(response.add_attribute("action", "collect"))
+}
+
+/// ## Description
+/// Collects all non-zero native balances of the contract and all tokens with a bridge,
+/// then swaps them to stablecoin in the same way as [`ExecuteMsg::Collect`].
+/// Assets without a bridge or a pair to swap are skipped and reported in the `skipped` attribute.
+fn collect_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut uniq = HashSet::new();
+    let mut assets: Vec<AssetWithLimit> = vec![];
+    for coin in deps.querier.query_all_balances(&env.contract.address)? {
+        if !coin.amount.is_zero() && uniq.insert(coin.denom.clone()) {
+            assets.push(AssetWithLimit {
+                info: native_asset_info(coin.denom),
+                limit: None,
+            });
+        }
+    }
+
+    let asset_labels = BRIDGES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    for asset_label in asset_labels {
+        if !uniq.insert(asset_label.clone()) {
+            continue;
+        }
+        let info = match deps.api.addr_validate(&asset_label) {
+            Ok(contract_addr) => AssetInfo::Token { contract_addr },
+            Err(_) => AssetInfo::NativeToken { denom: asset_label },
+        };
+        assets.push(AssetWithLimit { info, limit: None });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut skipped: Vec<AssetInfo> = vec![];
+    assets.retain(|a| {
+        let can_swap = a.info == config.stablecoin
+            || swap(deps.as_ref(), &config, a.info.clone(), Uint128::zero()).is_ok();
+        if !can_swap {
+            skipped.push(a.info.clone());
+        }
+        can_swap
+    });
+
+    let response = collect(deps, env, info, assets, minimum_receive, false)?;
+    Ok(response.add_attributes(skipped.into_iter().map(|it| attr("skipped", it.to_string()))))
+}
+
+/// ## Description
+/// Handles the failed distribution of a best effort [`ExecuteMsg::Collect`].
+/// The error is recorded as an attribute instead of reverting the transaction.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        (DISTRIBUTE_FEES_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new()
+            .add_attribute("action", "distribute_fees_failed")
+            .add_attribute("error", err)),
+        _ => Err(StdError::generic_err("Unexpected reply").into()),
+    }
 }
 
 /// ## Description
@@ -167,17 +320,22 @@ enum SwapTarget {
     Bridge { asset: AssetInfo, msg: CosmosMsg },
 }
 
+type SwapAssetsParts = (Vec<CosmosMsg>, Vec<AssetInfo>, Vec<AssetInfo>);
+
 /// ## Description
 /// Swap all non stablecoin tokens to stablecoin. Returns a [`ContractError`] on failure, otherwise returns
-/// a [`Response`] object if the operation was successful.
+/// the swap messages, the bridge assets to swap next and the assets swapped.
+/// With check_min_swap_amount, assets with balance below their min_swap_amount are skipped.
 fn swap_assets(
     deps: Deps,
     contract_addr: &Addr,
     config: &Config,
     assets: Vec<AssetWithLimit>,
-) -> Result<(Vec<CosmosMsg>, Vec<AssetInfo>), ContractError> {
+    check_min_swap_amount: bool,
+) -> Result<SwapAssetsParts, ContractError> {
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut bridge_assets = HashMap::new();
+    let mut swapped_assets = vec![];
 
     for a in assets {
         // Get balance
@@ -187,8 +345,12 @@ fn swap_assets(
                 balance = limit;
             }
         }
+        if check_min_swap_amount && balance < config.min_swap_amount(&a.info) {
+            continue;
+        }
 
         if !balance.is_zero() {
+            swapped_assets.push(a.info.clone());
             let swap_msg = swap(deps, config, a.info, balance)?;
             match swap_msg {
                 SwapTarget::Stable(msg) => {
@@ -202,7 +364,7 @@ fn swap_assets(
         }
     }
 
-    Ok((messages, bridge_assets.into_values().collect()))
+    Ok((messages, bridge_assets.into_values().collect(), swapped_assets))
 }
 
 /// ## Description
@@ -261,13 +423,13 @@ fn swap_bridge_assets(
         return Ok(Response::default());
     }
 
+    let config = CONFIG.load(deps.storage)?;
+
     // Check that the contract doesn't call itself endlessly
-    if depth >= BRIDGES_EXECUTION_MAX_DEPTH {
+    if depth >= config.max_bridge_depth {
         return Err(ContractError::MaxBridgeDepth(depth));
     }
 
-    let config = CONFIG.load(deps.storage)?;
-
     let bridges = assets
         .into_iter()
         .map(|a| AssetWithLimit {
@@ -276,11 +438,12 @@ fn swap_bridge_assets(
         })
         .collect();
 
-    let (mut messages, bridge_assets) = swap_assets(
+    let (mut messages, bridge_assets, swapped_assets) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &config,
-        bridges)?;
+        bridges,
+        false)?;
 
     // There should always be some messages, if there are none - something went wrong
     if messages.is_empty() {
@@ -289,13 +452,17 @@ fn swap_bridge_assets(
         )));
     }
 
+    let remaining_bridges = bridge_assets.len();
     if !bridge_assets.is_empty() {
         messages.push(build_swap_bridge_msg(&env.contract.address, bridge_assets, depth + 1)?)
     }
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("action", "swap_bridge_assets"))
+        .add_attribute("action", "swap_bridge_assets")
+        .add_attribute("depth", depth.to_string())
+        .add_attributes(swapped_assets.into_iter().map(|it| attr("swapped", it.to_string())))
+        .add_attribute("remaining_bridges", remaining_bridges.to_string()))
 }
 
 /// ## Description
@@ -320,6 +487,24 @@ fn distribute_fees(
         .add_attributes(attributes))
 }
 
+/// ## Description
+/// Returns the stablecoin balance, or a [`ContractError::AssertionMinimumReceive`] if it is below **minimum_receive**.
+fn assert_minimum_receive(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    minimum_receive: Uint128,
+) -> Result<Uint128, ContractError> {
+    let total_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
+    if total_amount < minimum_receive {
+        return Err(ContractError::AssertionMinimumReceive {
+            minimum_receive,
+            amount: total_amount,
+        });
+    }
+    Ok(total_amount)
+}
+
 type DistributeMsgParts = (Vec<CosmosMsg>, Vec<(String, String)>);
 
 /// ## Description
@@ -334,37 +519,119 @@ fn distribute(
     let mut messages = vec![];
     let mut attributes = vec![];
 
-    let total_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
-    if let Some(minimum_receive) = minimum_receive {
-        if total_amount < minimum_receive {
-            return Err(ContractError::AssertionMinimumReceive {
-                minimum_receive,
-                amount: total_amount,
-            });
-        }
-    }
+    let total_amount = match minimum_receive {
+        Some(minimum_receive) => assert_minimum_receive(deps.as_ref(), &env, config, minimum_receive)?,
+        None => config.stablecoin.query_pool(&deps.querier, &env.contract.address)?,
+    };
 
     if total_amount.is_zero() {
         return Ok((messages, attributes));
     }
 
-    let total_weight = config.target_list.iter()
-        .map(|(_, weight)| *weight)
-        .sum::<u64>();
+    for (to, amount, msg) in compute_distribution(config, total_amount) {
+        let asset = config.stablecoin.with_balance(amount);
+        let send_msg = match msg {
+            Some(msg) => asset.send_msg(&to, msg)?,
+            None => asset.transfer_msg(&to)?,
+        };
+        messages.push(send_msg);
+        attributes.push(("to".to_string(), to.to_string()));
+        attributes.push(("amount".to_string(), amount.to_string()));
+    }
+
+    attributes.push(("action".to_string(), "distribute_fees".to_string()));
+
+    Ok((messages, attributes))
+}
 
-    for (to, weight) in &config.target_list {
-        let amount = total_amount.multiply_ratio(*weight, total_weight);
+/// ## Description
+/// Splits the stablecoin amount across the target list. Returns the non-zero share of each target,
+/// what is not shared is left in the contract.
+fn compute_distribution(config: &Config, total_amount: Uint128) -> Vec<(Addr, Uint128, Option<Binary>)> {
+    // ratios are weighted by their atomics, so both modes share the same split below
+    let targets: Vec<(Addr, Uint128, Option<Binary>)> = if config.use_target_ratios {
+        config.target_ratios.iter()
+            .map(|(to, ratio)| (to.clone(), ratio.atomics(), None))
+            .collect()
+    } else {
+        config.target_list.iter()
+            .map(|(to, weight, msg)| (to.clone(), Uint128::from(*weight), msg.clone()))
+            .collect()
+    };
+    let total_weight = targets.iter()
+        .map(|(_, weight, _)| *weight)
+        .sum::<Uint128>();
+
+    // With min_distribution, each share is taken from what is left:
+    //   amount = floor(remaining_amount * weight / remaining_weight)
+    // A share below the floor stays in remaining_amount, so the following recipients absorb it
+    // pro rata, and the last recipient takes all of remaining_amount. If the last share is
+    // below the floor, it stays in the contract for the next distribution.
+    let mut shares = vec![];
+    let mut remaining_amount = total_amount;
+    let mut remaining_weight = total_weight;
+    for (to, weight, msg) in targets {
+        let amount = match config.min_distribution {
+            Some(min_distribution) => {
+                let amount = remaining_amount.multiply_ratio(weight, remaining_weight);
+                remaining_weight -= weight;
+                if amount < min_distribution {
+                    continue;
+                }
+                remaining_amount -= amount;
+                amount
+            }
+            None => total_amount.multiply_ratio(weight, total_weight),
+        };
         if !amount.is_zero() {
-            let send_msg = config.stablecoin.with_balance(amount).transfer_msg(to)?;
-            messages.push(send_msg);
-            attributes.push(("to".to_string(), to.to_string()));
-            attributes.push(("amount".to_string(), amount.to_string()));
+            shares.push((to, amount, msg));
         }
     }
 
-    attributes.push(("action".to_string(), "distribute_fees".to_string()));
+    shares
+}
 
-    Ok((messages, attributes))
+/// ## Description
+/// Validates target addresses, targets with msg must be contracts.
+fn validate_target_list(
+    deps: Deps,
+    target_list: Vec<(String, u64, Option<Binary>)>,
+) -> StdResult<Vec<(Addr, u64, Option<Binary>)>> {
+    target_list.into_iter()
+        .map(|(addr, weight, msg)| {
+            let addr = deps.api.addr_validate(&addr)?;
+            if msg.is_some() {
+                deps.querier.query::<ContractInfoResponse>(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                    contract_addr: addr.to_string(),
+                })).map_err(|_| StdError::generic_err(format!("target with msg must be a contract: {}", addr)))?;
+            }
+            Ok((addr, weight, msg))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Validates target addresses and that the ratios sum to one, within [`TARGET_RATIOS_TOLERANCE`] atomics.
+fn validate_target_ratios(
+    deps: Deps,
+    target_ratios: Vec<(String, Decimal)>,
+) -> StdResult<Vec<(Addr, Decimal)>> {
+    let target_ratios = target_ratios.into_iter()
+        .map(|(addr, ratio)| Ok((deps.api.addr_validate(&addr)?, ratio)))
+        .collect::<StdResult<Vec<(Addr, Decimal)>>>()?;
+    let total_atomics = target_ratios.iter()
+        .map(|(_, ratio)| ratio.atomics())
+        .sum::<Uint128>();
+    let one_atomics = Decimal::one().atomics();
+    let diff = if total_atomics > one_atomics {
+        total_atomics - one_atomics
+    } else {
+        one_atomics - total_atomics
+    };
+    if diff > Uint128::from(TARGET_RATIOS_TOLERANCE) {
+        return Err(StdError::generic_err("target_ratios must sum to 1"));
+    }
+    Ok(target_ratios)
 }
 
 /// ## Description
@@ -375,7 +642,12 @@ pub fn update_config(
     info: MessageInfo,
     operator: Option<String>,
     factory_contract: Option<String>,
-    target_list: Option<Vec<(String, u64)>>,
+    target_list: Option<Vec<(String, u64, Option<Binary>)>>,
+    max_bridge_depth: Option<u64>,
+    min_distribution: Option<Uint128>,
+    min_swap_amounts: Option<Vec<(AssetInfo, Uint128)>>,
+    target_ratios: Option<Vec<(String, Decimal)>>,
+    router: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -383,6 +655,10 @@ pub fn update_config(
         return Err(ContractError::Unauthorized {});
     }
 
+    if target_list.is_some() && target_ratios.is_some() {
+        return Err(StdError::generic_err("target_list and target_ratios cannot be both set").into());
+    }
+
     if let Some(operator) = operator {
         config.operator = deps.api.addr_validate(&operator)?;
     }
@@ -392,9 +668,47 @@ pub fn update_config(
     }
 
     if let Some(target_list) = target_list {
-        config.target_list = target_list.into_iter()
-        .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
-        .collect::<StdResult<_>>()?
+        config.target_list = validate_target_list(deps.as_ref(), target_list)?;
+        config.use_target_ratios = false;
+    }
+
+    if let Some(target_ratios) = target_ratios {
+        config.target_ratios = validate_target_ratios(deps.as_ref(), target_ratios)?;
+        config.use_target_ratios = true;
+    }
+
+    if let Some(max_bridge_depth) = max_bridge_depth {
+        if max_bridge_depth == 0 {
+            return Err(StdError::generic_err("max_bridge_depth must be at least 1").into());
+        }
+        let lowered = max_bridge_depth < config.max_bridge_depth;
+        config.max_bridge_depth = max_bridge_depth;
+
+        // stored bridges must still reach stablecoin within the lowered depth
+        if lowered {
+            for (_, _, result) in validate_bridges(deps.as_ref(), &config)? {
+                result?;
+            }
+        }
+    }
+
+    if let Some(min_distribution) = min_distribution {
+        config.min_distribution = if min_distribution.is_zero() {
+            None
+        } else {
+            Some(min_distribution)
+        };
+    }
+
+    if let Some(min_swap_amounts) = min_swap_amounts {
+        for (asset_info, _) in min_swap_amounts.iter() {
+            asset_info.check(deps.api)?;
+        }
+        config.min_swap_amounts = min_swap_amounts;
+    }
+
+    if let Some(router) = router {
+        config.router = Some(Router(deps.api.addr_validate(&router)?));
     }
 
     CONFIG.save(deps.storage, &config)?;
@@ -434,27 +748,43 @@ fn update_bridges(
         }
     }
 
+    // Check that bridge tokens can be swapped to stablecoin
+    for (_, _, result) in validate_bridges(deps.as_ref(), &config)? {
+        result?;
+    }
+
+    Ok(Response::default().add_attribute("action", "update_bridges"))
+}
+
+/// The asset, the bridge and the validation result of a registered bridge
+type BridgeValidation = (AssetInfo, AssetInfo, Result<PairInfo, ContractError>);
+
+/// ## Description
+/// Validates every registered bridge with [`validate_bridge`], returning the result of each.
+fn validate_bridges(deps: Deps, config: &Config) -> StdResult<Vec<BridgeValidation>> {
     let bridges = BRIDGES
         .range(deps.storage, None, None, Order::Ascending)
         .collect::<StdResult<Vec<(String, AssetInfo)>>>()?;
 
-    for (asset_label, bridge) in bridges {
-        let asset = match deps.api.addr_validate(&asset_label) {
-            Ok(contract_addr) => AssetInfo::Token { contract_addr },
-            Err(_) => AssetInfo::NativeToken { denom: asset_label },
-        };
-        // Check that bridge tokens can be swapped to stablecoin
-        validate_bridge(
-            deps.as_ref(),
-            &config.factory_contract,
-            &asset,
-            &bridge,
-            &config.stablecoin,
-            BRIDGES_INITIAL_DEPTH,
-        )?;
-    }
-
-    Ok(Response::default().add_attribute("action", "update_bridges"))
+    Ok(bridges
+        .into_iter()
+        .map(|(asset_label, bridge)| {
+            let asset = match deps.api.addr_validate(&asset_label) {
+                Ok(contract_addr) => AssetInfo::Token { contract_addr },
+                Err(_) => AssetInfo::NativeToken { denom: asset_label },
+            };
+            let result = validate_bridge(
+                deps,
+                &config.factory_contract,
+                &asset,
+                &bridge,
+                &config.stablecoin,
+                BRIDGES_INITIAL_DEPTH,
+                config.max_bridge_depth,
+            );
+            (asset, bridge, result)
+        })
+        .collect())
 }
 
 /// ## Description
@@ -465,7 +795,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::Balances { assets } => to_binary(&query_get_balances(deps, env, assets)?),
         QueryMsg::Bridges {} => to_binary(&query_bridges(deps, env)?),
+        QueryMsg::BridgePath { asset } => to_binary(&query_bridge_path(deps, asset)?),
+        QueryMsg::ValidateBridges {} => to_binary(&query_validate_bridges(deps)?),
         QueryMsg::CollectSimulation { assets } => to_binary(&query_collect_simulation(deps, env, assets)?),
+        QueryMsg::DistributeSimulation {} => to_binary(&query_distribute_simulation(deps, env)?),
+        QueryMsg::ReverseCollectSimulation { asset, return_amount } => {
+            to_binary(&query_reverse_collect_simulation(deps, asset, return_amount)?)
+        }
     }
 }
 
@@ -488,6 +824,29 @@ fn query_get_balances(deps: Deps, env: Env, assets: Vec<AssetInfo>) -> StdResult
     Ok(resp)
 }
 
+/// ## Description
+/// Returns the stablecoin amount each target receives if fees are distributed now, split the same
+/// way as [`distribute`]. The amount left undistributed is attributed to this contract at the end.
+fn query_distribute_simulation(deps: Deps, env: Env) -> StdResult<Vec<(Addr, Uint128)>> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
+    if total_amount.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let mut shares: Vec<(Addr, Uint128)> = compute_distribution(&config, total_amount)
+        .into_iter()
+        .map(|(to, amount, _)| (to, amount))
+        .collect();
+    let distributed_amount = shares.iter().map(|(_, amount)| *amount).sum::<Uint128>();
+    let remaining_amount = total_amount.checked_sub(distributed_amount)?;
+    if !remaining_amount.is_zero() {
+        shares.push((env.contract.address, remaining_amount));
+    }
+
+    Ok(shares)
+}
+
 /// ## Description
 /// Returns bridge tokens used for swapping fee tokens to stablecoin.
 fn query_bridges(deps: Deps, _env: Env) -> StdResult<Vec<(String, String)>> {
@@ -500,12 +859,61 @@ fn query_bridges(deps: Deps, _env: Env) -> StdResult<Vec<(String, String)>> {
         .collect()
 }
 
+/// ## Description
+/// Returns the validation result of every registered bridge, so a bridge whose pool has gone away
+/// can be found before collect fails.
+fn query_validate_bridges(deps: Deps) -> StdResult<Vec<BridgeValidationResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(validate_bridges(deps, &config)?
+        .into_iter()
+        .map(|(asset, bridge, result)| BridgeValidationResponse {
+            asset: asset.to_string(),
+            bridge: bridge.to_string(),
+            error: result.err().map(|err| err.to_string()),
+        })
+        .collect())
+}
+
+/// ## Description
+/// Returns the swap path of an asset to stablecoin, resolved the same way as [`swap`].
+fn query_bridge_path(deps: Deps, asset: AssetInfo) -> Result<Vec<AssetInfo>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let uluna = native_asset_info(ULUNA_DENOM.to_string());
+
+    let mut path = vec![asset.clone()];
+    let mut from_token = asset;
+    while from_token.ne(&config.stablecoin) {
+        // the first swap is executed by collect, the following ones by SwapBridgeAssets
+        if path.len() > config.max_bridge_depth as usize {
+            return Err(ContractError::MaxBridgeDepth(config.max_bridge_depth));
+        }
+
+        let next_token = if let Ok(bridge_token) = BRIDGES.load(deps.storage, from_token.to_string()) {
+            query_pair_info(&deps.querier, &config.factory_contract, &[from_token.clone(), bridge_token.clone()])?;
+            bridge_token
+        } else if query_pair_info(&deps.querier, &config.factory_contract, &[from_token.clone(), config.stablecoin.clone()]).is_ok() {
+            config.stablecoin.clone()
+        } else if from_token.ne(&uluna) && query_pair_info(&deps.querier, &config.factory_contract, &[from_token.clone(), uluna.clone()]).is_ok() {
+            uluna.clone()
+        } else {
+            return Err(ContractError::CannotSwap(from_token));
+        };
+
+        path.push(next_token.clone());
+        from_token = next_token;
+    }
+
+    Ok(path)
+}
+
 fn query_collect_simulation(
     deps: Deps,
     env: Env,
     assets: Vec<AssetWithLimit>
 ) -> Result<CollectSimulationResponse, ContractError> {
 
+    let config = CONFIG.load(deps.storage)?;
+
     // Check for duplicate assets
     let mut uniq = HashMap::new();
     for a in assets {
@@ -518,19 +926,68 @@ fn query_collect_simulation(
             }
         }
 
+        // skipped by collect
+        if a.info.ne(&config.stablecoin) && balance < config.min_swap_amount(&a.info) {
+            balance = Uint128::zero();
+        }
+
         // swap
         if uniq.insert(a.info, balance).is_some() {
             return Err(ContractError::DuplicatedAsset {});
         }
     }
 
-    let config = CONFIG.load(deps.storage)?;
     if !uniq.contains_key(&config.stablecoin) {
         let stable_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
         uniq.insert(config.stablecoin.clone(), stable_amount);
     }
 
-    bulk_swap_simulation(deps, uniq, config, BRIDGES_INITIAL_DEPTH)
+    // simulate each asset on its own, seeded with stablecoin so bridged assets are reduced fully
+    let mut breakdown = vec![];
+    for (asset_info, amount) in uniq.iter() {
+        let mut assets = HashMap::from([(config.stablecoin.clone(), Uint128::zero())]);
+        assets.insert(asset_info.clone(), *amount);
+        let res = bulk_swap_simulation(deps, assets, config.clone(), BRIDGES_INITIAL_DEPTH, &mut vec![])?;
+        breakdown.push((asset_info.to_string(), res.return_amount));
+    }
+    breakdown.sort();
+
+    let mut paths = vec![];
+    let mut res = bulk_swap_simulation(deps, uniq, config, BRIDGES_INITIAL_DEPTH, &mut paths)?;
+    paths.sort_by_key(|(asset_info, _)| asset_info.to_string());
+    res.paths = paths;
+    // isolated swaps do not share pool slippage, so the total is taken from the breakdown
+    res.return_amount = breakdown.iter().map(|(_, amount)| *amount).sum();
+    res.breakdown = breakdown;
+
+    Ok(res)
+}
+
+/// ## Description
+/// Returns the amount of an asset to collect for the given amount of stablecoin, walking the swap
+/// path of [`query_bridge_path`] backwards.
+fn query_reverse_collect_simulation(
+    deps: Deps,
+    asset: AssetInfo,
+    return_amount: Uint128,
+) -> Result<ReverseCollectSimulationResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let router = config.router
+        .ok_or_else(|| StdError::generic_err("router is not set"))?;
+    let path = query_bridge_path(deps, asset)?;
+
+    let mut amount = return_amount;
+    let mut amounts = vec![amount];
+    for hop in path.windows(2).rev() {
+        amount = router.try_reverse_swap_simulation(&deps.querier, hop[0].clone(), hop[1].clone(), amount)?;
+        amounts.push(amount);
+    }
+    amounts.reverse();
+
+    Ok(ReverseCollectSimulationResponse {
+        offer_amount: amount,
+        amounts,
+    })
 }
 
 fn bulk_swap_simulation(
@@ -538,8 +995,16 @@ fn bulk_swap_simulation(
     assets: HashMap<AssetInfo, Uint128>,
     config: Config,
     depth: u64,
+    paths: &mut Vec<(AssetInfo, SwapPath)>,
 ) -> Result<CollectSimulationResponse, ContractError> {
 
+    // only the paths of the input assets are reported
+    let mut add_path = |asset_info: &AssetInfo, path: SwapPath| {
+        if depth == BRIDGES_INITIAL_DEPTH {
+            paths.push((asset_info.clone(), path));
+        }
+    };
+
     let mut next_assets: HashMap<AssetInfo, Uint128> = HashMap::new();
     let uluna = native_asset_info(ULUNA_DENOM.to_string());
     for (from_asset_info, amount_in) in assets {
@@ -556,8 +1021,9 @@ fn bulk_swap_simulation(
         // Check if bridge tokens exist
         let bridge_token = BRIDGES.load(deps.storage, from_asset_info.to_string());
         if let Ok(to_asset_info) = bridge_token {
-            let return_amount = try_swap_simulation(&deps.querier, &config, from_asset_info, to_asset_info.clone(), amount_in)?;
+            let return_amount = try_swap_simulation(&deps.querier, &config, from_asset_info.clone(), to_asset_info.clone(), amount_in)?;
             add_amount(&mut next_assets, to_asset_info, return_amount);
+            add_path(&from_asset_info, SwapPath::Bridge);
             continue;
         }
 
@@ -565,6 +1031,7 @@ fn bulk_swap_simulation(
         let return_amount = try_swap_simulation(&deps.querier, &config, from_asset_info.clone(), config.stablecoin.clone(), amount_in);
         if let Ok(return_amount) = return_amount {
             add_amount(&mut next_assets, config.stablecoin.clone(), return_amount);
+            add_path(&from_asset_info, SwapPath::Direct);
             continue;
         }
 
@@ -573,6 +1040,7 @@ fn bulk_swap_simulation(
             let return_amount = try_swap_simulation(&deps.querier, &config, from_asset_info.clone(), uluna.clone(), amount_in);
             if let Ok(return_amount) = return_amount {
                 add_amount(&mut next_assets, uluna.clone(), return_amount);
+                add_path(&from_asset_info, SwapPath::Intermediate);
                 continue;
             }
         }
@@ -586,16 +1054,18 @@ fn bulk_swap_simulation(
             return_amount: next_assets.get(&config.stablecoin)
                 .copied()
                 .unwrap_or_default(),
+            paths: vec![],
+            breakdown: vec![],
         });
     }
 
     let next_depth = depth + 1;
-    if next_depth >= BRIDGES_EXECUTION_MAX_DEPTH {
+    if next_depth >= config.max_bridge_depth {
         return Err(ContractError::MaxBridgeDepth(depth));
     }
 
 
-    bulk_swap_simulation(deps, next_assets, config, next_depth)
+    bulk_swap_simulation(deps, next_assets, config, next_depth, paths)
 }
 
 fn add_amount(assets: &mut HashMap<AssetInfo, Uint128>, key: AssetInfo, return_amount: Uint128) {
@@ -608,6 +1078,28 @@ fn add_amount(assets: &mut HashMap<AssetInfo, Uint128>, key: AssetInfo, return_a
 /// ## Description
 /// Used for contract migration. Returns a default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // target_list entries without msg, a config in the current shape is kept as is
+    if CONFIG.load(deps.storage).is_ok() {
+        return Ok(Response::default());
+    }
+    if let Ok(legacy_config) = LEGACY_CONFIG.load(deps.storage) {
+        CONFIG.save(deps.storage, &Config {
+            owner: legacy_config.owner,
+            operator: legacy_config.operator,
+            factory_contract: legacy_config.factory_contract,
+            target_list: legacy_config.target_list.into_iter()
+                .map(|(addr, weight)| (addr, weight, None))
+                .collect(),
+            stablecoin: legacy_config.stablecoin,
+            max_bridge_depth: legacy_config.max_bridge_depth,
+            min_distribution: legacy_config.min_distribution,
+            min_swap_amounts: legacy_config.min_swap_amounts,
+            target_ratios: vec![],
+            use_target_ratios: false,
+            router: None,
+        })?;
+    }
+
     Ok(Response::default())
 }
@@ -1,5 +1,6 @@
 pub mod contract;
 pub mod error;
+pub mod ownership;
 pub mod state;
 pub mod utils;
 
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdError, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
-use cosmwasm_std::testing::{MockApi, MockStorage};
+use std::collections::{HashMap, HashSet};
+use cosmwasm_std::{Addr, AllBalanceResponse, BalanceResponse, BankQuery, Binary, Coin, ContractInfoResponse, ContractResult, Decimal, Empty, Fraction, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdError, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::testing::{MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{token_asset, AssetInfo, PairInfo, Asset};
 use astroport::generator::{PendingTokenResponse};
-use astroport::pair::SimulationResponse;
+use astroport::pair::{ReverseSimulationResponse, SimulationResponse};
+use spectrum::adapters::router::{SimulateSwapOperationsResponse, SwapOperation};
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -27,6 +28,8 @@ pub struct WasmMockQuerier {
     raw: HashMap<(String, Binary), Binary>,
     pairs: HashMap<Vec<u8>, PairInfo>,
     prices: HashMap<String, Decimal>,
+    contracts: HashSet<String>,
+    denoms: HashSet<String>,
 }
 
 impl WasmMockQuerier {
@@ -36,6 +39,8 @@ impl WasmMockQuerier {
             raw: HashMap::new(),
             pairs: HashMap::new(),
             prices: HashMap::new(),
+            contracts: HashSet::new(),
+            denoms: HashSet::new(),
         }
     }
 
@@ -43,6 +48,12 @@ impl WasmMockQuerier {
         self.balances.insert((token, addr), amount);
     }
 
+    /// sets the balance of a native denom, also returned by the all balances query
+    pub fn set_native_balance(&mut self, denom: String, addr: String, amount: Uint128) {
+        self.denoms.insert(denom.clone());
+        self.set_balance(denom, addr, amount);
+    }
+
     fn get_balance(&self, token: String, addr: String) -> Uint128 {
         *self.balances.get(&(token, addr)).unwrap_or(&Uint128::zero())
     }
@@ -51,11 +62,19 @@ impl WasmMockQuerier {
         self.pairs.insert(pair_key(&asset_infos), pair_info);
     }
 
+    pub fn remove_pair(&mut self, asset_infos: &[AssetInfo; 2]) {
+        self.pairs.remove(&pair_key(asset_infos));
+    }
+
     fn get_pair(&self, asset_infos: &[AssetInfo; 2]) -> Option<&PairInfo> {
         self.pairs.get(&pair_key(&asset_infos))
     }
 
 
+    pub fn set_contract(&mut self, contract_addr: String) {
+        self.contracts.insert(contract_addr);
+    }
+
     pub fn set_price(&mut self, pair: String, price: Decimal) {
         self.prices.insert(pair, price);
     }
@@ -78,6 +97,21 @@ impl WasmMockQuerier {
                     },
                 })
             },
+            QueryRequest::Bank(BankQuery::AllBalances {
+                                   address,
+                               }) => {
+                let mut amount: Vec<Coin> = self.denoms.iter()
+                    .map(|denom| Coin {
+                        denom: denom.clone(),
+                        amount: self.get_balance(denom.clone(), address.clone()),
+                    })
+                    .filter(|coin| !coin.amount.is_zero())
+                    .collect();
+                amount.sort_by(|a, b| a.denom.cmp(&b.denom));
+                to_binary(&AllBalanceResponse {
+                    amount,
+                })
+            },
             QueryRequest::Wasm(WasmQuery::Smart {
                                    contract_addr,
                                    msg,
@@ -93,6 +127,14 @@ impl WasmMockQuerier {
                     Ok(Binary::default())
                 }
             },
+            QueryRequest::Wasm(WasmQuery::ContractInfo {
+                                   contract_addr,
+                               }) => {
+                if !self.contracts.contains(contract_addr) {
+                    return QuerierResult::Err(SystemError::NoSuchContract { addr: contract_addr.clone() });
+                }
+                to_binary(&ContractInfoResponse::new(1, MOCK_CONTRACT_ADDR))
+            },
             _ => return QuerierResult::Err(SystemError::Unknown {}),
         };
         QuerierResult::Ok(ContractResult::from(result))
@@ -141,6 +183,29 @@ impl WasmMockQuerier {
                     commission_amount: Default::default()
                 })
             },
+            MockQueryMsg::ReverseSimulation { ask_asset } => {
+                let price = *self.get_price(contract_addr)
+                    .ok_or_else(|| StdError::generic_err("No price"))?;
+                to_binary(&ReverseSimulationResponse {
+                    offer_amount: ask_asset.amount.multiply_ratio(price.denominator(), price.numerator()),
+                    spread_amount: Default::default(),
+                    commission_amount: Default::default()
+                })
+            },
+            MockQueryMsg::ReverseSimulateSwapOperations { ask_amount, operations } => {
+                let mut amount = ask_amount;
+                for operation in operations.iter().rev() {
+                    let asset_infos = [operation.get_offer_asset_info(), operation.get_ask_asset_info()];
+                    let pair_info = self.get_pair(&asset_infos)
+                        .ok_or_else(|| StdError::generic_err("No pair info"))?;
+                    let price = *self.get_price(&pair_info.contract_addr.to_string())
+                        .ok_or_else(|| StdError::generic_err("No price"))?;
+                    amount = amount.multiply_ratio(price.denominator(), price.numerator());
+                }
+                to_binary(&SimulateSwapOperationsResponse {
+                    amount,
+                })
+            },
         }
     }
 }
@@ -165,6 +230,13 @@ enum MockQueryMsg {
     Simulation {
         offer_asset: Asset,
     },
+    ReverseSimulation {
+        ask_asset: Asset,
+    },
+    ReverseSimulateSwapOperations {
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
 }
 
 impl Querier for WasmMockQuerier {
@@ -0,0 +1,311 @@
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// ## Description
+/// This structure describes the basic settings for creating a request for a change of ownership.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipProposal {
+    /// a new ownership.
+    pub owner: Addr,
+    /// time to live a request
+    pub ttl: u64,
+    /// delay between the first claim and the ownership change
+    #[serde(default)]
+    pub timelock: u64,
+    /// time when a timelocked claim can be finalized
+    #[serde(default)]
+    pub effective_at: Option<u64>,
+}
+
+/// ## Description
+/// Creates a new request to change ownership. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only owner can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `env` is the object of type [`Env`].
+///
+/// `new_owner` is a new owner.
+///
+/// `expires_in` is the validity period of the offer to change the owner.
+///
+/// `timelock` is the delay between claiming and finalizing the ownership change.
+///
+/// `owner` is the current owner.
+///
+/// `proposal` is the object of type [`OwnershipProposal`].
+#[allow(clippy::too_many_arguments)]
+pub fn propose_new_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    new_owner: String,
+    expires_in: u64,
+    timelock: Option<u64>,
+    owner: Addr,
+    proposal: Item<OwnershipProposal>,
+) -> StdResult<Response> {
+    // permission check
+    if info.sender != owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+
+    // check that owner is not the same
+    if new_owner == owner {
+        return Err(StdError::generic_err("New owner cannot be same"));
+    }
+
+    proposal.save(
+        deps.storage,
+        &OwnershipProposal {
+            owner: new_owner.clone(),
+            ttl: env.block.time.seconds() + expires_in,
+            timelock: timelock.unwrap_or_default(),
+            effective_at: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_owner"),
+        attr("new_owner", new_owner),
+    ]))
+}
+
+/// ## Description
+/// Removes a request to change ownership. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only owner can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `owner` is the current owner.
+///
+/// `proposal` is the object of type [`OwnershipProposal`].
+pub fn drop_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Addr,
+    proposal: Item<OwnershipProposal>,
+) -> StdResult<Response> {
+    // permission check
+    if info.sender != owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    proposal.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "drop_ownership_proposal")]))
+}
+
+/// ## Description
+/// Approves owner. When the proposal has a timelock, the first claim starts the delay and
+/// a second claim after the delay finalizes the change. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only owner can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `env` is the object of type [`Env`].
+///
+/// `proposal` is the object of type [`OwnershipProposal`].
+///
+/// `cb` is a type of callback function that takes two parameters of type [`DepsMut`] and [`Addr`].
+pub fn claim_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    proposal: Item<OwnershipProposal>,
+    cb: fn(DepsMut, Addr) -> StdResult<()>,
+) -> StdResult<Response> {
+    let mut p: OwnershipProposal = proposal
+        .load(deps.storage)
+        .map_err(|_| StdError::generic_err("Ownership proposal not found"))?;
+
+    // Check sender
+    if info.sender != p.owner {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let now = env.block.time.seconds();
+    match p.effective_at {
+        Some(effective_at) => {
+            if now < effective_at {
+                return Err(StdError::generic_err("Ownership claim is timelocked"));
+            }
+        }
+        None => {
+            if now > p.ttl {
+                return Err(StdError::generic_err("Ownership proposal expired"));
+            }
+
+            if p.timelock > 0 {
+                let effective_at = now + p.timelock;
+                p.effective_at = Some(effective_at);
+                proposal.save(deps.storage, &p)?;
+
+                return Ok(Response::new().add_attributes(vec![
+                    attr("action", "claim_ownership_pending"),
+                    attr("new_owner", p.owner),
+                    attr("effective_at", effective_at.to_string()),
+                ]));
+            }
+        }
+    }
+
+    proposal.remove(deps.storage);
+
+    // run callback
+    cb(deps, p.owner.clone())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_ownership"),
+        attr("new_owner", p.owner),
+    ]))
+}
+
+/// ## Description
+/// This structure describes the basic settings for creating a request for a change of operator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorProposal {
+    /// a new operator.
+    pub operator: Addr,
+    /// time to live a request
+    pub ttl: u64,
+}
+
+/// ## Description
+/// Creates a new request to change operator. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only operator can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `env` is the object of type [`Env`].
+///
+/// `new_operator` is a new operator.
+///
+/// `expires_in` is the validity period of the offer to change the operator.
+///
+/// `operator` is the current operator.
+///
+/// `proposal` is the object of type [`OperatorProposal`].
+pub fn propose_new_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    new_operator: String,
+    expires_in: u64,
+    operator: Addr,
+    proposal: Item<OperatorProposal>,
+) -> StdResult<Response> {
+    // permission check
+    if info.sender != operator {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    let new_operator = deps.api.addr_validate(&new_operator)?;
+
+    // check that operator is not the same
+    if new_operator == operator {
+        return Err(StdError::generic_err("New operator cannot be same"));
+    }
+
+    proposal.save(
+        deps.storage,
+        &OperatorProposal {
+            operator: new_operator.clone(),
+            ttl: env.block.time.seconds() + expires_in,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_operator"),
+        attr("new_operator", new_operator),
+    ]))
+}
+
+/// ## Description
+/// Removes a request to change operator. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only operator can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `operator` is the current operator.
+///
+/// `proposal` is the object of type [`OperatorProposal`].
+pub fn drop_operator_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: Addr,
+    proposal: Item<OperatorProposal>,
+) -> StdResult<Response> {
+    // permission check
+    if info.sender != operator {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    proposal.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "drop_operator_proposal")]))
+}
+
+/// ## Description
+/// Approves operator. Returns an [`Err`] on failure or returns the [`Response`] with the specified attributes if the operation was successful.
+/// ## Executor
+/// Only the proposed operator can execute it
+/// ## Params
+/// `deps` is the object of type [`DepsMut`].
+///
+/// `info` is the object of type [`MessageInfo`].
+///
+/// `env` is the object of type [`Env`].
+///
+/// `proposal` is the object of type [`OperatorProposal`].
+///
+/// `cb` is a type of callback function that takes two parameters of type [`DepsMut`] and [`Addr`].
+pub fn claim_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    env: Env,
+    proposal: Item<OperatorProposal>,
+    cb: fn(DepsMut, Addr) -> StdResult<()>,
+) -> StdResult<Response> {
+    let p: OperatorProposal = proposal
+        .load(deps.storage)
+        .map_err(|_| StdError::generic_err("Operator proposal not found"))?;
+
+    // Check sender
+    if info.sender != p.operator {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    if env.block.time.seconds() > p.ttl {
+        return Err(StdError::generic_err("Operator proposal expired"));
+    }
+
+    proposal.remove(deps.storage);
+
+    // run callback
+    cb(deps, p.operator.clone())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_operator"),
+        attr("new_operator", p.operator),
+    ]))
+}
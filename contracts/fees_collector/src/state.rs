@@ -1,8 +1,11 @@
-use astroport::{asset::AssetInfo, common::OwnershipProposal};
-use cosmwasm_std::{Addr};
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use spectrum::adapters::router::Router;
+use crate::ownership::{OperatorProposal, OwnershipProposal};
+use crate::utils::BRIDGES_EXECUTION_MAX_DEPTH;
 
 /// This structure stores the main parameter for the fees collector contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,17 +16,70 @@ pub struct Config {
     pub operator: Addr,
     /// The factory contract address
     pub factory_contract: Addr,
-    /// The list of address and weight to receive fees
-    pub target_list: Vec<(Addr, u64)>,
+    /// The list of address, weight and optional msg to receive fees
+    pub target_list: Vec<(Addr, u64, Option<Binary>)>,
     /// The stablecoin token address
     pub stablecoin: AssetInfo,
+    /// Swap execution depth limit
+    #[serde(default = "default_max_bridge_depth")]
+    pub max_bridge_depth: u64,
+    /// Recipient shares below this amount are skipped and absorbed by the following recipients
+    #[serde(default)]
+    pub min_distribution: Option<Uint128>,
+    /// Assets with balance below the amount are not swapped by collect and left to accumulate
+    #[serde(default)]
+    pub min_swap_amounts: Vec<(AssetInfo, Uint128)>,
+    /// The list of address and ratio to receive fees, ratios sum to one
+    #[serde(default)]
+    pub target_ratios: Vec<(Addr, Decimal)>,
+    /// If true, fees are split by target_ratios instead of the target_list weights
+    #[serde(default)]
+    pub use_target_ratios: bool,
+    /// The router contract address used to simulate reverse swaps
+    #[serde(default)]
+    pub router: Option<Router>,
+}
+
+impl Config {
+    pub fn min_swap_amount(&self, asset_info: &AssetInfo) -> Uint128 {
+        self.min_swap_amounts.iter()
+            .find(|(it, _)| it == asset_info)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default()
+    }
+}
+
+/// Config with (address, weight) target list, only read by migrate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub operator: Addr,
+    pub factory_contract: Addr,
+    pub target_list: Vec<(Addr, u64)>,
+    pub stablecoin: AssetInfo,
+    #[serde(default = "default_max_bridge_depth")]
+    pub max_bridge_depth: u64,
+    #[serde(default)]
+    pub min_distribution: Option<Uint128>,
+    #[serde(default)]
+    pub min_swap_amounts: Vec<(AssetInfo, Uint128)>,
+}
+
+fn default_max_bridge_depth() -> u64 {
+    BRIDGES_EXECUTION_MAX_DEPTH
 }
 
 /// Stores the contract configuration at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the config before target_list had msg, only read by migrate
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
 /// Stores bridge tokens used to swap fee tokens to stablecoin
 pub const BRIDGES: Map<String, AssetInfo> = Map::new("bridges");
 
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Stores the latest proposal to change contract operator
+pub const OPERATOR_PROPOSAL: Item<OperatorProposal> = Item::new("operator_proposal");
@@ -1,17 +1,18 @@
-use astroport::asset::{AssetInfo, PairInfo};
+use astroport::asset::{native_asset_info, Asset, AssetInfo, PairInfo};
 use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg,
 };
 use astroport::factory::PairType;
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Timestamp,
-    Uint128, WasmMsg, to_binary,
+    from_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Storage, Timestamp,
+    Uint128, WasmMsg, to_binary, attr, Reply, SubMsg, SubMsgResult,
 };
 use cw20::Cw20ExecuteMsg;
-use spectrum::fees_collector::{AssetWithLimit, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use spectrum::adapters::pair::Pair;
+use spectrum::fees_collector::{AssetWithLimit, BridgeValidationResponse, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReverseCollectSimulationResponse, SwapPath};
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query, reply};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use crate::state::{Config, CONFIG};
@@ -26,7 +27,9 @@ const FACTORY_1: &str = "factory_1";
 const FACTORY_2: &str = "factory_2";
 const TOKEN_1: &str = "token_1";
 const TOKEN_2: &str = "token_2";
+const TOKEN_3: &str = "token_3";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const ROUTER: &str = "router";
 
 #[test]
 fn test() -> Result<(), ContractError> {
@@ -34,9 +37,181 @@ fn test() -> Result<(), ContractError> {
     create(&mut deps)?;
     config(&mut deps)?;
     owner(&mut deps)?;
+    owner_timelock(&mut deps)?;
+    operator(&mut deps)?;
     bridges(&mut deps)?;
+    max_bridge_depth(&mut deps)?;
     collect(&mut deps)?;
+    swap_bridge_assets(&mut deps)?;
+    collect_best_effort(&mut deps)?;
+    collect_simulation_paths(&mut deps)?;
+    collect_min_swap_amount(&mut deps)?;
+    bridge_path(&mut deps)?;
+    reverse_collect_simulation(&mut deps)?;
     distribute_fees(&mut deps)?;
+    distribute_fees_min_distribution(&mut deps)?;
+    distribute_simulation(&mut deps)?;
+    distribute_fees_target_msg(&mut deps)?;
+    distribute_fees_target_ratios(&mut deps)?;
+    migrate_target_list(&mut deps)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_collect_all() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let mut pairs = vec![
+        (token_1.clone(), token_2.clone(), "token1token2"),
+        (token_2.clone(), ibc.clone(), "token2ibc"),
+    ];
+    for denom in ["uatom", "uluna", "uosmo"] {
+        pairs.push((AssetInfo::NativeToken { denom: denom.to_string() }, ibc.clone(), denom));
+    }
+    for (asset_0, asset_1, contract_addr) in pairs {
+        deps.querier.set_pair(
+            &[asset_0.clone(), asset_1.clone()],
+            PairInfo {
+                asset_infos: vec![asset_0, asset_1],
+                contract_addr: Addr::unchecked(contract_addr),
+                liquidity_token: Addr::unchecked("liquidity0000"),
+                pair_type: PairType::Xyk {},
+            },
+        );
+    }
+
+    let info = mock_info(OPERATOR_1, &[]);
+    let msg = ExecuteMsg::UpdateBridges {
+        add: Some(vec![(token_1.clone(), token_2.clone())]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    // three native denoms and the stablecoin, plus the bridged token and a denom without pair
+    for (denom, amount) in [("uatom", 100u128), ("uluna", 200u128), ("uosmo", 300u128), (IBC_TOKEN, 400u128), ("uspam", 600u128)] {
+        deps.querier.set_native_balance(denom.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(amount));
+    }
+    deps.querier.set_balance(TOKEN_1.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(500u128));
+
+    let msg = ExecuteMsg::CollectAll { minimum_receive: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let swap_msg = |pair: &str, asset_info: AssetInfo, amount: u128| {
+        Pair(Addr::unchecked(pair)).swap_msg(
+            &Asset { info: asset_info, amount: Uint128::from(amount) },
+            Some(Decimal::MAX),
+            Some(Decimal::percent(50)),
+            None,
+        )
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            swap_msg("uatom", native_asset_info("uatom".to_string()), 100)?,
+            swap_msg("uluna", native_asset_info("uluna".to_string()), 200)?,
+            swap_msg("uosmo", native_asset_info("uosmo".to_string()), 300)?,
+            swap_msg("token1token2", token_1, 500)?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::SwapBridgeAssets { assets: vec![token_2], depth: 0 })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+            }),
+        ]
+    );
+    assert_eq!(res.attributes, vec![
+        attr("action", "collect"),
+        attr("skipped", "uspam"),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_bridges() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let token_3 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_3),
+    };
+    let pairs = vec![
+        (token_1.clone(), token_2.clone(), "token1token2"),
+        (token_3.clone(), token_2.clone(), "token3token2"),
+        (token_2.clone(), ibc.clone(), "token2ibc"),
+    ];
+    for (asset_0, asset_1, contract_addr) in pairs {
+        deps.querier.set_pair(
+            &[asset_0.clone(), asset_1.clone()],
+            PairInfo {
+                asset_infos: vec![asset_0, asset_1],
+                contract_addr: Addr::unchecked(contract_addr),
+                liquidity_token: Addr::unchecked("liquidity0000"),
+                pair_type: PairType::Xyk {},
+            },
+        );
+    }
+
+    let info = mock_info(OPERATOR_1, &[]);
+    let msg = ExecuteMsg::UpdateBridges {
+        add: Some(vec![(token_1.clone(), token_2.clone()), (token_3.clone(), token_2.clone())]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let res: Vec<BridgeValidationResponse> =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ValidateBridges {})?)?;
+    assert_eq!(res, vec![
+        BridgeValidationResponse {
+            asset: TOKEN_1.to_string(),
+            bridge: TOKEN_2.to_string(),
+            error: None,
+        },
+        BridgeValidationResponse {
+            asset: TOKEN_3.to_string(),
+            bridge: TOKEN_2.to_string(),
+            error: None,
+        },
+    ]);
+
+    // the pair of token_3 is gone after the bridge was registered
+    deps.querier.remove_pair(&[token_3, token_2]);
+    let res: Vec<BridgeValidationResponse> =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::ValidateBridges {})?)?;
+    assert_eq!(res[0].error, None);
+    assert_eq!(res[1].asset, TOKEN_3.to_string());
+    assert_eq!(res[1].error, Some("Generic error: Querier contract error: Generic error: No pair info".to_string()));
 
     Ok(())
 }
@@ -62,7 +237,8 @@ fn create(
         stablecoin: AssetInfo::NativeToken {
             denom: IBC_TOKEN.to_string(),
         },
-        target_list: vec![(USER_2.to_string(), 2), (USER_3.to_string(), 3)],
+        target_list: vec![(USER_2.to_string(), 2, None), (USER_3.to_string(), 3, None)],
+        target_ratios: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg);
     assert!(res.is_ok());
@@ -74,10 +250,16 @@ fn create(
             owner: Addr::unchecked(USER_1),
             operator: Addr::unchecked(OPERATOR_1),
             factory_contract: Addr::unchecked(FACTORY_1),
-            target_list: vec![(Addr::unchecked(USER_2), 2), (Addr::unchecked(USER_3), 3)],
+            target_list: vec![(Addr::unchecked(USER_2), 2, None), (Addr::unchecked(USER_3), 3, None)],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            max_bridge_depth: 3,
+            min_distribution: None,
+            min_swap_amounts: vec![],
+            target_ratios: vec![],
+            use_target_ratios: false,
+            router: None,
         }
     );
 
@@ -94,6 +276,11 @@ fn config(
         operator: Some(OPERATOR_2.to_string()),
         factory_contract: None,
         target_list: None,
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -106,6 +293,11 @@ fn config(
         operator: None,
         factory_contract: Some(FACTORY_2.to_string()),
         target_list: None,
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -113,7 +305,12 @@ fn config(
     let msg = ExecuteMsg::UpdateConfig {
         operator: None,
         factory_contract: None,
-        target_list: Some(vec![(USER_1.to_string(), 1)]),
+        target_list: Some(vec![(USER_1.to_string(), 1, None)]),
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -126,17 +323,28 @@ fn config(
             owner: Addr::unchecked(USER_1),
             operator: Addr::unchecked(OPERATOR_2),
             factory_contract: Addr::unchecked(FACTORY_2),
-            target_list: vec![(Addr::unchecked(USER_1), 1)],
+            target_list: vec![(Addr::unchecked(USER_1), 1, None)],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            max_bridge_depth: 3,
+            min_distribution: None,
+            min_swap_amounts: vec![],
+            target_ratios: vec![],
+            use_target_ratios: false,
+            router: None,
         }
     );
 
     let msg = ExecuteMsg::UpdateConfig {
         operator: Some(OPERATOR_1.to_string()),
         factory_contract: Some(FACTORY_1.to_string()),
-        target_list: Some(vec![(USER_2.to_string(), 2), (USER_3.to_string(), 3)]),
+        target_list: Some(vec![(USER_2.to_string(), 2, None), (USER_3.to_string(), 3, None)]),
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -149,10 +357,16 @@ fn config(
             owner: Addr::unchecked(USER_1),
             operator: Addr::unchecked(OPERATOR_1),
             factory_contract: Addr::unchecked(FACTORY_1),
-            target_list: vec![(Addr::unchecked(USER_2), 2), (Addr::unchecked(USER_3), 3)],
+            target_list: vec![(Addr::unchecked(USER_2), 2, None), (Addr::unchecked(USER_3), 3, None)],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            max_bridge_depth: 3,
+            min_distribution: None,
+            min_swap_amounts: vec![],
+            target_ratios: vec![],
+            use_target_ratios: false,
+            router: None,
         }
     );
 
@@ -167,6 +381,7 @@ fn owner(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<
     let msg = ExecuteMsg::ProposeNewOwner {
         owner: OWNER.to_string(),
         expires_in: 100,
+        timelock: None,
     };
 
     let info = mock_info(USER_2, &[]);
@@ -256,6 +471,180 @@ fn owner(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<
     Ok(())
 }
 
+fn owner_timelock(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(1000);
+
+    // propose new owner with timelock
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: USER_2.to_string(),
+        expires_in: 100,
+        timelock: Some(50),
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // first claim starts the timelock
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ClaimOwnership {})?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_ownership_pending"),
+            attr("new_owner", USER_2),
+            attr("effective_at", "1050"),
+        ]
+    );
+    let config: Config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(OWNER, config.owner);
+
+    // claim before the timelock passes
+    env.block.time = Timestamp::from_seconds(1049);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ClaimOwnership {});
+    assert_error(res, "Ownership claim is timelocked");
+
+    // claim after the timelock, even beyond the proposal ttl
+    env.block.time = Timestamp::from_seconds(1150);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {})?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_ownership"),
+            attr("new_owner", USER_2),
+        ]
+    );
+    let config: Config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(USER_2, config.owner);
+
+    // hand ownership back immediately without timelock
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: OWNER.to_string(),
+        expires_in: 100,
+        timelock: Some(0),
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimOwnership {})?;
+    assert_eq!(res.attributes[0], attr("action", "claim_ownership"));
+    let config: Config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(OWNER, config.owner);
+
+    Ok(())
+}
+
+fn operator(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(0);
+
+    // new operator
+    let msg = ExecuteMsg::ProposeNewOperator {
+        operator: OPERATOR_2.to_string(),
+        expires_in: 100,
+    };
+
+    let info = mock_info(OWNER, &[]);
+
+    // unauthorized check
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // claim before a proposal
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimOperator {},
+    );
+    assert_error(res, "Operator proposal not found");
+
+    // propose new operator
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_ok());
+
+    // drop operator proposal
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::DropOperatorProposal {},
+    );
+    assert!(res.is_ok());
+
+    // operator proposal dropped
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimOperator {},
+    );
+    assert_error(res, "Operator proposal not found");
+
+    // propose new operator again
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_ok());
+
+    // unauthorized operator claim
+    let info = mock_info(USER_3, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimOperator {},
+    );
+    assert_error(res, "Unauthorized");
+
+    env.block.time = Timestamp::from_seconds(101);
+
+    // operator proposal expired
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimOperator {},
+    );
+    assert_error(res, "Operator proposal expired");
+
+    env.block.time = Timestamp::from_seconds(100);
+
+    // claim operator
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::ClaimOperator {},
+    )?;
+    assert_eq!(0, res.messages.len());
+
+    // query config
+    let config: Config =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(OPERATOR_2, config.operator);
+
+    // hand operator back
+    let info = mock_info(OPERATOR_2, &[]);
+    let msg = ExecuteMsg::ProposeNewOperator {
+        operator: OPERATOR_1.to_string(),
+        expires_in: 100,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    let info = mock_info(OPERATOR_1, &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOperator {})?;
+
+    let config: Config =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(OPERATOR_1, config.operator);
+    Ok(())
+}
+
 fn bridges(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -355,6 +744,121 @@ fn bridges(
     Ok(())
 }
 
+fn max_bridge_depth(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let token_4 = AssetInfo::Token {
+        contract_addr: Addr::unchecked("token_4"),
+    };
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    deps.querier.set_pair(
+        &[token_4.clone(), token_1.clone()],
+        PairInfo {
+            asset_infos: vec![token_4.clone(), token_1.clone()],
+            contract_addr: Addr::unchecked("token4token1"),
+            liquidity_token: Addr::unchecked("liquidity0004"),
+            pair_type: PairType::Xyk {},
+        },
+    );
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: Some(0),
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "max_bridge_depth must be at least 1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: Some(1),
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    // token_4 -> token_1 -> token_2 -> stablecoin needs 2 bridge executions
+    let msg = ExecuteMsg::UpdateBridges {
+        add: Some(vec![(token_4.clone(), token_1.clone()), (token_1.clone(), token_2)]),
+        remove: None,
+    };
+    let operator_info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), operator_info.clone(), msg.clone());
+    assert_error(res, "Max bridge length of 0 was reached");
+
+    let contract_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), contract_info, ExecuteMsg::SwapBridgeAssets {
+        assets: vec![token_1.clone()],
+        depth: 1,
+    });
+    assert_error(res, "Max bridge length of 1 was reached");
+
+    let msg_depth = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: Some(2),
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg_depth)?;
+    execute(deps.as_mut(), env.clone(), operator_info.clone(), msg)?;
+
+    // lowering the depth below the stored bridges is rejected
+    let msg_depth = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: Some(1),
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg_depth);
+    assert_error(res, "Max bridge length of 0 was reached");
+
+    let msg = ExecuteMsg::UpdateBridges {
+        add: None,
+        remove: Some(vec![token_4, token_1]),
+    };
+    execute(deps.as_mut(), env.clone(), operator_info, msg)?;
+
+    // restore the default depth
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: Some(3),
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env, info, msg)?;
+
+    Ok(())
+}
+
 fn collect(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -383,7 +887,8 @@ fn collect(
             },
             limit: None,
         }],
-        minimum_receive: None
+        minimum_receive: None,
+        best_effort: false,
     };
 
     let info = mock_info(USER_1, &[]);
@@ -467,6 +972,13 @@ fn collect(
         res,
         CollectSimulationResponse {
             return_amount: Uint128::from(500000u128),
+            paths: vec![
+                (AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) }, SwapPath::Bridge),
+            ],
+            breakdown: vec![
+                (IBC_TOKEN.to_string(), Uint128::zero()),
+                (TOKEN_1.to_string(), Uint128::from(500000u128)),
+            ],
         }
     );
 
@@ -484,7 +996,8 @@ fn collect(
             },
             limit: Some(Uint128::from(1500000u128)),
         }],
-        minimum_receive: None
+        minimum_receive: None,
+        best_effort: false,
     };
 
     // collect success
@@ -521,21 +1034,461 @@ fn collect(
     Ok(())
 }
 
-fn distribute_fees(
+fn swap_bridge_assets(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
     let env = mock_env();
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
 
-    // set balance
-    deps.querier.set_balance(
-        IBC_TOKEN.to_string(),
-        MOCK_CONTRACT_ADDR.to_string(),
-        Uint128::from(1000000u128),
+    // token_1 -> token_2 -> stablecoin, each hop is a recursive invocation
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::SwapBridgeAssets {
+        assets: vec![token_1.clone()],
+        depth: 0,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "swap_bridge_assets"),
+            attr("depth", "0"),
+            attr("swapped", TOKEN_1),
+            attr("remaining_bridges", "1"),
+        ]
+    );
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::SwapBridgeAssets { assets: vec![token_2.clone()], depth: 1 })?,
+        }))
     );
 
-    let msg = ExecuteMsg::DistributeFees { minimum_receive: Some(Uint128::from(2000000u128)) };
-
-    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::SwapBridgeAssets {
+        assets: vec![token_2],
+        depth: 1,
+    };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "swap_bridge_assets"),
+            attr("depth", "1"),
+            attr("swapped", TOKEN_2),
+            attr("remaining_bridges", "0"),
+        ]
+    );
+    assert_eq!(res.messages.len(), 1);
+
+    Ok(())
+}
+
+fn collect_best_effort(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            limit: None,
+        }],
+        minimum_receive: Some(Uint128::from(1000000u128)),
+        best_effort: true,
+    };
+
+    // swaps and the minimum receive are plain messages, distribution is caught on error
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: TOKEN_2.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "token2ibc".to_string(),
+                    amount: Uint128::new(2000000u128),
+                    msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                        ask_asset_info: None,
+                        belief_price: Some(Decimal::MAX),
+                        max_spread: Some(Decimal::percent(50)),
+                        to: None,
+                    })?
+                })?,
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::AssertMinimumReceive { minimum_receive: Uint128::from(1000000u128) })?,
+            }),
+            SubMsg::reply_on_error(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+            }, 1),
+        ]
+    );
+
+    // the floor is missed, the plain message fails and reverts the whole tx
+    let msg = ExecuteMsg::AssertMinimumReceive { minimum_receive: Uint128::from(1000000u128) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OPERATOR_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err().to_string();
+    assert_eq!(err, "Assertion failed; minimum receive amount: 1000000, actual amount: 0");
+
+    deps.querier.set_native_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(1000000u128));
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_native_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    // distribution fails after the swaps
+    let err = "Generic error: distribution failed".to_string();
+
+    // reply swallows the failure
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 1,
+        result: SubMsgResult::Err(err.clone()),
+    })?;
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_fees_failed"),
+            attr("error", err),
+        ]
+    );
+
+    // unexpected reply
+    let res = reply(deps.as_mut(), env, Reply {
+        id: 2,
+        result: SubMsgResult::Err("error".to_string()),
+    });
+    assert_error(res, "Unexpected reply");
+
+    Ok(())
+}
+
+fn collect_simulation_paths(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_3 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_3) };
+    let uluna = AssetInfo::NativeToken { denom: "uluna".to_string() };
+    let ibc = AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() };
+
+    // token_3 has no bridge and no stablecoin pair, only a uluna pair
+    deps.querier.set_pair(
+        &[token_3.clone(), uluna.clone()],
+        PairInfo {
+            asset_infos: vec![token_3.clone(), uluna.clone()],
+            contract_addr: Addr::unchecked("token3uluna"),
+            liquidity_token: Addr::unchecked("liquidity0003"),
+            pair_type: PairType::Xyk {},
+        },
+    );
+    deps.querier.set_pair(
+        &[uluna.clone(), ibc.clone()],
+        PairInfo {
+            asset_infos: vec![uluna, ibc],
+            contract_addr: Addr::unchecked("ulunaibc"),
+            liquidity_token: Addr::unchecked("liquidity0004"),
+            pair_type: PairType::Xyk {},
+        },
+    );
+    deps.querier.set_price("token3uluna".to_string(), Decimal::percent(100u64));
+    deps.querier.set_price("ulunaibc".to_string(), Decimal::percent(10u64));
+    deps.querier.set_balance(
+        TOKEN_3.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000000u128),
+    );
+
+    // token_1 via bridge, token_2 directly, token_3 via uluna
+    let msg = QueryMsg::CollectSimulation {
+        assets: vec![
+            AssetWithLimit {
+                info: token_3.clone(),
+                limit: None,
+            },
+            AssetWithLimit {
+                info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) },
+                limit: None,
+            },
+            AssetWithLimit {
+                info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) },
+                limit: None,
+            },
+        ],
+    };
+    let res: CollectSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        CollectSimulationResponse {
+            return_amount: Uint128::from(1100000u128),
+            paths: vec![
+                (AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) }, SwapPath::Bridge),
+                (AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }, SwapPath::Direct),
+                (token_3, SwapPath::Intermediate),
+            ],
+            breakdown: vec![
+                (IBC_TOKEN.to_string(), Uint128::zero()),
+                (TOKEN_1.to_string(), Uint128::from(500000u128)),
+                (TOKEN_2.to_string(), Uint128::from(500000u128)),
+                (TOKEN_3.to_string(), Uint128::from(100000u128)),
+            ],
+        }
+    );
+
+    // token_1 via bridge, token_2 directly
+    let msg = QueryMsg::CollectSimulation {
+        assets: vec![
+            AssetWithLimit {
+                info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) },
+                limit: None,
+            },
+            AssetWithLimit {
+                info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) },
+                limit: None,
+            },
+        ],
+    };
+    let res: CollectSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.return_amount, Uint128::from(1000000u128));
+    assert_eq!(
+        res.breakdown,
+        vec![
+            (IBC_TOKEN.to_string(), Uint128::zero()),
+            (TOKEN_1.to_string(), Uint128::from(500000u128)),
+            (TOKEN_2.to_string(), Uint128::from(500000u128)),
+        ]
+    );
+    let total: Uint128 = res.breakdown.iter().map(|(_, amount)| *amount).sum();
+    assert_eq!(total, res.return_amount);
+
+    Ok(())
+}
+
+fn collect_min_swap_amount(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) };
+    let token_3 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_3) };
+
+    // token_1 balance is above its threshold, token_3 balance is below
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: Some(vec![
+            (token_1.clone(), Uint128::from(500000u128)),
+            (token_3.clone(), Uint128::from(2000000u128)),
+        ]),
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let assets = vec![
+        AssetWithLimit {
+            info: token_1.clone(),
+            limit: None,
+        },
+        AssetWithLimit {
+            info: token_3,
+            limit: None,
+        },
+    ];
+    let msg = ExecuteMsg::Collect {
+        assets: assets.clone(),
+        minimum_receive: None,
+        best_effort: false,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OPERATOR_1, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "token1token2".to_string(),
+                    amount: Uint128::new(1000000u128),
+                    msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                        ask_asset_info: None,
+                        belief_price: Some(Decimal::MAX),
+                        max_spread: Some(Decimal::percent(50)),
+                        to: None,
+                    })?
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::SwapBridgeAssets { assets: vec![AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }], depth: 0 })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+            }),
+        ]
+    );
+
+    // simulation skips token_3 as well
+    let msg = QueryMsg::CollectSimulation { assets };
+    let res: CollectSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        CollectSimulationResponse {
+            return_amount: Uint128::from(500000u128),
+            paths: vec![
+                (token_1, SwapPath::Bridge),
+            ],
+            breakdown: vec![
+                (IBC_TOKEN.to_string(), Uint128::zero()),
+                (TOKEN_1.to_string(), Uint128::from(500000u128)),
+                (TOKEN_3.to_string(), Uint128::zero()),
+            ],
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: Some(vec![]),
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env, info, msg)?;
+
+    Ok(())
+}
+
+fn bridge_path(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) };
+    let token_2 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) };
+    let token_3 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_3) };
+    let uluna = AssetInfo::NativeToken { denom: "uluna".to_string() };
+    let ibc = AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() };
+
+    let cases = [
+        // direct to stablecoin
+        (token_2.clone(), vec![token_2.clone(), ibc.clone()]),
+        // via bridge
+        (token_1.clone(), vec![token_1, token_2, ibc.clone()]),
+        // via uluna fallback
+        (token_3.clone(), vec![token_3, uluna, ibc.clone()]),
+        (ibc.clone(), vec![ibc]),
+    ];
+    for (asset, expected) in cases {
+        let msg = QueryMsg::BridgePath { asset };
+        let res: Vec<AssetInfo> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res, expected);
+    }
+
+    let msg = QueryMsg::BridgePath {
+        asset: AssetInfo::Token { contract_addr: Addr::unchecked("token_5") },
+    };
+    let res = query(deps.as_ref(), env, msg);
+    assert_eq!(res, Err(StdError::generic_err("Cannot swap token_5. No swap destinations")));
+
+    Ok(())
+}
+
+fn reverse_collect_simulation(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) };
+    let token_3 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_3) };
+    let ibc = AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() };
+
+    // reverse simulation requires the router
+    let msg = QueryMsg::ReverseCollectSimulation {
+        asset: token_1.clone(),
+        return_amount: Uint128::from(100u128),
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Generic error: router is not set")));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: Some(ROUTER.to_string()),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    let cases = [
+        // via bridge, token1 -> token2 at 2, token2 -> ibc at 0.25
+        (token_1, vec![200u128, 400u128, 100u128]),
+        // via uluna fallback, token3 -> uluna at 1, uluna -> ibc at 0.1
+        (token_3, vec![1000u128, 1000u128, 100u128]),
+        (ibc, vec![100u128]),
+    ];
+    for (asset, amounts) in cases {
+        let msg = QueryMsg::ReverseCollectSimulation {
+            asset,
+            return_amount: Uint128::from(100u128),
+        };
+        let res: ReverseCollectSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(
+            res,
+            ReverseCollectSimulationResponse {
+                offer_amount: Uint128::from(amounts[0]),
+                amounts: amounts.into_iter().map(Uint128::from).collect(),
+            }
+        );
+    }
+
+    Ok(())
+}
+
+fn distribute_fees(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // set balance
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000000u128),
+    );
+
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: Some(Uint128::from(2000000u128)) };
+
+    let info = mock_info(USER_1, &[]);
 
     // unauthorized check
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -573,3 +1526,304 @@ fn distribute_fees(
 
     Ok(())
 }
+
+fn distribute_fees_min_distribution(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1, None), (USER_2.to_string(), 3, None), (USER_3.to_string(), 6, None)]),
+        max_bridge_depth: None,
+        min_distribution: Some(Uint128::from(3u128)),
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(20u128),
+    );
+
+    // user1 share is 2, below the floor, so it is absorbed by user2 (20 * 3 / 9 = 6) and user3 (14)
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_2.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(6u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_3.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(14u128),
+                }]
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn distribute_simulation(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1, None), (USER_2.to_string(), 2, None), (USER_3.to_string(), 4, None)]),
+        max_bridge_depth: None,
+        min_distribution: Some(Uint128::zero()),
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(100u128),
+    );
+
+    // 100 * 1 / 7, 100 * 2 / 7 and 100 * 4 / 7 are rounded down, the remainder stays in the contract
+    let res: Vec<(Addr, Uint128)> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::DistributeSimulation {})?)?;
+    assert_eq!(res, vec![
+        (Addr::unchecked(USER_1), Uint128::from(14u128)),
+        (Addr::unchecked(USER_2), Uint128::from(28u128)),
+        (Addr::unchecked(USER_3), Uint128::from(57u128)),
+        (Addr::unchecked(MOCK_CONTRACT_ADDR), Uint128::from(1u128)),
+    ]);
+    assert_eq!(res.iter().map(|(_, amount)| *amount).sum::<Uint128>(), Uint128::from(100u128));
+
+    // the simulated amounts are sent by distribute
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None };
+    let distribute = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        distribute.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        res[..3].iter()
+            .map(|(to, amount)| CosmosMsg::Bank(BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: *amount,
+                }]
+            }))
+            .collect::<Vec<CosmosMsg>>(),
+    );
+
+    Ok(())
+}
+
+fn distribute_fees_target_msg(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let hook = to_binary(&"deposit")?;
+
+    // target with msg must be a contract
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1, None), (USER_2.to_string(), 3, Some(hook.clone()))]),
+        max_bridge_depth: None,
+        min_distribution: Some(Uint128::zero()),
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "target with msg must be a contract: user_2");
+
+    deps.querier.set_contract(USER_2.to_string());
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(100u128),
+    );
+
+    // plain target gets a bank send, target with msg gets the msg with funds
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(25u128),
+                }]
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: USER_2.to_string(),
+                msg: hook,
+                funds: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(75u128),
+                }],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn distribute_fees_target_ratios(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let info = mock_info(OWNER, &[]);
+    let update_ratios = |target_ratios: Vec<(&str, u64)>| ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        max_bridge_depth: None,
+        min_distribution: Some(Uint128::zero()),
+        min_swap_amounts: None,
+        target_ratios: Some(target_ratios.into_iter()
+            .map(|(addr, percent)| (addr.to_string(), Decimal::percent(percent)))
+            .collect()),
+        router: None,
+    };
+
+    // ratios must sum to one
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_ratios(vec![(USER_2, 33), (USER_3, 66)]));
+    assert_error(res, "target_ratios must sum to 1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1, None)]),
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: Some(vec![(USER_1.to_string(), Decimal::one())]),
+        router: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "target_list and target_ratios cannot be both set");
+
+    execute(deps.as_mut(), env.clone(), info.clone(), update_ratios(vec![(USER_1, 20), (USER_2, 30), (USER_3, 50)]))?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert!(config.use_target_ratios);
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000000u128),
+    );
+
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(200000u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_2.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(300000u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_3.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(500000u128),
+                }]
+            }),
+        ]
+    );
+
+    // setting target_list switches back to weights
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_2.to_string(), 2, None), (USER_3.to_string(), 3, None)]),
+        max_bridge_depth: None,
+        min_distribution: None,
+        min_swap_amounts: None,
+        target_ratios: None,
+        router: None,
+    };
+    execute(deps.as_mut(), env, info, msg)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert!(!config.use_target_ratios);
+
+    Ok(())
+}
+
+fn migrate_target_list(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // config without msg in target_list
+    deps.storage.set(
+        b"config",
+        br#"{"owner":"owner","operator":"operator_1","factory_contract":"factory_1","target_list":[["user_2",2],["user_3",3]],"stablecoin":{"native_token":{"denom":"ibc/stablecoin"}}}"#,
+    );
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {})?;
+
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(config.target_list, vec![(Addr::unchecked(USER_2), 2, None), (Addr::unchecked(USER_3), 3, None)]);
+
+    // migrating again keeps the config
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {})?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?, config);
+
+    // config in ratio mode has an empty target_list, ratios are kept
+    let config = Config {
+        target_list: vec![],
+        target_ratios: vec![(Addr::unchecked(USER_2), Decimal::percent(40)), (Addr::unchecked(USER_3), Decimal::percent(60))],
+        use_target_ratios: true,
+        ..config
+    };
+    CONFIG.save(deps.as_mut().storage, &config)?;
+    migrate(deps.as_mut(), env, MigrateMsg {})?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?, config);
+
+    Ok(())
+}
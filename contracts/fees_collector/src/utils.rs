@@ -8,9 +8,7 @@ use spectrum::fees_collector::ExecuteMsg;
 
 /// The default bridge depth for a fee token
 pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
-/// Maximum amount of bridges to use in a multi-hop swap
-pub const BRIDGES_MAX_DEPTH: u64 = 2;
-/// Swap execution depth limit
+/// Default swap execution depth limit
 pub const BRIDGES_EXECUTION_MAX_DEPTH: u64 = 3;
 
 /// Maximum spread percentage when swapping
@@ -69,7 +67,7 @@ pub fn build_swap_bridge_msg(
     Ok(msg)
 }
 
-/// Validates bridge token
+/// Validates bridge token, the path must fit within max_depth swap executions
 pub fn validate_bridge(
     deps: Deps,
     factory_contract: &Addr,
@@ -77,6 +75,7 @@ pub fn validate_bridge(
     bridge_token: &AssetInfo,
     stablecoin_token: &AssetInfo,
     depth: u64,
+    max_depth: u64,
 ) -> Result<PairInfo, ContractError> {
     // Check if the bridge pool exists
     let bridge_pool = query_pair_info(&deps.querier, factory_contract, &[from_token.clone(), bridge_token.clone()])?;
@@ -84,7 +83,7 @@ pub fn validate_bridge(
     // Check if the bridge token - stablecoin pool exists
     let stablecoin_pool = query_pair_info(&deps.querier, factory_contract, &[bridge_token.clone(), stablecoin_token.clone()]);
     if stablecoin_pool.is_err() {
-        if depth >= BRIDGES_MAX_DEPTH {
+        if depth + 1 >= max_depth {
             return Err(ContractError::MaxBridgeDepth(depth));
         }
 
@@ -100,6 +99,7 @@ pub fn validate_bridge(
             &next_bridge_token,
             stablecoin_token,
             depth + 1,
+            max_depth,
         )?;
     }
 
@@ -1,15 +1,17 @@
 use std::cmp;
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128};
 use astroport::asset::{Asset, token_asset};
 use astroport::querier::query_token_balance;
 use crate::error::ContractError;
 use astroport::generator::{PendingTokenResponse, UserInfoV2};
 use astroport::restricted_vector::RestrictedVector;
+use cw_storage_plus::Bound;
 use spectrum::adapters::asset::AssetEx;
 use crate::astro_generator::GeneratorEx;
-use crate::model::{CallbackMsg, Config, PoolInfo, RewardInfo, UserInfo};
-use crate::state::{CONFIG, POOL_INFO, REWARD_INFO, USER_INFO};
+use crate::oper::{load_boost_fee, load_token_fee};
+use crate::model::{BoostInfoResponse, CallbackMsg, Config, PendingTokenAllResponse, PoolInfo, RewardInfo, UserInfo};
+use crate::state::{CONFIG, POOL_CONFIG, POOL_FEE, POOL_INFO, REWARD_INFO, TOTAL_FEE, USER_INFO};
 
 pub fn execute_deposit(
     deps: DepsMut,
@@ -60,6 +62,7 @@ pub fn execute_withdraw(
     info: MessageInfo,
     lp_token: String,
     amount: Uint128,
+    claim_rewards: Option<bool>,
 ) -> Result<Response, ContractError> {
     let lp_token = deps.api.addr_validate(&lp_token)?;
     let config = CONFIG.load(deps.storage)?;
@@ -85,29 +88,57 @@ pub fn execute_withdraw(
         }.to_cosmos_msg(&env.contract.address)?);
     }
 
-    Ok(Response::new()
-        .add_messages(messages)
-        .add_message(CallbackMsg::Withdraw {
+    messages.push(CallbackMsg::Withdraw {
+        lp_token: lp_token.clone(),
+        staker_addr: info.sender.clone(),
+        amount,
+    }.to_cosmos_msg(&env.contract.address)?);
+    if claim_rewards.unwrap_or(false) {
+        messages.push(CallbackMsg::ClaimRewards {
             lp_token,
             staker_addr: info.sender,
-            amount,
-        }.to_cosmos_msg(&env.contract.address)?)
+        }.to_cosmos_msg(&env.contract.address)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
         .add_attribute("action", "withdraw")
     )
 }
 
-pub fn execute_claim_rewards(
+/// Maximum number of pools withdrawn by a single WithdrawAll
+const MAX_WITHDRAW_ALL_POOLS: usize = 10;
+
+pub fn execute_withdraw_all(
     mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    lp_tokens: Vec<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
+    let mut user_infos: Vec<(Addr, UserInfo)> = vec![];
+    for lp_token in POOL_INFO.keys(deps.storage, None, None, Order::Ascending) {
+        let lp_token = lp_token?;
+        match USER_INFO.may_load(deps.storage, (&lp_token, &info.sender))? {
+            Some(user_info) if !user_info.bond_share.is_zero() => user_infos.push((lp_token, user_info)),
+            _ => continue,
+        };
+        if user_infos.len() > MAX_WITHDRAW_ALL_POOLS {
+            return Err(StdError::generic_err(
+                format!("bonded in more than {} pools, use Withdraw for each lp_token", MAX_WITHDRAW_ALL_POOLS)
+            ).into());
+        }
+    }
 
     let mut messages: Vec<CosmosMsg> = vec![];
+    let mut pool_count = 0usize;
+    for (lp_token, user_info) in user_infos {
+        let pool_info = POOL_INFO.load(deps.storage, &lp_token)?;
+        let total_bond_amount = config.generator.query_deposit(&deps.querier, &lp_token, &env.contract.address)?;
+        let amount = pool_info.calc_bond_amount(total_bond_amount, user_info.bond_share);
+        if amount.is_zero() {
+            continue;
+        }
 
-    for lp_token in lp_tokens {
-        let lp_token = deps.api.addr_validate(&lp_token)?;
         let astro_user_info = config.generator.query_user_info(&deps.querier, &lp_token, &env.contract.address)?
             .ok_or_else(|| StdError::generic_err("UserInfo is not found"))?;
         let (claim, prev_balances) = reconcile_claimed_by_others(
@@ -127,35 +158,167 @@ pub fn execute_claim_rewards(
                 prev_balances,
             }.to_cosmos_msg(&env.contract.address)?);
         }
-        messages.push(CallbackMsg::ClaimRewards {
+        messages.push(CallbackMsg::Withdraw {
             lp_token,
             staker_addr: info.sender.clone(),
+            amount,
         }.to_cosmos_msg(&env.contract.address)?);
+        pool_count += 1;
     }
 
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "withdraw_all")
+        .add_attribute("pool_count", pool_count.to_string())
+    )
+}
+
+pub fn execute_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+    let lp_tokens = lp_tokens.iter()
+        .map(|lp_token| deps.api.addr_validate(lp_token))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let messages = claim_rewards_msgs(deps, &env, &info.sender, lp_tokens)?;
+
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("action", "claim_rewards")
     )
 }
 
+/// Maximum number of pools claimed by a single ClaimAllRewards
+const MAX_CLAIM_ALL_POOLS: usize = 30;
+
+pub fn execute_claim_all_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut lp_tokens: Vec<Addr> = vec![];
+    for lp_token in POOL_INFO.keys(deps.storage, None, None, Order::Ascending) {
+        let lp_token = lp_token?;
+        match USER_INFO.may_load(deps.storage, (&lp_token, &info.sender))? {
+            Some(user_info) if !user_info.bond_share.is_zero() => lp_tokens.push(lp_token),
+            _ => continue,
+        };
+        if lp_tokens.len() > MAX_CLAIM_ALL_POOLS {
+            return Err(StdError::generic_err(
+                format!("bonded in more than {} pools, use ClaimRewards with explicit lp_tokens", MAX_CLAIM_ALL_POOLS)
+            ).into());
+        }
+    }
+
+    let pool_count = lp_tokens.len();
+    let messages = claim_rewards_msgs(deps, &env, &info.sender, lp_tokens)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "claim_all_rewards")
+        .add_attribute("pool_count", pool_count.to_string())
+    )
+}
+
+fn claim_rewards_msgs(
+    mut deps: DepsMut,
+    env: &Env,
+    staker_addr: &Addr,
+    lp_tokens: Vec<Addr>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    for lp_token in lp_tokens {
+        let astro_user_info = config.generator.query_user_info(&deps.querier, &lp_token, &env.contract.address)?
+            .ok_or_else(|| StdError::generic_err("UserInfo is not found"))?;
+        let (claim, prev_balances) = reconcile_claimed_by_others(
+            deps.branch(),
+            env,
+            &config,
+            &lp_token,
+            &astro_user_info
+        )?;
+        if claim {
+            messages.push(config.generator.withdraw_msg(lp_token.to_string(), Uint128::from(1u128))?);
+            messages.push(
+                config.generator.deposit_msg(lp_token.to_string(), Uint128::from(1u128))?
+            );
+            messages.push(CallbackMsg::AfterBondClaimed {
+                lp_token: lp_token.clone(),
+                prev_balances,
+            }.to_cosmos_msg(&env.contract.address)?);
+        }
+        messages.push(CallbackMsg::ClaimRewards {
+            lp_token,
+            staker_addr: staker_addr.clone(),
+        }.to_cosmos_msg(&env.contract.address)?);
+    }
+
+    Ok(messages)
+}
+
+/// Returns proxy reward tokens beyond max_reward_tokens, tokens with deferred rewards go first then by address
+fn skipped_reward_tokens(
+    config: &Config,
+    pool_info: &PoolInfo,
+    astro_user_info: &UserInfoV2,
+) -> Vec<Addr> {
+    let max_reward_tokens = config.max_reward_tokens as usize;
+    if max_reward_tokens == 0 || astro_user_info.reward_debt_proxy.inner_ref().len() <= max_reward_tokens {
+        return vec![];
+    }
+
+    let mut tokens: Vec<Addr> = astro_user_info.reward_debt_proxy.inner_ref().iter()
+        .map(|(token, _)| token.clone())
+        .collect();
+    tokens.sort_by_key(|token| (pool_info.deferred_reward(token).is_zero(), token.clone()));
+    tokens.split_off(max_reward_tokens)
+}
+
 fn fetch_balance(
     querier: &QuerierWrapper,
     config: &Config,
     contract_addr: &Addr,
     astro_user_info: &UserInfoV2,
+    skipped_tokens: &[Addr],
+    asset_rewards: &[Addr],
 ) -> StdResult<Vec<(Addr, Uint128)>> {
     let astro_amount = query_token_balance(querier, &config.astro_token, contract_addr)?;
     let mut balances: Vec<(Addr, Uint128)> = vec![
         (config.astro_token.clone(), astro_amount)
     ];
     for (token, _) in astro_user_info.reward_debt_proxy.inner_ref() {
+        if skipped_tokens.contains(token) {
+            continue;
+        }
         let token_amount = query_token_balance(querier, token, contract_addr)?;
         balances.push((token.clone(), token_amount));
     }
+    push_asset_balances(querier, contract_addr, asset_rewards, &mut balances)?;
     Ok(balances)
 }
 
+/// Appends balances of the extra reward assets not already tracked
+fn push_asset_balances(
+    querier: &QuerierWrapper,
+    contract_addr: &Addr,
+    asset_rewards: &[Addr],
+    balances: &mut Vec<(Addr, Uint128)>,
+) -> StdResult<()> {
+    for asset in asset_rewards {
+        if balances.iter().any(|(token, _)| token == asset) {
+            continue;
+        }
+        let asset_amount = query_token_balance(querier, asset, contract_addr)?;
+        balances.push((asset.clone(), asset_amount));
+    }
+    Ok(())
+}
+
 fn reconcile_claimed_by_others(
     deps: DepsMut,
     env: &Env,
@@ -165,19 +328,20 @@ fn reconcile_claimed_by_others(
 ) -> StdResult<(bool, Vec<(Addr, Uint128)>)> {
 
     // load
-    let pool_info_op = POOL_INFO.may_load(deps.storage, lp_token)?
-        .filter(|pool_info| !pool_info.total_bond_share.is_zero());
-    let mut pool_info = match pool_info_op {
-        None => {
-            let balances = fetch_balance(&deps.querier, config, &env.contract.address, astro_user_info)?;
-            return Ok((true, balances))
-        },
-        Some(pool_info) if pool_info.last_reconcile == env.block.height => {
-            let balances = fetch_balance(&deps.querier, config, &env.contract.address, astro_user_info)?;
-            return Ok((false, balances))
-        },
-        Some(pool_info) => pool_info,
-    };
+    let mut pool_info = POOL_INFO.may_load(deps.storage, lp_token)?
+        .unwrap_or_default();
+    let skipped_tokens = skipped_reward_tokens(config, &pool_info, astro_user_info);
+    let asset_rewards = POOL_CONFIG.may_load(deps.storage, lp_token)?
+        .unwrap_or_default()
+        .asset_rewards;
+    if pool_info.total_bond_share.is_zero() {
+        let balances = fetch_balance(&deps.querier, config, &env.contract.address, astro_user_info, &skipped_tokens, &asset_rewards)?;
+        return Ok((true, balances))
+    }
+    if pool_info.last_reconcile == env.block.height {
+        let balances = fetch_balance(&deps.querier, config, &env.contract.address, astro_user_info, &skipped_tokens, &asset_rewards)?;
+        return Ok((false, balances))
+    }
 
     // reconcile astro
     let mut astro_reward = REWARD_INFO.may_load(deps.storage, &config.astro_token)?
@@ -187,8 +351,11 @@ fn reconcile_claimed_by_others(
     let target_add_astro_amount = (astro_user_info.reward_user_index - pool_info.prev_reward_user_index) * astro_user_info.virtual_amount;
     let net_astro_amount = cmp::min(add_astro_amount, target_add_astro_amount);
     if !net_astro_amount.is_zero() {
-        reconcile_astro_reward(config, astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
+        let boost_fee = load_boost_fee(deps.storage, config, lp_token)?;
+        let fee = reconcile_astro_reward(config, boost_fee, astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
+        assign_pool_fee(deps.storage, lp_token, &config.astro_token, &mut astro_reward, fee)?;
         REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
+        add_total_fee(deps.storage, &config.astro_token, fee)?;
     }
 
     // track balances
@@ -200,21 +367,30 @@ fn reconcile_claimed_by_others(
     let rewards_debt_map: HashMap<_, _> =
         pool_info.prev_reward_debt_proxy.inner_ref().iter().cloned().collect();
     for (token, debt) in astro_user_info.reward_debt_proxy.inner_ref() {
+        let prev_debt = rewards_debt_map.get(token).cloned().unwrap_or_default();
+        if skipped_tokens.contains(token) {
+            pool_info.add_deferred_reward(token, debt.saturating_sub(prev_debt));
+            continue;
+        }
+
         let mut token_reward = REWARD_INFO.may_load(deps.storage, token)?
             .unwrap_or_default();
-        let prev_debt = rewards_debt_map.get(token).cloned().unwrap_or_default();
-        let target_add_token_amount = debt.saturating_sub(prev_debt);
+        let target_add_token_amount = debt.saturating_sub(prev_debt) + pool_info.take_deferred_reward(token);
 
         let token_amount = query_token_balance(&deps.querier, token, &env.contract.address)?;
         let add_token_amount = token_amount.saturating_sub(token_reward.reconciled_amount);
         let net_token_amount = cmp::min(add_token_amount, target_add_token_amount);
         if !net_token_amount.is_zero() {
-            reconcile_token_reward(token, &mut pool_info, &mut token_reward, net_token_amount)?;
+            let token_fee = load_token_fee(config, token);
+            let fee = reconcile_token_reward(token, token_fee, &mut pool_info, &mut token_reward, net_token_amount)?;
+            assign_pool_fee(deps.storage, lp_token, token, &mut token_reward, fee)?;
             REWARD_INFO.save(deps.storage, token, &token_reward)?;
+            add_total_fee(deps.storage, token, fee)?;
         }
 
         balances.push((token.clone(), token_amount));
     }
+    push_asset_balances(&deps.querier, &env.contract.address, &asset_rewards, &mut balances)?;
 
     // set index and save
     pool_info.prev_reward_user_index = astro_user_info.reward_user_index;
@@ -226,18 +402,19 @@ fn reconcile_claimed_by_others(
 
 fn reconcile_astro_reward(
     config: &Config,
+    boost_fee: Decimal,
     astro_user_info: &UserInfoV2,
     pool_info: &mut PoolInfo,
     astro_reward: &mut RewardInfo,
     net_astro_amount: Uint128,
-) -> StdResult<()> {
+) -> StdResult<Uint128> {
 
     let based_astro = net_astro_amount.multiply_ratio(
         astro_user_info.amount * Decimal::percent(40),
         astro_user_info.virtual_amount,
     );
     let boosted_astro = net_astro_amount.checked_sub(based_astro)?;
-    let fee = boosted_astro * config.boost_fee;
+    let fee = boosted_astro * boost_fee;
     let net_boosted_astro = boosted_astro - fee;
     let to_staker = net_boosted_astro * config.staker_rate;
     let to_lp = net_boosted_astro - to_staker + based_astro;
@@ -247,21 +424,58 @@ fn reconcile_astro_reward(
     astro_reward.reconciled_amount += net_astro_amount;
     pool_info.reward_indexes.update(&config.astro_token, astro_per_share)?;
 
-    Ok(())
+    Ok(fee)
+}
+
+fn add_total_fee(
+    storage: &mut dyn Storage,
+    token: &Addr,
+    fee: Uint128,
+) -> StdResult<()> {
+    if fee.is_zero() {
+        return Ok(());
+    }
+    let total_fee = TOTAL_FEE.may_load(storage, token)?.unwrap_or_default();
+    TOTAL_FEE.save(storage, token, &(total_fee + fee))
+}
+
+/// Moves the fee of a pool with fee_collector override out of the global fee, to be sent to the override
+fn assign_pool_fee(
+    storage: &mut dyn Storage,
+    lp_token: &Addr,
+    token: &Addr,
+    token_reward: &mut RewardInfo,
+    fee: Uint128,
+) -> StdResult<()> {
+    if fee.is_zero() {
+        return Ok(());
+    }
+    let has_fee_collector = POOL_CONFIG.may_load(storage, lp_token)?
+        .and_then(|pool_config| pool_config.fee_collector)
+        .is_some();
+    if !has_fee_collector {
+        return Ok(());
+    }
+    token_reward.fee = token_reward.fee.checked_sub(fee)?;
+    let pool_fee = POOL_FEE.may_load(storage, (token, lp_token))?.unwrap_or_default();
+    POOL_FEE.save(storage, (token, lp_token), &(pool_fee + fee))
 }
 
 fn reconcile_token_reward(
     token: &Addr,
+    token_fee: Decimal,
     pool_info: &mut PoolInfo,
     token_reward: &mut RewardInfo,
     net_token_amount: Uint128,
-) -> StdResult<()> {
+) -> StdResult<Uint128> {
 
-    let token_per_share = Decimal::from_ratio(net_token_amount, pool_info.total_bond_share);
+    let fee = net_token_amount * token_fee;
+    let token_per_share = Decimal::from_ratio(net_token_amount - fee, pool_info.total_bond_share);
+    token_reward.fee += fee;
     token_reward.reconciled_amount += net_token_amount;
     pool_info.reward_indexes.update(token, token_per_share)?;
 
-    Ok(())
+    Ok(fee)
 }
 
 pub fn callback_after_bond_claimed(
@@ -285,32 +499,75 @@ pub fn callback_after_bond_claimed(
     if let Some(prev_astro_amount) = prev_balance_map.get(&config.astro_token) {
         let net_astro_amount = astro_amount.checked_sub(*prev_astro_amount)?;
         if !net_astro_amount.is_zero() {
-            reconcile_astro_reward(
+            let boost_fee = load_boost_fee(deps.storage, &config, &lp_token)?;
+            let fee = reconcile_astro_reward(
                 &config,
+                boost_fee,
                 &astro_user_info,
                 &mut pool_info,
                 &mut astro_reward,
                 net_astro_amount,
             )?;
+            assign_pool_fee(deps.storage, &lp_token, &config.astro_token, &mut astro_reward, fee)?;
             REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
+            add_total_fee(deps.storage, &config.astro_token, fee)?;
         }
     }
 
-    // reconcile other tokens
-    for (token, _) in astro_user_info.reward_debt_proxy.inner_ref() {
+    // reconcile other tokens, tokens skipped from prev_balances are deferred to the next claim
+    let rewards_debt_map: HashMap<_, _> =
+        pool_info.prev_reward_debt_proxy.inner_ref().iter().cloned().collect();
+    let mut skipped_tokens: Vec<String> = vec![];
+    for (token, debt) in astro_user_info.reward_debt_proxy.inner_ref() {
         let mut token_reward = REWARD_INFO.may_load(deps.storage, token)?
             .unwrap_or_default();
         if let Some(prev_token_amount) = prev_balance_map.get(token) {
             let token_amount = query_token_balance(&deps.querier, token, &env.contract.address)?;
-            let net_token_amount = token_amount.checked_sub(*prev_token_amount)?;
+            let net_token_amount = token_amount.checked_sub(*prev_token_amount)? + pool_info.take_deferred_reward(token);
             if !net_token_amount.is_zero() {
-                reconcile_token_reward(
+                let token_fee = load_token_fee(&config, token);
+                let fee = reconcile_token_reward(
                     token,
+                    token_fee,
                     &mut pool_info,
                     &mut token_reward,
                     net_token_amount,
                 )?;
+                assign_pool_fee(deps.storage, &lp_token, token, &mut token_reward, fee)?;
                 REWARD_INFO.save(deps.storage, token, &token_reward)?;
+                add_total_fee(deps.storage, token, fee)?;
+            }
+        } else {
+            let prev_debt = rewards_debt_map.get(token).cloned().unwrap_or_default();
+            pool_info.add_deferred_reward(token, debt.saturating_sub(prev_debt));
+            skipped_tokens.push(token.to_string());
+        }
+    }
+
+    // reconcile extra reward assets, proxy tokens are already reconciled above
+    let pool_config = POOL_CONFIG.may_load(deps.storage, &lp_token)?
+        .unwrap_or_default();
+    for asset in pool_config.asset_rewards.iter() {
+        if astro_user_info.reward_debt_proxy.inner_ref().iter().any(|(token, _)| token == asset) {
+            continue;
+        }
+        if let Some(prev_asset_amount) = prev_balance_map.get(asset) {
+            let asset_amount = query_token_balance(&deps.querier, asset, &env.contract.address)?;
+            let net_asset_amount = asset_amount.checked_sub(*prev_asset_amount)?;
+            if !net_asset_amount.is_zero() {
+                let mut asset_reward = REWARD_INFO.may_load(deps.storage, asset)?
+                    .unwrap_or_default();
+                let asset_fee = load_token_fee(&config, asset);
+                let fee = reconcile_token_reward(
+                    asset,
+                    asset_fee,
+                    &mut pool_info,
+                    &mut asset_reward,
+                    net_asset_amount,
+                )?;
+                assign_pool_fee(deps.storage, &lp_token, asset, &mut asset_reward, fee)?;
+                REWARD_INFO.save(deps.storage, asset, &asset_reward)?;
+                add_total_fee(deps.storage, asset, fee)?;
             }
         }
     }
@@ -321,7 +578,11 @@ pub fn callback_after_bond_claimed(
     pool_info.last_reconcile = env.block.height;
     POOL_INFO.save(deps.storage, &lp_token, &pool_info)?;
 
-    Ok(Response::new())
+    let mut response = Response::new();
+    if !skipped_tokens.is_empty() {
+        response = response.add_attribute("skipped_reward_tokens", skipped_tokens.join(","));
+    }
+    Ok(response)
 }
 
 pub fn callback_after_bond_changed(
@@ -392,9 +653,12 @@ pub fn callback_deposit(
     Ok(Response::new()
         .add_message(deposit_msg)
         .add_message(CallbackMsg::AfterBondChanged {
-            lp_token,
+            lp_token: lp_token.clone(),
         }.to_cosmos_msg(&env.contract.address)?)
         .add_attribute("add_share", share)
+        .add_attribute("staker", staker_addr)
+        .add_attribute("lp_token", lp_token)
+        .add_attribute("total_bond_share", pool_info.total_bond_share)
     )
 }
 
@@ -414,6 +678,10 @@ pub fn callback_withdraw(
     // update
     reconcile_to_user_info(&pool_info, &mut user_info)?;
     let total_bond_amount = config.generator.query_deposit(&deps.querier, &lp_token, &env.contract.address)?;
+    let available = pool_info.calc_bond_amount(total_bond_amount, user_info.bond_share);
+    if amount > available {
+        return Err(ContractError::InsufficientBond { requested: amount, available });
+    }
     let share = pool_info.calc_bond_share(total_bond_amount, amount, true);
     user_info.bond_share = user_info.bond_share.checked_sub(share)?;
     pool_info.total_bond_share = pool_info.total_bond_share.checked_sub(share)?;
@@ -427,9 +695,12 @@ pub fn callback_withdraw(
         .add_message(withdraw_msg)
         .add_message(token_asset(lp_token.clone(), amount).transfer_msg(&staker_addr)?)
         .add_message(CallbackMsg::AfterBondChanged {
-            lp_token,
+            lp_token: lp_token.clone(),
         }.to_cosmos_msg(&env.contract.address)?)
         .add_attribute("deduct_share", share)
+        .add_attribute("staker", staker_addr)
+        .add_attribute("lp_token", lp_token)
+        .add_attribute("total_bond_share", pool_info.total_bond_share)
     )
 }
 
@@ -475,11 +746,74 @@ pub fn query_pending_token(
     lp_token: String,
     user: String,
 ) -> Result<PendingTokenResponse, ContractError> {
-
-    // load
     let lp_token = deps.api.addr_validate(&lp_token)?;
     let user = deps.api.addr_validate(&user)?;
     let config = CONFIG.load(deps.storage)?;
+    pending_token(deps, &env, &config, lp_token, &user)
+}
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Sums the pending ASTRO and proxy tokens of the user over the pools the user has bonded into,
+/// paginated by LP token
+pub fn query_pending_token_all(
+    deps: Deps,
+    env: Env,
+    user: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<PendingTokenAllResponse, ContractError> {
+    let user = deps.api.addr_validate(&user)?;
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|it| deps.api.addr_validate(&it)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    // limit bounds the scanned pools, not the matched ones, to keep query gas bounded
+    let scanned = POOL_INFO.keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let last_key = if scanned.len() == limit {
+        scanned.last().map(|it| it.to_string())
+    } else {
+        None
+    };
+
+    let mut pending: Vec<Asset> = vec![];
+    for lp_token in scanned {
+        if !USER_INFO.has(deps.storage, (&lp_token, &user)) {
+            continue;
+        }
+        let res = pending_token(deps, &env, &config, lp_token, &user)?;
+        let mut assets = vec![token_asset(config.astro_token.clone(), res.pending)];
+        assets.extend(res.pending_on_proxy.unwrap_or_default());
+        for asset in assets {
+            if asset.amount.is_zero() {
+                continue;
+            }
+            match pending.iter_mut().find(|it| it.info == asset.info) {
+                Some(it) => it.amount = it.amount.checked_add(asset.amount)?,
+                None => pending.push(asset),
+            }
+        }
+    }
+
+    Ok(PendingTokenAllResponse {
+        pending,
+        last_key,
+    })
+}
+
+fn pending_token(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    lp_token: Addr,
+    user: &Addr,
+) -> Result<PendingTokenResponse, ContractError> {
+
+    // load
     let astro_user_info = match config.generator.query_user_info(&deps.querier, &lp_token, &env.contract.address)? {
         Some(astro_user_info) => astro_user_info,
         None => {
@@ -491,7 +825,7 @@ pub fn query_pending_token(
     };
     let mut pool_info = POOL_INFO.may_load(deps.storage, &lp_token)?
         .unwrap_or_default();
-    let mut user_info = USER_INFO.may_load(deps.storage, (&lp_token, &user))?
+    let mut user_info = USER_INFO.may_load(deps.storage, (&lp_token, user))?
         .unwrap_or_else(|| UserInfo::create(&pool_info));
     let pending_token = config.generator.query_pending_token(&deps.querier, &lp_token, &env.contract.address)?;
 
@@ -502,9 +836,11 @@ pub fn query_pending_token(
     let add_astro_amount = astro_amount.saturating_sub(astro_reward.reconciled_amount);
     let target_add_astro_amount = (astro_user_info.reward_user_index - pool_info.prev_reward_user_index) * astro_user_info.virtual_amount;
     let net_astro_amount = cmp::min(add_astro_amount, target_add_astro_amount) + pending_token.pending;
-    reconcile_astro_reward(&config, &astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
+    let boost_fee = load_boost_fee(deps.storage, config, &lp_token)?;
+    reconcile_astro_reward(config, boost_fee, &astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
 
-    // reconcile other tokens
+    // reconcile other tokens, tokens beyond max_reward_tokens are left to a later claim as in claim
+    let skipped_tokens = skipped_reward_tokens(config, &pool_info, &astro_user_info);
     let rewards_debt_map: HashMap<_, _> =
         pool_info.prev_reward_debt_proxy.inner_ref().iter().cloned().collect();
     let pending_token_map: HashMap<_, _> = if let Some(tokens) = pending_token.pending_on_proxy {
@@ -513,16 +849,20 @@ pub fn query_pending_token(
         HashMap::new()
     };
     for (token, debt) in astro_user_info.reward_debt_proxy.inner_ref() {
+        if skipped_tokens.contains(token) {
+            continue;
+        }
         let mut token_reward = REWARD_INFO.may_load(deps.storage, token)?
             .unwrap_or_default();
         let prev_debt = rewards_debt_map.get(token).cloned().unwrap_or_default();
-        let target_add_token_amount = debt.saturating_sub(prev_debt);
+        let target_add_token_amount = debt.saturating_sub(prev_debt) + pool_info.take_deferred_reward(token);
         let add_pending_amount = pending_token_map.get(&token.to_string()).cloned().unwrap_or_default();
 
         let token_amount = query_token_balance(&deps.querier, token, &env.contract.address)?;
         let add_token_amount = token_amount.saturating_sub(token_reward.reconciled_amount);
         let net_token_amount = cmp::min(add_token_amount, target_add_token_amount) + add_pending_amount;
-        reconcile_token_reward(token, &mut pool_info, &mut token_reward, net_token_amount)?;
+        let token_fee = load_token_fee(config, token);
+        reconcile_token_reward(token, token_fee, &mut pool_info, &mut token_reward, net_token_amount)?;
     }
     pool_info.prev_reward_debt_proxy = astro_user_info.reward_debt_proxy;
 
@@ -571,3 +911,53 @@ pub fn query_deposit(
     let user_bond_amount = pool_info.calc_bond_amount(total_bond_amount, user_info.bond_share);
     Ok(user_bond_amount)
 }
+
+pub fn query_boost_info(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+) -> Result<BoostInfoResponse, ContractError> {
+
+    // load
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // query
+    let (amount, virtual_amount) = match config.generator.query_user_info(&deps.querier, &lp_token, &env.contract.address)? {
+        Some(astro_user_info) => (astro_user_info.amount, astro_user_info.virtual_amount),
+        None => (Uint128::zero(), Uint128::zero()),
+    };
+    let boost_ratio = if amount.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(virtual_amount, amount)
+    };
+    Ok(BoostInfoResponse {
+        amount,
+        virtual_amount,
+        boost_ratio,
+    })
+}
+
+pub fn query_claimable_rewards(
+    deps: Deps,
+    env: Env,
+    lp_token: String,
+    user: String,
+) -> Result<Vec<Asset>, ContractError> {
+
+    // same amounts as ClaimRewards, zero amounts are not transferred
+    let config = CONFIG.load(deps.storage)?;
+    let pending_token = query_pending_token(deps, env, lp_token, user)?;
+    let mut assets: Vec<Asset> = vec![];
+    if !pending_token.pending.is_zero() {
+        assets.push(token_asset(config.astro_token, pending_token.pending));
+    }
+    for asset in pending_token.pending_on_proxy.unwrap_or_default() {
+        if !asset.amount.is_zero() {
+            assets.push(asset);
+        }
+    }
+
+    Ok(assets)
+}
@@ -3,11 +3,11 @@ use cosmwasm_std::{entry_point, DepsMut, Env, MessageInfo, Response, from_binary
 use cw20::Cw20ReceiveMsg;
 use astroport_governance::utils::get_period;
 use spectrum::adapters::generator::Generator;
-use crate::bond::{callback_after_bond_changed, callback_after_bond_claimed, callback_claim_rewards, callback_deposit, callback_withdraw, execute_deposit, execute_withdraw, query_deposit, query_pending_token, execute_claim_rewards};
-use crate::oper::{execute_controller_vote, execute_send_income, execute_update_config, execute_update_parameters, query_config, validate_percentage};
+use crate::bond::{callback_after_bond_changed, callback_after_bond_claimed, callback_claim_rewards, callback_deposit, callback_withdraw, execute_deposit, execute_withdraw, execute_withdraw_all, query_boost_info, query_deposit, query_pending_token, query_pending_token_all, query_claimable_rewards, execute_claim_rewards, execute_claim_all_rewards};
+use crate::oper::{execute_collect_fees, execute_controller_vote, execute_send_income, execute_sync_quota, execute_update_config, execute_update_parameters, execute_set_pool_config, execute_set_pool_fee_collector, execute_update_pool_boost_fee, query_config, query_pool_boost_fee, query_pool_config, validate_percentage};
 use crate::error::ContractError;
 use crate::model::{CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakingState};
-use crate::query::{query_pool_info, query_reward_info, query_staker_info, query_staking_state, query_user_info};
+use crate::query::{query_parameter_history, query_pool_info, query_reward_info, query_staker_info, query_staking_state, query_total_fees, query_user_info};
 use crate::staking::{callback_after_staking_claimed, execute_claim_income, execute_relock, execute_request_unstake, execute_stake, execute_withdraw_unstaked};
 use crate::state::{CONFIG, OWNERSHIP_PROPOSAL, STAKING_STATE};
 
@@ -35,6 +35,8 @@ pub fn instantiate(
         max_quota: msg.max_quota,
         staker_rate: msg.staker_rate,
         boost_fee: msg.boost_fee,
+        fee_overrides: vec![],
+        max_reward_tokens: 0,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -63,7 +65,21 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         ExecuteMsg::UpdateConfig {
             controller,
             boost_fee,
-        } => execute_update_config(deps, env, info, controller, boost_fee),
+            fee_overrides,
+            max_reward_tokens,
+        } => execute_update_config(deps, env, info, controller, boost_fee, fee_overrides, max_reward_tokens),
+        ExecuteMsg::UpdatePoolBoostFee {
+            lp_token,
+            boost_fee,
+        } => execute_update_pool_boost_fee(deps, env, info, lp_token, boost_fee),
+        ExecuteMsg::SetPoolConfig {
+            lp_token,
+            asset_rewards,
+        } => execute_set_pool_config(deps, env, info, lp_token, asset_rewards),
+        ExecuteMsg::SetPoolFeeCollector {
+            lp_token,
+            fee_collector,
+        } => execute_set_pool_fee_collector(deps, env, info, lp_token, fee_collector),
         ExecuteMsg::UpdateParameters {
             max_quota,
             staker_rate,
@@ -71,9 +87,13 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
 
         ExecuteMsg::ControllerVote { votes } => execute_controller_vote(deps, env, info, votes),
         ExecuteMsg::SendIncome {} => execute_send_income(deps, env, info),
+        ExecuteMsg::CollectFees { tokens } => execute_collect_fees(deps, env, info, tokens),
+        ExecuteMsg::SyncQuota { lp_token } => execute_sync_quota(deps, env, info, lp_token),
 
         ExecuteMsg::ClaimRewards { lp_tokens } => execute_claim_rewards(deps, env, info, lp_tokens),
-        ExecuteMsg::Withdraw { lp_token, amount, } => execute_withdraw(deps, env, info, lp_token, amount),
+        ExecuteMsg::ClaimAllRewards {} => execute_claim_all_rewards(deps, env, info),
+        ExecuteMsg::Withdraw { lp_token, amount, claim } => execute_withdraw(deps, env, info, lp_token, amount, claim),
+        ExecuteMsg::WithdrawAll {} => execute_withdraw_all(deps, env, info),
 
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
@@ -159,11 +179,18 @@ fn handle_callback(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     let result = match msg {
         QueryMsg::PendingToken { lp_token, user } => to_binary(&query_pending_token(deps, env, lp_token, user)?),
+        QueryMsg::PendingTokenAll { user, start_after, limit } => to_binary(&query_pending_token_all(deps, env, user, start_after, limit)?),
+        QueryMsg::BoostInfo { lp_token } => to_binary(&query_boost_info(deps, env, lp_token)?),
         QueryMsg::Deposit { lp_token, user } => to_binary(&query_deposit(deps, env, lp_token, user)?),
+        QueryMsg::ClaimableRewards { lp_token, user } => to_binary(&query_claimable_rewards(deps, env, lp_token, user)?),
         QueryMsg::Config { } => to_binary(&query_config(deps, env)?),
         QueryMsg::PoolInfo { lp_token } => to_binary(&query_pool_info(deps, env, lp_token)?),
+        QueryMsg::PoolBoostFee { lp_token } => to_binary(&query_pool_boost_fee(deps, env, lp_token)?),
+        QueryMsg::PoolConfig { lp_token } => to_binary(&query_pool_config(deps, env, lp_token)?),
         QueryMsg::UserInfo { lp_token, user } => to_binary(&query_user_info(deps, env, lp_token, user)?),
         QueryMsg::RewardInfo { token } => to_binary(&query_reward_info(deps, env, token)?),
+        QueryMsg::TotalFees { } => to_binary(&query_total_fees(deps, env)?),
+        QueryMsg::ParameterHistory { } => to_binary(&query_parameter_history(deps, env)?),
         QueryMsg::StakingState { } => to_binary(&query_staking_state(deps, env)?),
         QueryMsg::StakerInfo { user } => to_binary(&query_staker_info(deps, env, user)?),
     }?;
@@ -19,6 +19,9 @@ pub enum ContractError {
     #[error("Exceed quota, remaining quota is {0}")]
     ExceedQuota(Uint128),
 
+    #[error("Insufficient bond; requested amount: {requested}, available amount: {available}")]
+    InsufficientBond { requested: Uint128, available: Uint128 },
+
     #[error("Cannot update {0} after set")]
     CannotUpdateAfterSet(String),
 }
@@ -2,6 +2,7 @@ use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdResult, to_binary, Uint128, Wasm
 use cw20::{Cw20ReceiveMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use astroport::asset::Asset;
 use astroport::restricted_vector::RestrictedVector;
 use spectrum::adapters::generator::Generator;
 use spectrum::helper::ScalingUint128;
@@ -31,6 +32,10 @@ pub struct Config {
     pub max_quota: Uint128,
     pub staker_rate: Decimal,
     pub boost_fee: Decimal,
+    /// Fee rate per reward token, ASTRO uses it in place of boost_fee and other tokens are fee-free when unset
+    #[serde(default)] pub fee_overrides: Vec<(Addr, Decimal)>,
+    /// Maximum number of proxy reward tokens reconciled per pool in a single claim, 0 is unlimited
+    #[serde(default)] pub max_reward_tokens: u32,
 }
 
 pub fn zero_address() -> Addr {
@@ -44,9 +49,35 @@ pub struct PoolInfo {
     pub prev_reward_user_index: Decimal,
     pub prev_reward_debt_proxy: RestrictedVector<Addr, Uint128>,
     #[serde(default)] pub last_reconcile: u64,
+    /// Proxy rewards received but skipped by max_reward_tokens, reconciled on a later claim
+    #[serde(default)] pub deferred_reward_proxy: Vec<(Addr, Uint128)>,
 }
 
 impl PoolInfo {
+    pub fn add_deferred_reward(&mut self, token: &Addr, amount: Uint128) {
+        if amount.is_zero() {
+            return;
+        }
+        match self.deferred_reward_proxy.iter_mut().find(|(addr, _)| addr == token) {
+            Some((_, deferred)) => *deferred += amount,
+            None => self.deferred_reward_proxy.push((token.clone(), amount)),
+        }
+    }
+
+    pub fn take_deferred_reward(&mut self, token: &Addr) -> Uint128 {
+        match self.deferred_reward_proxy.iter().position(|(addr, _)| addr == token) {
+            Some(index) => self.deferred_reward_proxy.remove(index).1,
+            None => Uint128::zero(),
+        }
+    }
+
+    pub fn deferred_reward(&self, token: &Addr) -> Uint128 {
+        self.deferred_reward_proxy.iter()
+            .find(|(addr, _)| addr == token)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_default()
+    }
+
     pub fn calc_bond_share(
         &self,
         total_bond_amount: Uint128,
@@ -106,6 +137,32 @@ pub struct UserInfoResponse {
     pub pending_rewards: RestrictedVector<Addr, Uint128>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTokenAllResponse {
+    /// Pending ASTRO and proxy tokens summed over the scanned pools
+    pub pending: Vec<Asset>,
+    /// The last scanned LP token to continue from, none when all pools are scanned
+    pub last_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoostInfoResponse {
+    /// LP amount deposited by the proxy in the generator
+    pub amount: Uint128,
+    /// Boosted amount used by the generator to distribute ASTRO
+    pub virtual_amount: Uint128,
+    /// virtual_amount / amount, one when nothing is deposited
+    pub boost_ratio: Decimal,
+}
+
+/// Parameters set by the controller, with the block time of the update
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParameterRecord {
+    pub time: u64,
+    pub max_quota: Uint128,
+    pub staker_rate: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct StakingState {
     pub total_bond_share: Uint128,
@@ -198,6 +255,15 @@ pub struct StakerInfoResponse {
     pub unstaking_period: u64,
 }
 
+/// Per pool settings of the LP token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PoolConfig {
+    /// Extra reward assets received on claim, reconciled alongside the proxy rewards
+    pub asset_rewards: Vec<Addr>,
+    /// Receives the fee of the pool in place of the global fee_collector
+    #[serde(default)] pub fee_collector: Option<Addr>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct RewardInfo {
     pub reconciled_amount: Uint128,
@@ -215,6 +281,25 @@ pub enum ExecuteMsg {
     UpdateConfig {
         controller: Option<String>,
         boost_fee: Option<Decimal>,
+        /// Replace fee rate per reward token
+        fee_overrides: Option<Vec<(String, Decimal)>>,
+        /// Maximum number of proxy reward tokens reconciled per pool in a single claim, 0 is unlimited
+        max_reward_tokens: Option<u32>,
+    },
+    /// Override boost_fee for the LP token, unset to fall back to the global boost_fee
+    UpdatePoolBoostFee {
+        lp_token: String,
+        boost_fee: Option<Decimal>,
+    },
+    /// Replace the extra reward assets of the LP token, empty to clear, the fee_collector override is kept
+    SetPoolConfig {
+        lp_token: String,
+        asset_rewards: Vec<String>,
+    },
+    /// Override fee_collector for the LP token, unset to fall back to the global fee_collector
+    SetPoolFeeCollector {
+        lp_token: String,
+        fee_collector: Option<String>,
     },
 
     // controller's actions
@@ -226,6 +311,14 @@ pub enum ExecuteMsg {
         votes: Vec<(String, u16)>,
     },
     SendIncome {},
+    /// Send the accrued fee of the reward tokens to the fee collector, by owner or fee collector
+    CollectFees {
+        tokens: Vec<String>,
+    },
+    /// Sync max_quota to the lock amount required for max boost on the LP token
+    SyncQuota {
+        lp_token: String,
+    },
 
     // from generator
     /// Update rewards and return it to user.
@@ -233,13 +326,19 @@ pub enum ExecuteMsg {
         /// the LP token contract address
         lp_tokens: Vec<String>,
     },
+    /// Claim rewards of the sender across all bonded pools, capped per call
+    ClaimAllRewards {},
     /// Withdraw LP tokens from the Generator
     Withdraw {
         /// The address of the LP token to withdraw
         lp_token: String,
         /// The amount to withdraw
         amount: Uint128,
+        /// Also send the pending rewards of the pool to the sender
+        claim: Option<bool>,
     },
+    /// Withdraw all LP tokens of the sender across pools, fails if bonded in more than 10 pools
+    WithdrawAll {},
 
     // owner
     /// Creates a request to change the contract's ownership
@@ -332,6 +431,14 @@ pub enum QueryMsg {
     PoolInfo {
         lp_token: String,
     },
+    /// Returns the boost_fee applied to the LP token
+    PoolBoostFee {
+        lp_token: String,
+    },
+    /// Returns the extra reward assets of the LP token
+    PoolConfig {
+        lp_token: String,
+    },
     UserInfo {
         lp_token: String,
         user: String,
@@ -342,7 +449,22 @@ pub enum QueryMsg {
 
     // from generator
     PendingToken { lp_token: String, user: String },
+    /// Returns the pending ASTRO and proxy tokens of the user summed over the pools,
+    /// scanning at most limit LP tokens after start_after
+    PendingTokenAll {
+        user: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the boost captured by the proxy on the LP token
+    BoostInfo { lp_token: String },
     Deposit { lp_token: String, user: String },
+    ClaimableRewards { lp_token: String, user: String },
+
+    /// Returns lifetime fee collected per reward token
+    TotalFees {},
+    /// Returns the recent parameter updates by the controller, oldest first
+    ParameterHistory {},
 
     // staker
     StakingState {},
@@ -1,9 +1,10 @@
-use cosmwasm_std::{CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
+use cosmwasm_std::{attr, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128};
 use astroport::asset::{token_asset};
 use spectrum::adapters::asset::AssetEx;
+use crate::astro_generator::GeneratorEx;
 use crate::error::ContractError;
-use crate::model::{Config};
-use crate::state::{CONFIG, REWARD_INFO};
+use crate::model::{Config, ParameterRecord, PoolConfig, RewardInfo};
+use crate::state::{CONFIG, PARAMETER_HISTORY, PARAMETER_HISTORY_LIMIT, POOL_BOOST_FEE, POOL_CONFIG, POOL_FEE, REWARD_INFO};
 
 pub fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
     if value > Decimal::one() {
@@ -18,6 +19,8 @@ pub fn execute_update_config(
     info: MessageInfo,
     controller: Option<String>,
     boost_fee: Option<Decimal>,
+    fee_overrides: Option<Vec<(String, Decimal)>>,
+    max_reward_tokens: Option<u32>,
 ) -> Result<Response, ContractError> {
 
     // only owner can update
@@ -35,15 +38,123 @@ pub fn execute_update_config(
         config.boost_fee = boost_fee;
     }
 
+    if let Some(fee_overrides) = fee_overrides {
+        config.fee_overrides = fee_overrides.into_iter()
+            .map(|(token, fee)| {
+                validate_percentage(fee, "fee_overrides")?;
+                Ok((deps.api.addr_validate(&token)?, fee))
+            })
+            .collect::<StdResult<_>>()?;
+    }
+
+    if let Some(max_reward_tokens) = max_reward_tokens {
+        config.max_reward_tokens = max_reward_tokens;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
-pub fn execute_update_parameters(
+pub fn execute_update_pool_boost_fee(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
+    lp_token: String,
+    boost_fee: Option<Decimal>,
+) -> Result<Response, ContractError> {
+
+    // only owner can update
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    match boost_fee {
+        Some(boost_fee) => {
+            validate_percentage(boost_fee, "boost_fee")?;
+            POOL_BOOST_FEE.save(deps.storage, &lp_token, &boost_fee)?;
+        },
+        None => POOL_BOOST_FEE.remove(deps.storage, &lp_token),
+    }
+
+    Ok(Response::default())
+}
+
+pub fn execute_set_pool_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    asset_rewards: Vec<String>,
+) -> Result<Response, ContractError> {
+
+    // only owner can update
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let mut validated: Vec<Addr> = vec![];
+    for asset in asset_rewards {
+        let asset = deps.api.addr_validate(&asset)?;
+        if asset == config.astro_token || validated.contains(&asset) {
+            return Err(ContractError::Std(StdError::generic_err(format!("duplicated asset_rewards: {}", asset))));
+        }
+        validated.push(asset);
+    }
+
+    let mut pool_config = POOL_CONFIG.may_load(deps.storage, &lp_token)?.unwrap_or_default();
+    pool_config.asset_rewards = validated;
+    save_pool_config(deps.storage, &lp_token, &pool_config)?;
+
+    Ok(Response::default())
+}
+
+pub fn execute_set_pool_fee_collector(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    lp_token: String,
+    fee_collector: Option<String>,
+) -> Result<Response, ContractError> {
+
+    // only owner can update
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let mut pool_config = POOL_CONFIG.may_load(deps.storage, &lp_token)?.unwrap_or_default();
+    pool_config.fee_collector = fee_collector
+        .map(|fee_collector| deps.api.addr_validate(&fee_collector))
+        .transpose()?;
+    save_pool_config(deps.storage, &lp_token, &pool_config)?;
+
+    Ok(Response::default())
+}
+
+/// Saves the pool config, an empty config is removed
+fn save_pool_config(
+    storage: &mut dyn Storage,
+    lp_token: &Addr,
+    pool_config: &PoolConfig,
+) -> StdResult<()> {
+    if pool_config.asset_rewards.is_empty() && pool_config.fee_collector.is_none() {
+        POOL_CONFIG.remove(storage, lp_token);
+        Ok(())
+    } else {
+        POOL_CONFIG.save(storage, lp_token, pool_config)
+    }
+}
+
+pub fn execute_update_parameters(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     max_quota: Option<Uint128>,
     staker_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
@@ -54,7 +165,13 @@ pub fn execute_update_parameters(
         return Err(ContractError::Unauthorized {});
     }
 
+    let prev_max_quota = config.max_quota;
+    let prev_staker_rate = config.staker_rate;
+
     if let Some(max_quota) = max_quota {
+        if max_quota.is_zero() {
+            return Err(ContractError::Std(StdError::generic_err("max_quota must be greater than 0")));
+        }
         config.max_quota = max_quota;
     }
 
@@ -65,7 +182,62 @@ pub fn execute_update_parameters(
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::default())
+    // keep the latest updates only
+    let mut history = PARAMETER_HISTORY.may_load(deps.storage)?.unwrap_or_default();
+    history.push(ParameterRecord {
+        time: env.block.time.seconds(),
+        max_quota: config.max_quota,
+        staker_rate: config.staker_rate,
+    });
+    if history.len() > PARAMETER_HISTORY_LIMIT {
+        history.remove(0);
+    }
+    PARAMETER_HISTORY.save(deps.storage, &history)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_parameters")
+        .add_attribute("prev_max_quota", prev_max_quota)
+        .add_attribute("max_quota", config.max_quota)
+        .add_attribute("prev_staker_rate", prev_staker_rate.to_string())
+        .add_attribute("staker_rate", config.staker_rate.to_string()))
+}
+
+pub fn execute_sync_quota(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+
+    // only controller can sync
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let astro_user_info = config.generator.query_user_info(&deps.querier, &lp_token, &env.contract.address)?
+        .ok_or_else(|| StdError::generic_err("UserInfo is not found"))?;
+    let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address)?;
+
+    // generator caps virtual amount at amount, 40% is base and 60% is boosted by voting power
+    let based_amount = astro_user_info.amount * Decimal::percent(40);
+    let boosted_amount = astro_user_info.virtual_amount.saturating_sub(based_amount);
+    if boosted_amount.is_zero() {
+        return Err(StdError::generic_err("Cannot sync quota without boost").into());
+    }
+    let max_boosted_amount = astro_user_info.amount.checked_sub(based_amount)?;
+    let old_quota = config.max_quota;
+    config.max_quota = lock.amount.multiply_ratio(max_boosted_amount, boosted_amount);
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sync_quota")
+        .add_attribute("lp_token", lp_token)
+        .add_attribute("old_quota", old_quota)
+        .add_attribute("new_quota", config.max_quota)
+    )
 }
 
 pub fn execute_controller_vote(
@@ -101,16 +273,21 @@ pub fn execute_send_income(
     }
 
     let mut messages: Vec<CosmosMsg> = vec![];
-    let mut reward_info = REWARD_INFO.load(deps.storage, &config.astro_token)?;
-    let fee = reward_info.fee;
-    reward_info.fee = Uint128::zero();
-    reward_info.reconciled_amount -= fee;
+    let reward_infos = REWARD_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (token, mut reward_info) in reward_infos {
+        let transfers = take_fees(deps.storage, &config, &token, &mut reward_info)?;
+        if transfers.is_empty() {
+            continue;
+        }
 
-    // save
-    REWARD_INFO.save(deps.storage, &config.astro_token, &reward_info)?;
+        // save
+        REWARD_INFO.save(deps.storage, &token, &reward_info)?;
 
-    if !fee.is_zero() {
-        messages.push(token_asset(config.astro_token, fee).transfer_msg(&config.fee_collector)?);
+        for (fee_collector, fee) in transfers {
+            messages.push(token_asset(token.clone(), fee).transfer_msg(&fee_collector)?);
+        }
     }
 
     Ok(Response::new()
@@ -118,6 +295,126 @@ pub fn execute_send_income(
     )
 }
 
+pub fn execute_collect_fees(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    tokens: Vec<String>,
+) -> Result<Response, ContractError> {
+
+    // only owner or fee collector can collect fees
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner && info.sender != config.fee_collector {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![attr("action", "collect_fees")];
+    for token in tokens {
+        let token = deps.api.addr_validate(&token)?;
+        let mut reward_info = match REWARD_INFO.may_load(deps.storage, &token)? {
+            Some(reward_info) => reward_info,
+            None => continue,
+        };
+        let transfers = take_fees(deps.storage, &config, &token, &mut reward_info)?;
+        if transfers.is_empty() {
+            continue;
+        }
+        REWARD_INFO.save(deps.storage, &token, &reward_info)?;
+
+        for (fee_collector, fee) in transfers {
+            messages.push(token_asset(token.clone(), fee).transfer_msg(&fee_collector)?);
+            attributes.push(attr("token", token.clone()));
+            attributes.push(attr("amount", fee));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes)
+    )
+}
+
+/// Takes the fee of the token, returns the global fee for fee_collector first,
+/// then the fee of each pool with fee_collector override. staker_income and pending rewards are not part of the fee
+fn take_fees(
+    storage: &mut dyn Storage,
+    config: &Config,
+    token: &Addr,
+    reward_info: &mut RewardInfo,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    let mut transfers: Vec<(Addr, Uint128)> = vec![];
+    if !reward_info.fee.is_zero() {
+        transfers.push((config.fee_collector.clone(), reward_info.fee));
+        reward_info.reconciled_amount = reward_info.reconciled_amount.checked_sub(reward_info.fee)?;
+        reward_info.fee = Uint128::zero();
+    }
+
+    let pool_fees = POOL_FEE.prefix(token)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (lp_token, fee) in pool_fees {
+        POOL_FEE.remove(storage, (token, &lp_token));
+        reward_info.reconciled_amount = reward_info.reconciled_amount.checked_sub(fee)?;
+
+        // fee accrued before the override is removed goes to the global fee_collector
+        let fee_collector = POOL_CONFIG.may_load(storage, &lp_token)?
+            .and_then(|pool_config| pool_config.fee_collector)
+            .unwrap_or_else(|| config.fee_collector.clone());
+        transfers.push((fee_collector, fee));
+    }
+
+    Ok(transfers)
+}
+
+pub fn query_pool_boost_fee(
+    deps: Deps,
+    _env: Env,
+    lp_token: String,
+) -> StdResult<Decimal> {
+    let config = CONFIG.load(deps.storage)?;
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    load_boost_fee(deps.storage, &config, &lp_token)
+}
+
+pub fn query_pool_config(
+    deps: Deps,
+    _env: Env,
+    lp_token: String,
+) -> StdResult<PoolConfig> {
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    Ok(POOL_CONFIG.may_load(deps.storage, &lp_token)?.unwrap_or_default())
+}
+
+/// Returns the boost fee override of the LP token, then the ASTRO fee override, or the global boost fee when unset
+pub fn load_boost_fee(
+    storage: &dyn Storage,
+    config: &Config,
+    lp_token: &Addr,
+) -> StdResult<Decimal> {
+    Ok(POOL_BOOST_FEE.may_load(storage, lp_token)?
+        .or_else(|| find_fee_override(config, &config.astro_token))
+        .unwrap_or(config.boost_fee))
+}
+
+/// Returns the fee rate of the reward token, zero when unset
+pub fn load_token_fee(
+    config: &Config,
+    token: &Addr,
+) -> Decimal {
+    find_fee_override(config, token)
+        .unwrap_or_default()
+}
+
+fn find_fee_override(
+    config: &Config,
+    token: &Addr,
+) -> Option<Decimal> {
+    config.fee_overrides.iter()
+        .find(|(it, _)| it == token)
+        .map(|(_, fee)| *fee)
+}
+
 pub fn query_config(
     deps: Deps,
     _env: Env,
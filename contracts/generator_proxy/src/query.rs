@@ -1,8 +1,8 @@
-use cosmwasm_std::{Deps, Env, StdResult};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Uint128};
 use crate::bond::reconcile_to_user_info;
-use crate::model::{PoolInfo, RewardInfo, StakerInfo, StakerInfoResponse, StakingState, UserInfo, UserInfoResponse};
+use crate::model::{ParameterRecord, PoolInfo, RewardInfo, StakerInfo, StakerInfoResponse, StakingState, UserInfo, UserInfoResponse};
 use crate::staking::{reconcile_staker_income, reconcile_to_staker_info};
-use crate::state::{CONFIG, POOL_INFO, REWARD_INFO, STAKER_INFO, STAKING_STATE, USER_INFO};
+use crate::state::{CONFIG, PARAMETER_HISTORY, POOL_INFO, REWARD_INFO, STAKER_INFO, STAKING_STATE, TOTAL_FEE, USER_INFO};
 
 pub fn query_pool_info(
     deps: Deps,
@@ -31,6 +31,21 @@ pub fn query_user_info(
     Ok(user_info.to_response(&pool_info, total_bond_amount))
 }
 
+pub fn query_total_fees(
+    deps: Deps,
+    _env: Env,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    TOTAL_FEE.range(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+pub fn query_parameter_history(
+    deps: Deps,
+    _env: Env,
+) -> StdResult<Vec<ParameterRecord>> {
+    Ok(PARAMETER_HISTORY.may_load(deps.storage)?.unwrap_or_default())
+}
+
 pub fn query_reward_info(
     deps: Deps,
     _env: Env,
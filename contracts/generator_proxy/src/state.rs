@@ -1,7 +1,7 @@
 use astroport::common::OwnershipProposal;
-use cosmwasm_std::{Addr};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
-use crate::model::{Config, PoolInfo, RewardInfo, StakerInfo, StakingState, UserInfo};
+use crate::model::{Config, ParameterRecord, PoolConfig, PoolInfo, RewardInfo, StakerInfo, StakingState, UserInfo};
 
 /// Stores the contract config
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -9,12 +9,30 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// Stores pool info per LP token, key = LP token
 pub const POOL_INFO: Map<&Addr, PoolInfo> = Map::new("pool_info");
 
+/// Stores boost fee overrides per LP token, key = LP token
+pub const POOL_BOOST_FEE: Map<&Addr, Decimal> = Map::new("pool_boost_fee");
+
+/// Stores extra reward assets per LP token, key = LP token
+pub const POOL_CONFIG: Map<&Addr, PoolConfig> = Map::new("pool_config");
+
+/// Stores fee collected per reward token for pools with fee_collector override, key = Reward Token, LP token
+pub const POOL_FEE: Map<(&Addr, &Addr), Uint128> = Map::new("pool_fee");
+
 /// Stores user info per user per LP token, key = LP token, User
 pub const USER_INFO: Map<(&Addr, &Addr), UserInfo> = Map::new("user_info");
 
 /// Stores reward info per reward token, key = Reward Token
 pub const REWARD_INFO: Map<&Addr, RewardInfo> = Map::new("reward_info");
 
+/// Stores lifetime fee collected per reward token, key = Reward Token
+pub const TOTAL_FEE: Map<&Addr, Uint128> = Map::new("total_fee");
+
+/// Stores the recent parameter updates, up to PARAMETER_HISTORY_LIMIT records
+pub const PARAMETER_HISTORY: Item<Vec<ParameterRecord>> = Item::new("parameter_history");
+
+/// Maximum number of parameter updates kept in history
+pub const PARAMETER_HISTORY_LIMIT: usize = 10;
+
 /// Stores the contract state for staking related
 pub const STAKING_STATE: Item<StakingState> = Item::new("staking_state");
 
@@ -1,29 +1,37 @@
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, from_binary, OwnedDeps, Response, StdError, Timestamp, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{Addr, attr, CosmosMsg, Decimal, from_binary, OwnedDeps, Response, StdError, StdResult, Timestamp, to_binary, Uint128, WasmMsg};
 use cosmwasm_std::testing::{MOCK_CONTRACT_ADDR, mock_env, mock_info, MockApi, MockStorage};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use astroport::asset::{token_asset};
+use astroport::asset::{Asset, token_asset};
 use astroport::generator::{ExecuteMsg as GeneratorExecuteMsg, Cw20HookMsg as GeneratorCw20HookMsg, UserInfoV2, PendingTokenResponse};
 use astroport_governance::utils::{EPOCH_START, WEEK};
 use astroport_governance::voting_escrow::{Cw20HookMsg as VotingCw20HookMsg, ExecuteMsg as VotingExecuteMsg};
 use astroport_governance::escrow_fee_distributor::{ExecuteMsg as FeeExecuteMsg};
 use astroport::restricted_vector::RestrictedVector;
 use spectrum::adapters::generator::Generator;
+use spectrum::adapters::asset::AssetEx;
 use crate::astro_gov::{AstroGov, AstroGovUnchecked, Lock};
 use crate::contract::{execute, instantiate, query};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::model::{CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfo, QueryMsg, RewardInfo, StakerInfoResponse, StakingState, UserInfoResponse};
+use crate::model::{BoostInfoResponse, CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, ParameterRecord, PendingTokenAllResponse, PoolConfig, PoolInfo, QueryMsg, RewardInfo, StakerInfoResponse, StakingState, UserInfoResponse};
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
 const REWARD_PROXY: &str = "reward_proxy";
+const REWARD_TOKEN_2: &str = "reward_2";
+const REWARD_PROXY_2: &str = "reward_proxy_2";
+const REWARD_TOKEN_3: &str = "reward_3";
+const REWARD_PROXY_3: &str = "reward_proxy_3";
+const ASSET_TOKEN: &str = "asset";
 const USER1: &str = "user1";
 const USER2: &str = "user2";
 const USER3: &str = "user3";
 const GENERATOR: &str = "generator";
 const CONTROLLER: &str = "controller";
 const FEE_COLLECTOR: &str = "fee_collector";
+const PARTNER_FEE_COLLECTOR: &str = "partner_fee_collector";
 const LP_TOKEN: &str = "lp_token";
+const LP_TOKEN_2: &str = "lp_token_2";
 
 const XASTRO_TOKEN: &str = "xastro";
 const VOTING_ESCROW: &str = "voting_escrow";
@@ -45,6 +53,1139 @@ fn test() -> Result<(), ContractError> {
     claim_income(&mut deps)?;
 
     send_income(&mut deps)?;
+    sync_quota(&mut deps)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_withdraw_all() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    // user1 is the only staker in pool 1 and shares pool 2 with user2
+    let deposits = [
+        (LP_TOKEN, USER1, 100u128, 100u128),
+        (LP_TOKEN_2, USER2, 60u128, 60u128),
+        (LP_TOKEN_2, USER1, 40u128, 150u128),
+    ];
+    for (lp_token, user, amount, balance) in deposits {
+        let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+            lp_token: Addr::unchecked(lp_token),
+            staker_addr: Addr::unchecked(user),
+            amount: Uint128::from(amount),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(GENERATOR.to_string(), lp_token.to_string(), Uint128::from(balance));
+        deps.querier.set_user_info(&Addr::unchecked(lp_token), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+            amount: Uint128::from(balance),
+            reward_user_index: Decimal::zero(),
+            reward_debt_proxy: RestrictedVector::default(),
+            virtual_amount: Uint128::from(balance),
+        })?;
+    }
+
+    // nothing to withdraw
+    let info = mock_info(USER3, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::WithdrawAll {})?;
+    assert!(res.messages.is_empty());
+
+    // withdraw full share of both pools
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::WithdrawAll {})?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "withdraw_all"),
+        attr("pool_count", "2"),
+    ]);
+    let mut expected: Vec<CosmosMsg> = vec![];
+    for (lp_token, amount) in [(LP_TOKEN, 100u128), (LP_TOKEN_2, 60u128)] {
+        expected.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR.to_string(),
+            msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                lp_token: lp_token.to_string(),
+                amount: Uint128::from(1u128)
+            })?,
+            funds: vec![],
+        }));
+        expected.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_token.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: GENERATOR.to_string(),
+                amount: Uint128::from(1u128),
+                msg: to_binary(&Cw20HookMsg::Deposit {})?,
+            })?,
+        }));
+        expected.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+                lp_token: Addr::unchecked(lp_token),
+                prev_balances: vec![
+                    (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+                ]
+            }))?,
+            funds: vec![],
+        }));
+        expected.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Withdraw {
+                lp_token: Addr::unchecked(lp_token),
+                staker_addr: Addr::unchecked(USER1),
+                amount: Uint128::from(amount),
+            }))?,
+            funds: vec![],
+        }));
+    }
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        expected
+    );
+
+    // withdraw callback clears the share of pool 2
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Withdraw {
+        lp_token: Addr::unchecked(LP_TOKEN_2),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(60u128),
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::UserInfo {
+        lp_token: LP_TOKEN_2.to_string(),
+        user: USER1.to_string(),
+    };
+    let res: UserInfoResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.bond_share, Uint128::zero());
+
+    Ok(())
+}
+
+#[test]
+fn test_withdraw_all_max_pools() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    // user1 is bonded in 11 pools
+    for i in 0..11 {
+        let lp_token = format!("lp_token_{}", i);
+        let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+            lp_token: Addr::unchecked(&lp_token),
+            staker_addr: Addr::unchecked(USER1),
+            amount: Uint128::from(100u128),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(GENERATOR.to_string(), lp_token, Uint128::from(100u128));
+    }
+
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::WithdrawAll {});
+    assert_error(res, "bonded in more than 10 pools, use Withdraw for each lp_token");
+
+    Ok(())
+}
+
+#[test]
+fn test_withdraw_claim() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    // user1 is the only staker and earns 100 astro
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_balance(GENERATOR.to_string(), LP_TOKEN.to_string(), Uint128::from(100u128));
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(100u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::default(),
+        virtual_amount: Uint128::from(40u128),
+    })?;
+    deps.querier.set_balance(ASTRO_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(100u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        prev_balances: vec![
+            (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+        ],
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let withdraw_callback = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Withdraw {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            staker_addr: Addr::unchecked(USER1),
+            amount: Uint128::from(100u128),
+        }))?,
+        funds: vec![],
+    });
+    let claim_callback = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ClaimRewards {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            staker_addr: Addr::unchecked(USER1),
+        }))?,
+        funds: vec![],
+    });
+
+    // withdraw without claim leaves rewards pending
+    let user_info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::Withdraw {
+        lp_token: LP_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        claim: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), msg)?;
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|it| it.msg).collect();
+    assert_eq!(messages.last(), Some(&withdraw_callback));
+
+    // withdraw with claim appends the claim after the withdraw
+    let msg = ExecuteMsg::Withdraw {
+        lp_token: LP_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+        claim: Some(true),
+    };
+    let res = execute(deps.as_mut(), env.clone(), user_info, msg)?;
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|it| it.msg).collect();
+    assert_eq!(messages[messages.len() - 2..], [withdraw_callback, claim_callback]);
+
+    // withdraw and claim callbacks unbond and send the pending astro
+    let msg = ExecuteMsg::Callback(CallbackMsg::Withdraw {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    let msg = ExecuteMsg::Callback(CallbackMsg::ClaimRewards {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER1.to_string(),
+                    amount: Uint128::from(100u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_claim_all_rewards() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+    // user1 is the only staker in pool 1 and shares pool 2 with user2
+    let deposits = [
+        (LP_TOKEN, USER1, 100u128, 100u128),
+        (LP_TOKEN_2, USER2, 60u128, 60u128),
+        (LP_TOKEN_2, USER1, 40u128, 100u128),
+    ];
+    for (lp_token, user, amount, balance) in deposits {
+        let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+            lp_token: Addr::unchecked(lp_token),
+            staker_addr: Addr::unchecked(user),
+            amount: Uint128::from(amount),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_balance(GENERATOR.to_string(), lp_token.to_string(), Uint128::from(balance));
+        deps.querier.set_user_info(&Addr::unchecked(lp_token), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+            amount: Uint128::from(balance),
+            reward_user_index: Decimal::zero(),
+            reward_debt_proxy: RestrictedVector::default(),
+            virtual_amount: Uint128::from(balance * 4 / 10),
+        })?;
+    }
+
+    // no boost, all astro goes to lp
+    for (lp_token, prev_balance, balance) in [(LP_TOKEN, 0u128, 100u128), (LP_TOKEN_2, 100u128, 150u128)] {
+        deps.querier.set_balance(ASTRO_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(balance));
+        let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(lp_token),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::from(prev_balance)),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    }
+
+    // pending summed over both pools
+    let msg = QueryMsg::PendingTokenAll {
+        user: USER1.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: PendingTokenAllResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PendingTokenAllResponse {
+        pending: vec![token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(120u128))],
+        last_key: None,
+    });
+
+    let msg = QueryMsg::PendingTokenAll {
+        user: USER1.to_string(),
+        start_after: None,
+        limit: Some(1),
+    };
+    let res: PendingTokenAllResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PendingTokenAllResponse {
+        pending: vec![token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(100u128))],
+        last_key: Some(LP_TOKEN.to_string()),
+    });
+
+    let msg = QueryMsg::PendingTokenAll {
+        user: USER1.to_string(),
+        start_after: res.last_key,
+        limit: Some(1),
+    };
+    let res: PendingTokenAllResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PendingTokenAllResponse {
+        pending: vec![token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(20u128))],
+        last_key: Some(LP_TOKEN_2.to_string()),
+    });
+
+    // limit counts scanned pools, user2 is only bonded in pool 2
+    let msg = QueryMsg::PendingTokenAll {
+        user: USER2.to_string(),
+        start_after: None,
+        limit: Some(1),
+    };
+    let res: PendingTokenAllResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PendingTokenAllResponse {
+        pending: vec![],
+        last_key: Some(LP_TOKEN.to_string()),
+    });
+
+    let msg = QueryMsg::PendingTokenAll {
+        user: USER3.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: PendingTokenAllResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PendingTokenAllResponse {
+        pending: vec![],
+        last_key: None,
+    });
+
+    // nothing to claim
+    let info = mock_info(USER3, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimAllRewards {})?;
+    assert!(res.messages.is_empty());
+
+    // claim both pools
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimAllRewards {})?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "claim_all_rewards"),
+        attr("pool_count", "2"),
+    ]);
+    let callbacks: Vec<CosmosMsg> = [LP_TOKEN, LP_TOKEN_2].iter()
+        .map(|lp_token| CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ClaimRewards {
+                lp_token: Addr::unchecked(*lp_token),
+                staker_addr: Addr::unchecked(USER1),
+            })).unwrap(),
+            funds: vec![],
+        }))
+        .collect();
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|it| it.msg).collect();
+    for callback in callbacks.iter() {
+        assert!(messages.contains(callback));
+    }
+
+    // both reward transfers are sent to user1
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    for (lp_token, amount) in [(LP_TOKEN, 100u128), (LP_TOKEN_2, 20u128)] {
+        let msg = ExecuteMsg::Callback(CallbackMsg::ClaimRewards {
+            lp_token: Addr::unchecked(lp_token),
+            staker_addr: Addr::unchecked(USER1),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        assert_eq!(
+            res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: ASTRO_TOKEN.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: USER1.to_string(),
+                        amount: Uint128::from(amount),
+                    })?,
+                    funds: vec![],
+                }),
+            ]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_boost_info() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let msg = QueryMsg::BoostInfo {
+        lp_token: LP_TOKEN.to_string(),
+    };
+
+    // nothing deposited
+    let res: BoostInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+    assert_eq!(res, BoostInfoResponse {
+        amount: Uint128::zero(),
+        virtual_amount: Uint128::zero(),
+        boost_ratio: Decimal::one(),
+    });
+
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(160u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::default(),
+        virtual_amount: Uint128::from(128u128),
+    })?;
+    let res: BoostInfoResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res, BoostInfoResponse {
+        amount: Uint128::from(160u128),
+        virtual_amount: Uint128::from(128u128),
+        boost_ratio: Decimal::percent(80),
+    });
+
+    Ok(())
+}
+
+#[test]
+fn test_pool_boost_fee() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let msg = ExecuteMsg::UpdatePoolBoostFee {
+        lp_token: LP_TOKEN_2.to_string(),
+        boost_fee: Some(Decimal::percent(120)),
+    };
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "boost_fee cannot greater than 1");
+
+    let msg = ExecuteMsg::UpdatePoolBoostFee {
+        lp_token: LP_TOKEN_2.to_string(),
+        boost_fee: Some(Decimal::percent(50)),
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // pool 1 uses the global boost_fee, pool 2 uses its override
+    // each pool earns 100 astro, 40 based and 60 boosted
+    let pools = [
+        (LP_TOKEN, Decimal::percent(10), 100u128, 6u128, Decimal::percent(67)),
+        (LP_TOKEN_2, Decimal::percent(50), 200u128, 36u128, Decimal::percent(55)),
+    ];
+    for (lp_token, boost_fee, astro_amount, total_fee, astro_index) in pools {
+        let msg = QueryMsg::PoolBoostFee {
+            lp_token: lp_token.to_string(),
+        };
+        let res: Decimal = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res, boost_fee);
+
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+            lp_token: Addr::unchecked(lp_token),
+            staker_addr: Addr::unchecked(USER1),
+            amount: Uint128::from(100u128),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_user_info(&Addr::unchecked(lp_token), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+            amount: Uint128::from(100u128),
+            reward_user_index: Decimal::one(),
+            reward_debt_proxy: RestrictedVector::default(),
+            virtual_amount: Uint128::from(100u128),
+        })?;
+        deps.querier.set_balance(ASTRO_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(astro_amount));
+
+        let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(lp_token),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::from(astro_amount - 100u128)),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info, msg)?;
+
+        let msg = QueryMsg::RewardInfo {
+            token: ASTRO_TOKEN.to_string(),
+        };
+        let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res.fee, Uint128::from(total_fee));
+
+        let msg = QueryMsg::PoolInfo {
+            lp_token: lp_token.to_string(),
+        };
+        let res: PoolInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res.reward_indexes, RestrictedVector::from(vec![
+            (Addr::unchecked(ASTRO_TOKEN), astro_index),
+        ]));
+    }
+
+    // unset override falls back to the global boost_fee
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::UpdatePoolBoostFee {
+        lp_token: LP_TOKEN_2.to_string(),
+        boost_fee: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::PoolBoostFee {
+        lp_token: LP_TOKEN_2.to_string(),
+    };
+    let res: Decimal = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res, Decimal::percent(10));
+
+    Ok(())
+}
+
+#[test]
+fn test_pool_config() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+        asset_rewards: vec![ASSET_TOKEN.to_string()],
+    };
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER1, &[]);
+    let dup_msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+        asset_rewards: vec![ASSET_TOKEN.to_string(), ASSET_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), dup_msg);
+    assert_error(res, "duplicated asset_rewards: asset");
+
+    let astro_msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+        asset_rewards: vec![ASTRO_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), astro_msg);
+    assert_error(res, "duplicated asset_rewards: astro");
+
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::PoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: PoolConfig = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PoolConfig {
+        asset_rewards: vec![Addr::unchecked(ASSET_TOKEN)],
+        fee_collector: None,
+    });
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(100u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::default(),
+        virtual_amount: Uint128::from(100u128),
+    })?;
+    deps.querier.set_balance(ASSET_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(5u128));
+
+    // next bond snapshots the asset balance
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER2.to_string(),
+        amount: Uint128::from(60u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert!(res.messages.into_iter().any(|it| it.msg == CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+                (Addr::unchecked(ASSET_TOKEN), Uint128::from(5u128)),
+            ],
+        })).unwrap(),
+        funds: vec![],
+    })));
+
+    // claim receives 20 asset
+    deps.querier.set_balance(ASSET_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(25u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        prev_balances: vec![
+            (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+            (Addr::unchecked(ASSET_TOKEN), Uint128::from(5u128)),
+        ],
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::PoolInfo {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: PoolInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.reward_indexes, RestrictedVector::from(vec![
+        (Addr::unchecked(ASSET_TOKEN), Decimal::percent(20)),
+    ]));
+
+    // empty list clears the config
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+        asset_rewards: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::PoolConfig {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: PoolConfig = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res, PoolConfig::default());
+
+    Ok(())
+}
+
+#[test]
+fn test_pool_fee_collector() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let msg = ExecuteMsg::SetPoolFeeCollector {
+        lp_token: LP_TOKEN_2.to_string(),
+        fee_collector: Some(PARTNER_FEE_COLLECTOR.to_string()),
+    };
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let owner_info = mock_info(USER1, &[]);
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg)?;
+
+    // updating the asset rewards keeps the fee_collector override
+    let msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN_2.to_string(),
+        asset_rewards: vec![ASSET_TOKEN.to_string()],
+    };
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg)?;
+    let pool_config_msg = QueryMsg::PoolConfig {
+        lp_token: LP_TOKEN_2.to_string(),
+    };
+    let res: PoolConfig = from_binary(&query(deps.as_ref(), env.clone(), pool_config_msg.clone())?)?;
+    assert_eq!(res, PoolConfig {
+        asset_rewards: vec![Addr::unchecked(ASSET_TOKEN)],
+        fee_collector: Some(Addr::unchecked(PARTNER_FEE_COLLECTOR)),
+    });
+    let msg = ExecuteMsg::SetPoolConfig {
+        lp_token: LP_TOKEN_2.to_string(),
+        asset_rewards: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), owner_info.clone(), msg)?;
+    let res: PoolConfig = from_binary(&query(deps.as_ref(), env.clone(), pool_config_msg.clone())?)?;
+    assert_eq!(res, PoolConfig {
+        asset_rewards: vec![],
+        fee_collector: Some(Addr::unchecked(PARTNER_FEE_COLLECTOR)),
+    });
+
+    // each pool earns 100 astro, fee is 10% of 60 boosted astro
+    for (lp_token, astro_amount) in [(LP_TOKEN, 100u128), (LP_TOKEN_2, 200u128)] {
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+            lp_token: Addr::unchecked(lp_token),
+            staker_addr: Addr::unchecked(USER1),
+            amount: Uint128::from(100u128),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+        deps.querier.set_user_info(&Addr::unchecked(lp_token), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+            amount: Uint128::from(100u128),
+            reward_user_index: Decimal::one(),
+            reward_debt_proxy: RestrictedVector::default(),
+            virtual_amount: Uint128::from(100u128),
+        })?;
+        deps.querier.set_balance(ASTRO_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(astro_amount));
+
+        let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(lp_token),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::from(astro_amount - 100u128)),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info, msg)?;
+    }
+
+    // the fee of pool 2 is kept apart from the global fee
+    let msg = QueryMsg::RewardInfo {
+        token: ASTRO_TOKEN.to_string(),
+    };
+    let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+    assert_eq!(res.fee, Uint128::from(6u128));
+    let res: Vec<(Addr, Uint128)> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::TotalFees {})?)?;
+    assert_eq!(res, vec![
+        (Addr::unchecked(ASTRO_TOKEN), Uint128::from(12u128)),
+    ]);
+
+    let info = mock_info(FEE_COLLECTOR, &[]);
+    let collect_msg = ExecuteMsg::CollectFees {
+        tokens: vec![ASTRO_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), collect_msg.clone())?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(6u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: PARTNER_FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(6u128),
+                })?,
+                funds: vec![],
+            }),
+        ]);
+
+    let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.fee, Uint128::zero());
+    assert_eq!(res.reconciled_amount, Uint128::from(188u128));
+
+    // nothing left to collect
+    let res = execute(deps.as_mut(), env.clone(), info, collect_msg)?;
+    assert!(res.messages.is_empty());
+
+    // unset falls back to the global fee_collector, the empty config is removed
+    let msg = ExecuteMsg::SetPoolFeeCollector {
+        lp_token: LP_TOKEN_2.to_string(),
+        fee_collector: None,
+    };
+    execute(deps.as_mut(), env.clone(), owner_info, msg)?;
+    let res: PoolConfig = from_binary(&query(deps.as_ref(), env, pool_config_msg)?)?;
+    assert_eq!(res, PoolConfig::default());
+
+    Ok(())
+}
+
+#[test]
+fn test_total_fees() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let res: Vec<(Addr, Uint128)> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::TotalFees {})?)?;
+    assert!(res.is_empty());
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(100u128),
+        reward_user_index: Decimal::one(),
+        reward_debt_proxy: RestrictedVector::default(),
+        virtual_amount: Uint128::from(100u128),
+    })?;
+
+    // each claim earns 100 astro, fee is 10% of 60 boosted astro
+    for (astro_amount, total_fee) in [(100u128, 6u128), (200u128, 12u128)] {
+        deps.querier.set_balance(ASTRO_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(astro_amount));
+        let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::from(astro_amount - 100u128)),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+        let res: Vec<(Addr, Uint128)> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::TotalFees {})?)?;
+        assert_eq!(res, vec![
+            (Addr::unchecked(ASTRO_TOKEN), Uint128::from(total_fee)),
+        ]);
+    }
+
+    // sending income does not reduce lifetime total
+    let info = mock_info(CONTROLLER, &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::SendIncome {})?;
+    let res: Vec<(Addr, Uint128)> = from_binary(&query(deps.as_ref(), env, QueryMsg::TotalFees {})?)?;
+    assert_eq!(res, vec![
+        (Addr::unchecked(ASTRO_TOKEN), Uint128::from(12u128)),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_overrides() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: None,
+        fee_overrides: Some(vec![(REWARD_TOKEN.to_string(), Decimal::percent(120))]),
+        max_reward_tokens: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "fee_overrides cannot greater than 1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: None,
+        fee_overrides: Some(vec![(REWARD_TOKEN.to_string(), Decimal::zero())]),
+        max_reward_tokens: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_reward_proxy(&Addr::unchecked(REWARD_PROXY), &Addr::unchecked(REWARD_TOKEN))?;
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(100u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::from(vec![
+            (Addr::unchecked(REWARD_PROXY), Uint128::zero()),
+        ]),
+        virtual_amount: Uint128::from(100u128),
+    })?;
+
+    // 0% override passes the whole reward to the index, 25% override takes fee
+    let claims = [
+        (Decimal::zero(), 20u128, 0u128, Decimal::percent(20)),
+        (Decimal::percent(25), 40u128, 5u128, Decimal::percent(35)),
+    ];
+    for (token_fee, token_amount, fee, token_index) in claims {
+        let msg = ExecuteMsg::UpdateConfig {
+            controller: None,
+            boost_fee: None,
+            fee_overrides: Some(vec![(REWARD_TOKEN.to_string(), token_fee)]),
+            max_reward_tokens: None,
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)?;
+
+        deps.querier.set_balance(REWARD_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(token_amount));
+        let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            prev_balances: vec![
+                (Addr::unchecked(REWARD_TOKEN), Uint128::from(token_amount - 20u128)),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+        let msg = QueryMsg::RewardInfo {
+            token: REWARD_TOKEN.to_string(),
+        };
+        let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res, RewardInfo {
+            staker_income: Uint128::zero(),
+            fee: Uint128::from(fee),
+            reconciled_amount: Uint128::from(token_amount),
+        });
+
+        let msg = QueryMsg::PoolInfo {
+            lp_token: LP_TOKEN.to_string(),
+        };
+        let res: PoolInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+        assert_eq!(res.reward_indexes, RestrictedVector::from(vec![
+            (Addr::unchecked(REWARD_TOKEN), token_index),
+        ]));
+    }
+
+    // token fee is sent with income
+    let info = mock_info(CONTROLLER, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::SendIncome {})?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(5u128),
+                })?,
+                funds: vec![],
+            }),
+        ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_collect_fees() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let env = mock_env();
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: None,
+        fee_overrides: Some(vec![(REWARD_TOKEN.to_string(), Decimal::percent(25))]),
+        max_reward_tokens: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_reward_proxy(&Addr::unchecked(REWARD_PROXY), &Addr::unchecked(REWARD_TOKEN))?;
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(100u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::from(vec![
+            (Addr::unchecked(REWARD_PROXY), Uint128::zero()),
+        ]),
+        virtual_amount: Uint128::from(100u128),
+    })?;
+
+    // claim 20 reward token, 25% fee
+    deps.querier.set_balance(REWARD_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(20u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        prev_balances: vec![
+            (Addr::unchecked(REWARD_TOKEN), Uint128::zero()),
+        ],
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::RewardInfo {
+        token: REWARD_TOKEN.to_string(),
+    };
+    let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+    assert_eq!(res.fee, Uint128::from(5u128));
+
+    let info = mock_info(USER2, &[]);
+    let collect_msg = ExecuteMsg::CollectFees {
+        tokens: vec![REWARD_TOKEN.to_string(), ASTRO_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, collect_msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // astro has no fee and is skipped
+    let info = mock_info(FEE_COLLECTOR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), collect_msg.clone())?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(5u128),
+                })?,
+                funds: vec![],
+            }),
+        ]);
+    assert_eq!(res.attributes, vec![
+        attr("action", "collect_fees"),
+        attr("token", REWARD_TOKEN),
+        attr("amount", "5"),
+    ]);
+
+    let res: RewardInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, RewardInfo {
+        staker_income: Uint128::zero(),
+        fee: Uint128::zero(),
+        reconciled_amount: Uint128::from(15u128),
+    });
+
+    // nothing left to collect
+    let res = execute(deps.as_mut(), env, info, collect_msg)?;
+    assert!(res.messages.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_max_reward_tokens() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: None,
+        fee_overrides: None,
+        max_reward_tokens: Some(2),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)?;
+
+    // user1 is the only staker in a pool with 3 proxy reward tokens
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::Deposit {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+        amount: Uint128::from(100u128),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    deps.querier.set_balance(GENERATOR.to_string(), LP_TOKEN.to_string(), Uint128::from(100u128));
+    let tokens = [REWARD_TOKEN, REWARD_TOKEN_2, REWARD_TOKEN_3];
+    let proxies = [REWARD_PROXY, REWARD_PROXY_2, REWARD_PROXY_3];
+    for (proxy, token) in proxies.iter().zip(tokens.iter()) {
+        deps.querier.set_reward_proxy(&Addr::unchecked(*proxy), &Addr::unchecked(*token))?;
+    }
+    let set_debts = |deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, debt: u128| {
+        deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+            amount: Uint128::from(100u128),
+            reward_user_index: Decimal::zero(),
+            reward_debt_proxy: RestrictedVector::from(proxies.iter().enumerate()
+                .map(|(i, proxy)| (Addr::unchecked(*proxy), Uint128::from(debt * (i as u128 + 1))))
+                .collect::<Vec<_>>()),
+            virtual_amount: Uint128::from(100u128),
+        })
+    };
+    set_debts(&mut deps, 0)?;
+
+    // only the first 2 tokens by address are claimed
+    let msg = ExecuteMsg::ClaimRewards {
+        lp_tokens: vec![LP_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)?;
+    let prev_balances = vec![
+        (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+        (Addr::unchecked(REWARD_TOKEN), Uint128::zero()),
+        (Addr::unchecked(REWARD_TOKEN_2), Uint128::zero()),
+    ];
+    assert_eq!(res.messages[2].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            prev_balances: prev_balances.clone(),
+        }))?,
+        funds: vec![],
+    }));
+
+    // the third token is received but deferred
+    for (i, token) in tokens.iter().enumerate() {
+        deps.querier.set_balance(token.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10u128 * (i as u128 + 1)));
+    }
+    set_debts(&mut deps, 10)?;
+
+    // pending token leaves out the token beyond the cap, as the claim does
+    let msg = QueryMsg::PendingToken {
+        lp_token: LP_TOKEN.to_string(),
+        user: USER1.to_string(),
+    };
+    let res: PendingTokenResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.pending_on_proxy, Some(vec![
+        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(10u128)),
+        token_asset(Addr::unchecked(REWARD_TOKEN_2), Uint128::from(20u128)),
+    ]));
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        prev_balances,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![attr("skipped_reward_tokens", REWARD_TOKEN_3)]);
+
+    let msg = QueryMsg::PoolInfo {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: PoolInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.deferred_reward_proxy, vec![
+        (Addr::unchecked(REWARD_TOKEN_3), Uint128::from(30u128)),
+    ]);
+
+    // pending token still includes the deferred reward
+    let msg = QueryMsg::PendingToken {
+        lp_token: LP_TOKEN.to_string(),
+        user: USER1.to_string(),
+    };
+    let res: PendingTokenResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.pending_on_proxy, Some(vec![
+        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(10u128)),
+        token_asset(Addr::unchecked(REWARD_TOKEN_2), Uint128::from(20u128)),
+        token_asset(Addr::unchecked(REWARD_TOKEN_3), Uint128::from(30u128)),
+    ]));
+
+    // the follow-up claim reconciles the deferred token first
+    env.block.height += 1;
+    let msg = ExecuteMsg::ClaimRewards {
+        lp_tokens: vec![LP_TOKEN.to_string()],
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)?;
+    assert_eq!(res.messages[2].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+        msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::AfterBondClaimed {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            prev_balances: vec![
+                (Addr::unchecked(ASTRO_TOKEN), Uint128::zero()),
+                (Addr::unchecked(REWARD_TOKEN), Uint128::from(10u128)),
+                (Addr::unchecked(REWARD_TOKEN_3), Uint128::from(30u128)),
+            ],
+        }))?,
+        funds: vec![],
+    }));
+
+    let msg = QueryMsg::PoolInfo {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: PoolInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.deferred_reward_proxy, vec![]);
+
+    // all rewards are paid in full
+    let msg = ExecuteMsg::Callback(CallbackMsg::ClaimRewards {
+        lp_token: Addr::unchecked(LP_TOKEN),
+        staker_addr: Addr::unchecked(USER1),
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    let transfers = tokens.iter().enumerate()
+        .map(|(i, token)| token_asset(Addr::unchecked(*token), Uint128::from(10u128 * (i as u128 + 1)))
+            .transfer_msg(&Addr::unchecked(USER1)))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+    assert_eq!(res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(), transfers);
 
     Ok(())
 }
@@ -60,6 +1201,66 @@ fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     }
 }
 
+#[test]
+fn test_update_parameters() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START);
+    let info = mock_info(CONTROLLER, &[]);
+
+    let msg = ExecuteMsg::UpdateParameters {
+        max_quota: Some(Uint128::zero()),
+        staker_rate: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "max_quota must be greater than 0");
+
+    let msg = ExecuteMsg::UpdateParameters {
+        max_quota: None,
+        staker_rate: Some(Decimal::percent(60)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "update_parameters"),
+        attr("prev_max_quota", "1000"),
+        attr("max_quota", "1000"),
+        attr("prev_staker_rate", "0.5"),
+        attr("staker_rate", "0.6"),
+    ]);
+
+    env.block.time = Timestamp::from_seconds(EPOCH_START + 100);
+    let msg = ExecuteMsg::UpdateParameters {
+        max_quota: Some(Uint128::from(2000u128)),
+        staker_rate: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "update_parameters"),
+        attr("prev_max_quota", "1000"),
+        attr("max_quota", "2000"),
+        attr("prev_staker_rate", "0.6"),
+        attr("staker_rate", "0.6"),
+    ]);
+
+    let res: Vec<ParameterRecord> = from_binary(&query(deps.as_ref(), env, QueryMsg::ParameterHistory {})?)?;
+    assert_eq!(res, vec![
+        ParameterRecord {
+            time: EPOCH_START,
+            max_quota: Uint128::from(1000u128),
+            staker_rate: Decimal::percent(60),
+        },
+        ParameterRecord {
+            time: EPOCH_START + 100,
+            max_quota: Uint128::from(2000u128),
+            staker_rate: Decimal::percent(60),
+        },
+    ]);
+
+    Ok(())
+}
+
 fn create(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
     let mut env = mock_env();
     env.block.time = Timestamp::from_seconds(EPOCH_START);
@@ -114,6 +1315,8 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
     let msg = ExecuteMsg::UpdateConfig {
         controller: None,
         boost_fee: Some(Decimal::percent(120)),
+        fee_overrides: None,
+        max_reward_tokens: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -125,6 +1328,8 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
     let msg = ExecuteMsg::UpdateConfig {
         controller: None,
         boost_fee: Some(Decimal::percent(20)),
+        fee_overrides: None,
+        max_reward_tokens: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -164,6 +1369,8 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
         staker_rate: Decimal::percent(60),
         max_quota: Uint128::from(1000u128),
         boost_fee: Decimal::percent(20),
+        fee_overrides: vec![],
+        max_reward_tokens: 0,
     });
 
     Ok(())
@@ -219,6 +1426,12 @@ fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
 
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![
+        attr("add_share", "100"),
+        attr("staker", USER1),
+        attr("lp_token", LP_TOKEN),
+        attr("total_bond_share", "100"),
+    ]);
     assert_eq!(
         res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
         [
@@ -356,6 +1569,7 @@ fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
             (Addr::unchecked(REWARD_TOKEN), Uint128::from(20u128)),
         ]),
         last_reconcile: 12345,
+        deferred_reward_proxy: vec![],
     });
 
     let msg = QueryMsg::RewardInfo {
@@ -450,6 +1664,7 @@ fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
         prev_reward_user_index: Decimal::permille(125),
         prev_reward_debt_proxy: RestrictedVector::default(),
         last_reconcile: 12345,
+        deferred_reward_proxy: vec![],
     });
 
     Ok(())
@@ -488,6 +1703,16 @@ fn claim_rewards(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) ->
         token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(30u128)),
     ]));
 
+    let msg = QueryMsg::ClaimableRewards {
+        lp_token: LP_TOKEN.to_string(),
+        user: USER1.to_string(),
+    };
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, vec![
+        token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(18u128)),
+        token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(30u128)),
+    ]);
+
     let info = mock_info(USER1, &[]);
     let msg = ExecuteMsg::ClaimRewards {
         lp_tokens: vec![LP_TOKEN.to_string()],
@@ -570,6 +1795,7 @@ fn claim_rewards(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) ->
             (Addr::unchecked(REWARD_TOKEN), Uint128::from(16u128)),
         ]),
         last_reconcile: 12346,
+        deferred_reward_proxy: vec![],
     });
 
     let msg = QueryMsg::RewardInfo {
@@ -668,6 +1894,7 @@ fn withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resu
     let msg = ExecuteMsg::Withdraw {
         lp_token: LP_TOKEN.to_string(),
         amount: Uint128::from(100u128),
+        claim: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -737,7 +1964,7 @@ fn withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resu
         lp_token: Addr::unchecked(LP_TOKEN),
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    assert_error(res, "Cannot Sub with 100 and 101");
+    assert_error(res, "Insufficient bond; requested amount: 101, available amount: 100");
 
     let msg = ExecuteMsg::Callback(CallbackMsg::Withdraw {
         amount: Uint128::from(100u128),
@@ -745,13 +1972,19 @@ fn withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resu
         lp_token: Addr::unchecked(LP_TOKEN),
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![
+        attr("deduct_share", "100"),
+        attr("staker", USER1),
+        attr("lp_token", LP_TOKEN),
+        attr("total_bond_share", "60"),
+    ]);
     assert_eq!(
         res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
         [
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: GENERATOR.to_string(),
                 funds: vec![],
-                msg: to_binary(&ExecuteMsg::Withdraw {
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
                     lp_token: LP_TOKEN.to_string(),
                     amount: Uint128::from(100u128),
                 })?,
@@ -799,6 +2032,7 @@ fn withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resu
         prev_reward_user_index: Decimal::permille(325),
         prev_reward_debt_proxy: RestrictedVector::default(),
         last_reconcile: 12347,
+        deferred_reward_proxy: vec![],
     });
 
     let msg = QueryMsg::UserInfo {
@@ -1255,3 +2489,44 @@ fn send_income(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> R
 
     Ok(())
 }
+
+fn sync_quota(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START);
+
+    // 400 based + 300 boosted out of max 600 boosted
+    deps.querier.set_user_info(&Addr::unchecked(LP_TOKEN), &Addr::unchecked(MOCK_CONTRACT_ADDR), &UserInfoV2 {
+        amount: Uint128::from(1000u128),
+        reward_user_index: Decimal::zero(),
+        reward_debt_proxy: RestrictedVector::default(),
+        virtual_amount: Uint128::from(700u128),
+    })?;
+    deps.querier.set_lock(Addr::unchecked(MOCK_CONTRACT_ADDR), &Lock {
+        amount: Uint128::from(900u128),
+        start: 0,
+        end: 1,
+        last_extend_lock_period: 0
+    })?;
+
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::SyncQuota {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(CONTROLLER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.attributes, vec![
+        attr("action", "sync_quota"),
+        attr("lp_token", LP_TOKEN),
+        attr("old_quota", "1000"),
+        attr("new_quota", "1800"),
+    ]);
+
+    let msg = QueryMsg::Config {};
+    let res: Config = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.max_quota, Uint128::from(1800u128));
+
+    Ok(())
+}
@@ -148,6 +148,8 @@ fn update_rewards(deps: DepsMut) -> Result<Response, ContractError> {
             funds: vec![],
             msg: to_binary(&SpecExecuteMsg::Withdraw {
                 amount: None,
+                staking_token: None,
+                recipient: None,
             })?,
         })));
 
@@ -243,6 +245,7 @@ fn withdraw(
         funds: vec![],
         msg: to_binary(&SpecExecuteMsg::Unbond {
             amount,
+            staking_token: None,
         })?,
     }));
 
@@ -366,6 +369,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 &SpecQueryMsg::RewardInfo {
                     time_seconds: Some(env.block.time.seconds()),
                     staker_addr: env.contract.address.to_string(),
+                    staking_token: None,
                 },
             )?;
             let reward_info = res.reward_info;
@@ -390,6 +394,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 &SpecQueryMsg::RewardInfo {
                     time_seconds: Some(env.block.time.seconds()),
                     staker_addr: env.contract.address.to_string(),
+                    staking_token: None,
                 },
             )?;
             let reward_info = res.reward_info;
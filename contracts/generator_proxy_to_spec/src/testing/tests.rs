@@ -139,6 +139,8 @@ fn test_update_rewards() {
             funds: vec![],
             msg: to_binary(&SpecExecuteMsg::Withdraw {
                 amount: None,
+                staking_token: None,
+                recipient: None,
             }).unwrap(),
         }))]
     );
@@ -252,6 +254,7 @@ fn test_withdraw() {
                 funds: vec![],
                 msg: to_binary(&SpecExecuteMsg::Unbond {
                     amount: Uint128::new(100),
+                    staking_token: None,
                 })
                 .unwrap(),
             }),
@@ -316,6 +319,7 @@ fn test_emergency_withdraw() {
                 funds: vec![],
                 msg: to_binary(&SpecExecuteMsg::Unbond {
                     amount: Uint128::new(100),
+                    staking_token: None,
                 })
                 .unwrap(),
             }),
@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use spectrum::lp_staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    StateResponse,
+    StateResponse, BondAtResponse, SimulateStakeResponse,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -20,4 +20,6 @@ fn main() {
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(RewardInfoResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(BondAtResponse), &out_dir);
+    export_schema(&schema_for!(SimulateStakeResponse), &out_dir);
 }
@@ -2,18 +2,20 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 
 use spectrum::{lp_staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    RewardInfoResponse, StateResponse, RewardInfoResponseItem,
+    RewardInfoResponse, StateResponse, RewardInfoResponseItem, BondAtResponse, SimulateStakeResponse,
 }};
 
 use crate::{
     state::{
-        read_reward_info, Config, RewardInfo, State, CONFIG, STATE, REWARD_INFOS, query_rewards, OWNERSHIP_PROPOSAL
+        read_reward_info, Config, RewardInfo, State, CONFIG, STATES, DISTRIBUTION_SCHEDULES, REWARD_INFOS, BOND_CHECKPOINTS,
+        LEGACY_CONFIG, LEGACY_STATE, LEGACY_REWARD_INFOS, LEGACY_BOND_CHECKPOINTS, query_rewards, OWNERSHIP_PROPOSAL,
+        save_bond_checkpoint, read_bond_at, zero_address,
     },
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner}, error::ContractError,
 };
@@ -21,30 +23,56 @@ use crate::{
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use std::collections::BTreeMap;
 
+/// maximum claim fee in percent
+const MAX_CLAIM_FEE_PERCENT: u64 = 10;
+
+/// maximum stakers per reward infos query
+const MAX_STAKER_ADDRS: usize = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 
+    // native reward can be funded at instantiate, cw20 reward is funded by transfer
+    let reward_token = if msg.reward_is_native {
+        if msg.reward_token.is_empty() || info.funds.iter().any(|coin| coin.denom != msg.reward_token) {
+            return Err(StdError::generic_err("only the reward denom can be sent at instantiate").into());
+        }
+        Addr::unchecked(msg.reward_token)
+    } else {
+        if !info.funds.is_empty() {
+            return Err(StdError::generic_err("cw20 reward cannot be funded with native coins").into());
+        }
+        deps.api.addr_validate(&msg.reward_token)?
+    };
+
+    let staking_token = deps.api.addr_validate(&msg.staking_token)?;
     CONFIG.save(
         deps.storage,
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
-            reward_token: deps.api.addr_validate(&msg.reward_token)?,
-            staking_token: deps.api.addr_validate(&msg.staking_token)?,
-            distribution_schedule: msg.distribution_schedule,
+            reward_token,
+            staking_token: staking_token.clone(),
+            claim_fee: Decimal::zero(),
+            fee_collector: deps.api.addr_validate(&msg.owner)?,
+            paused: false,
+            reward_is_native: msg.reward_is_native,
         },
     )?;
 
-    STATE.save(
+    DISTRIBUTION_SCHEDULES.save(deps.storage, &staking_token, &msg.distribution_schedule)?;
+    STATES.save(
         deps.storage,
+        &staking_token,
         &State {
             last_distributed: env.block.time.seconds(),
             total_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            total_distributed: Uint128::zero(),
         },
     )?;
 
@@ -55,13 +83,23 @@ pub fn instantiate(
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+        ExecuteMsg::Unbond { amount, staking_token } => unbond(deps, env, info, amount, staking_token),
         ExecuteMsg::Withdraw {
-            amount
-        } => withdraw(deps, env, info, amount),
+            amount,
+            staking_token,
+            recipient,
+        } => withdraw(deps, env, info, amount, staking_token, recipient),
+        ExecuteMsg::Restake {} => restake(deps, env, info),
+        ExecuteMsg::RegisterStakingToken {
+            token,
+            schedule,
+        } => register_staking_token(deps, env, info, token, schedule),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, env, info, paused),
         ExecuteMsg::UpdateConfig {
             distribution_schedule,
-        } => update_config(deps, env, info, distribution_schedule),
+            claim_fee,
+            fee_collector,
+        } => update_config(deps, env, info, distribution_schedule, claim_fee, fee_collector),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -102,79 +140,97 @@ pub fn receive_cw20(
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
-    let config: Config = CONFIG.load(deps.storage)?;
-
     match from_binary(&cw20_msg.msg) {
         Ok(Cw20HookMsg::Bond { staker_addr }) => {
-            // only staking token contract can execute this message
-            if config.staking_token != info.sender {
+            // only registered staking token contracts can execute this message
+            if !STATES.has(deps.storage, &info.sender) {
                 return Err(ContractError::Unauthorized {});
             }
 
             let cw20_sender = deps.api.addr_validate(&cw20_msg.sender)?.to_string();
-            bond(deps, env, staker_addr.unwrap_or(cw20_sender), cw20_msg.amount)
+            bond(deps, env, info.sender, staker_addr.unwrap_or(cw20_sender), cw20_msg.amount)
         }
         Err(_) => Err(ContractError::InvalidMessage {}),
     }
 }
 
-pub fn bond(deps: DepsMut, env: Env, sender_addr: String, amount: Uint128) -> Result<Response, ContractError> {
+pub fn bond(
+    deps: DepsMut,
+    env: Env,
+    staking_token: Addr,
+    sender_addr: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
     let sender_addr = deps.api.addr_validate(&sender_addr)?;
 
     let config: Config = CONFIG.load(deps.storage)?;
-    let mut state: State = STATE.load(deps.storage)?;
-    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &sender_addr)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state: State = STATES.load(deps.storage, &staking_token)?;
+    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &staking_token, &sender_addr)?;
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_reward(config.paused, &distribution_schedule, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut reward_info)?;
 
     // Increase bond_amount
+    let prev_bond_amount = reward_info.bond_amount;
     increase_bond_amount(&mut state, &mut reward_info, amount);
 
     // Store updated state with staker's reward_info
-    REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
-    STATE.save(deps.storage, &state)?;
+    REWARD_INFOS.save(deps.storage, (&staking_token, &sender_addr), &reward_info)?;
+    STATES.save(deps.storage, &staking_token, &state)?;
+    save_bond_checkpoint(deps.storage, &staking_token, &sender_addr, env.block.time.seconds(), prev_bond_amount, reward_info.bond_amount)?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "bond"),
         ("owner", sender_addr.as_str()),
+        ("staking_token", staking_token.as_str()),
         ("amount", amount.to_string().as_str()),
     ]))
 }
 
-pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> Result<Response, ContractError> {
+pub fn unbond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    staking_token: Option<String>,
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps.as_ref(), &config, staking_token)?;
     let sender_addr = info.sender;
 
-    let mut state: State = STATE.load(deps.storage)?;
-    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &sender_addr)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state: State = STATES.load(deps.storage, &staking_token)?;
+    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &staking_token, &sender_addr)?;
 
     if reward_info.bond_amount < amount {
         return Err(ContractError::UnbondExceedBalance {});
     }
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_reward(config.paused, &distribution_schedule, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut reward_info)?;
 
     // Decrease bond_amount
+    let prev_bond_amount = reward_info.bond_amount;
     decrease_bond_amount(&mut state, &mut reward_info, amount)?;
 
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
     if reward_info.pending_reward.is_zero() && reward_info.bond_amount.is_zero() {
-        REWARD_INFOS.remove(deps.storage, &sender_addr);
+        REWARD_INFOS.remove(deps.storage, (&staking_token, &sender_addr));
     } else {
-        REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
+        REWARD_INFOS.save(deps.storage, (&staking_token, &sender_addr), &reward_info)?;
     }
 
     // Store updated state
-    STATE.save(deps.storage, &state)?;
+    STATES.save(deps.storage, &staking_token, &state)?;
+    save_bond_checkpoint(deps.storage, &staking_token, &sender_addr, env.block.time.seconds(), prev_bond_amount, reward_info.bond_amount)?;
 
     Ok(Response::new()
         .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.staking_token.to_string(),
+            contract_addr: staking_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
                 recipient: sender_addr.to_string(),
                 amount,
@@ -184,20 +240,34 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> Re
         .add_attributes(vec![
             ("action", "unbond"),
             ("owner", sender_addr.as_str()),
+            ("staking_token", staking_token.as_str()),
             ("amount", amount.to_string().as_str()),
         ]))
 }
 
 // withdraw rewards to executor
-pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, spec_amount: Option<Uint128>) -> Result<Response, ContractError> {
+pub fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spec_amount: Option<Uint128>,
+    staking_token: Option<String>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
     let sender_addr = info.sender;
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => sender_addr.clone(),
+    };
 
     let config: Config = CONFIG.load(deps.storage)?;
-    let mut state: State = STATE.load(deps.storage)?;
-    let mut reward_info = read_reward_info(deps.storage, &sender_addr)?;
+    let staking_token = validate_staking_token(deps.as_ref(), &config, staking_token)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state: State = STATES.load(deps.storage, &staking_token)?;
+    let mut reward_info = read_reward_info(deps.storage, &staking_token, &sender_addr)?;
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
+    compute_reward(config.paused, &distribution_schedule, &mut state, env.block.time.seconds());
     compute_staker_reward(&state, &mut reward_info)?;
 
     let amount = spec_amount.unwrap_or(reward_info.pending_reward);
@@ -206,27 +276,105 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, spec_amount: Option<
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
     if reward_info.bond_amount.is_zero() && reward_info.pending_reward.is_zero() {
-        REWARD_INFOS.remove(deps.storage, &sender_addr);
+        REWARD_INFOS.remove(deps.storage, (&staking_token, &sender_addr));
     } else {
-        REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
+        REWARD_INFOS.save(deps.storage, (&staking_token, &sender_addr), &reward_info)?;
     }
 
     // Store updated state
-    STATE.save(deps.storage, &state)?;
+    STATES.save(deps.storage, &staking_token, &state)?;
+
+    // Deduct claim fee from the reward
+    let fee_amount = amount * config.claim_fee;
+    let user_amount = amount.checked_sub(fee_amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !fee_amount.is_zero() {
+        messages.push(reward_transfer_msg(&config, &config.fee_collector, fee_amount)?);
+    }
+    messages.push(reward_transfer_msg(&config, &recipient, user_amount)?);
 
     Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        .add_messages(messages)
+        .add_attributes(vec![
+            ("action", "withdraw"),
+            ("owner", sender_addr.as_str()),
+            ("recipient", recipient.as_str()),
+            ("amount", amount.to_string().as_str()),
+            ("fee_amount", fee_amount.to_string().as_str()),
+        ]))
+}
+
+// transfer reward as native coins or cw20 depending on the reward token
+fn reward_transfer_msg(config: &Config, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    if config.reward_is_native {
+        Ok(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: config.reward_token.to_string(),
+                amount,
+            }],
+        }))
+    } else {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: config.reward_token.to_string(),
             msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: sender_addr.to_string(),
+                recipient: recipient.to_string(),
                 amount,
             })?,
             funds: vec![],
-        })])
+        }))
+    }
+}
+
+// bond pending rewards to executor, claim fee is still sent to the fee collector
+pub fn restake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let sender_addr = info.sender;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    if config.reward_token != config.staking_token {
+        return Err(ContractError::RestakeTokenMismatch {});
+    }
+
+    let staking_token = config.staking_token.clone();
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state: State = STATES.load(deps.storage, &staking_token)?;
+    let mut reward_info = read_reward_info(deps.storage, &staking_token, &sender_addr)?;
+
+    // Compute global reward & staker reward
+    compute_reward(config.paused, &distribution_schedule, &mut state, env.block.time.seconds());
+    compute_staker_reward(&state, &mut reward_info)?;
+
+    let amount = reward_info.pending_reward;
+    reward_info.pending_reward = Uint128::zero();
+
+    // Deduct claim fee from the reward and bond the rest
+    let fee_amount = amount * config.claim_fee;
+    let bond_amount = amount.checked_sub(fee_amount)?;
+    let prev_bond_amount = reward_info.bond_amount;
+    increase_bond_amount(&mut state, &mut reward_info, bond_amount);
+
+    // Store updated state with staker's reward_info
+    REWARD_INFOS.save(deps.storage, (&staking_token, &sender_addr), &reward_info)?;
+    STATES.save(deps.storage, &staking_token, &state)?;
+    save_bond_checkpoint(deps.storage, &staking_token, &sender_addr, env.block.time.seconds(), prev_bond_amount, reward_info.bond_amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !fee_amount.is_zero() {
+        messages.push(reward_transfer_msg(&config, &config.fee_collector, fee_amount)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
         .add_attributes(vec![
-            ("action", "withdraw"),
+            ("action", "restake"),
             ("owner", sender_addr.as_str()),
-            ("amount", amount.to_string().as_str()),
+            ("amount", bond_amount.to_string().as_str()),
+            ("fee_amount", fee_amount.to_string().as_str()),
         ]))
 }
 
@@ -235,17 +383,35 @@ pub fn update_config(
     _env: Env,
     info: MessageInfo,
     distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+    claim_fee: Option<Decimal>,
+    fee_collector: Option<String>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
-    let state: State = STATE.load(deps.storage)?;
 
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
 
     if let Some(distribution_schedule) = distribution_schedule {
-        assert_new_schedules(&config, &state, distribution_schedule.clone())?;
-        config.distribution_schedule = distribution_schedule;
+        let state: State = STATES.load(deps.storage, &config.staking_token)?;
+        let current_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &config.staking_token)?;
+        assert_new_schedules(&current_schedule, &state, distribution_schedule.clone())?;
+        DISTRIBUTION_SCHEDULES.save(deps.storage, &config.staking_token, &distribution_schedule)?;
+    }
+
+    if let Some(claim_fee) = claim_fee {
+        if claim_fee > Decimal::percent(MAX_CLAIM_FEE_PERCENT) {
+            return Err(StdError::generic_err(format!("claim_fee cannot be greater than {}%", MAX_CLAIM_FEE_PERCENT)).into());
+        }
+        config.claim_fee = claim_fee;
+    }
+
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+    }
+
+    if !config.claim_fee.is_zero() && config.fee_collector == zero_address() {
+        return Err(StdError::generic_err("fee_collector is required to charge claim_fee").into());
     }
 
     CONFIG.save(deps.storage, &config)?;
@@ -253,6 +419,90 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
 
+pub fn register_staking_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    schedule: Vec<(u64, u64, Uint128)>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let staking_token = deps.api.addr_validate(&token)?;
+    match STATES.may_load(deps.storage, &staking_token)? {
+        Some(state) => {
+            let current_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+            assert_new_schedules(&current_schedule, &state, schedule.clone())?;
+        }
+        None => {
+            STATES.save(
+                deps.storage,
+                &staking_token,
+                &State {
+                    last_distributed: env.block.time.seconds(),
+                    total_bond_amount: Uint128::zero(),
+                    global_reward_index: Decimal::zero(),
+                    total_distributed: Uint128::zero(),
+                },
+            )?;
+        }
+    }
+    DISTRIBUTION_SCHEDULES.save(deps.storage, &staking_token, &schedule)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "register_staking_token"),
+        ("staking_token", staking_token.as_str()),
+    ]))
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // distribute up to now under the current flag
+    let staking_tokens = STATES.keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for staking_token in staking_tokens {
+        let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+        let mut state = STATES.load(deps.storage, &staking_token)?;
+        compute_reward(config.paused, &distribution_schedule, &mut state, env.block.time.seconds());
+        STATES.save(deps.storage, &staking_token, &state)?;
+    }
+
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_paused"),
+        ("paused", &paused.to_string()),
+    ]))
+}
+
+/// returns the given staking token, or the staking token given at instantiate, the token must be registered
+fn validate_staking_token(deps: Deps, config: &Config, staking_token: Option<String>) -> StdResult<Addr> {
+    let staking_token = match staking_token {
+        Some(staking_token) => deps.api.addr_validate(&staking_token)?,
+        None => config.staking_token.clone(),
+    };
+    if !DISTRIBUTION_SCHEDULES.has(deps.storage, &staking_token) {
+        return Err(StdError::generic_err("staking token not registered"));
+    }
+
+    Ok(staking_token)
+}
+
 fn increase_bond_amount(state: &mut State, reward_info: &mut RewardInfo, amount: Uint128) {
     state.total_bond_amount += amount;
     reward_info.bond_amount += amount;
@@ -269,14 +519,20 @@ fn decrease_bond_amount(
 }
 
 // compute distributed rewards and update global reward index
-fn compute_reward(config: &Config, state: &mut State, time_seconds: u64) {
+// while paused, nothing is distributed and the elapsed time is deferred until unpaused
+fn compute_reward(paused: bool, distribution_schedule: &[(u64, u64, Uint128)], state: &mut State, time_seconds: u64) {
+    if paused {
+        return;
+    }
+
     if state.total_bond_amount.is_zero() {
         state.last_distributed = time_seconds;
         return;
     }
 
+    let total_scheduled: Uint128 = distribution_schedule.iter().map(|s| s.2).sum();
     let mut distributed_amount: Uint128 = Uint128::zero();
-    for s in config.distribution_schedule.iter() {
+    for s in distribution_schedule.iter() {
         if s.0 > time_seconds || s.1 < state.last_distributed {
             continue;
         }
@@ -289,7 +545,14 @@ fn compute_reward(config: &Config, state: &mut State, time_seconds: u64) {
         distributed_amount += distribution_amount_per_second * Uint128::from(passed_time as u128);
     }
 
+    // never distribute beyond the scheduled total
+    let distributed_amount = std::cmp::min(
+        distributed_amount,
+        total_scheduled.saturating_sub(state.total_distributed),
+    );
+
     state.last_distributed = time_seconds;
+    state.total_distributed += distributed_amount;
     state.global_reward_index += Decimal::from_ratio(distributed_amount, state.total_bond_amount);
 }
 
@@ -307,39 +570,67 @@ fn compute_staker_reward(state: &State, reward_info: &mut RewardInfo) -> StdResu
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::State { time_seconds } => to_binary(&query_state(deps, env, time_seconds)?),
-        QueryMsg::RewardInfo { staker_addr, time_seconds} => {
-            to_binary(&query_reward_info(deps, env, staker_addr, time_seconds)?)
+        QueryMsg::State { time_seconds, staking_token } => to_binary(&query_state(deps, env, time_seconds, staking_token)?),
+        QueryMsg::RewardInfo { staker_addr, time_seconds, staking_token } => {
+            to_binary(&query_reward_info(deps, env, staker_addr, time_seconds, staking_token)?)
         },
-        QueryMsg::AllRewardInfos { start_after, limit, time_seconds } => {
-            to_binary(&query_all_reward_infos(deps, start_after, limit, time_seconds)?)
+        QueryMsg::AllRewardInfos { start_after, limit, time_seconds, staking_token } => {
+            to_binary(&query_all_reward_infos(deps, start_after, limit, time_seconds, staking_token)?)
+        },
+        QueryMsg::RewardInfos { staker_addrs, time_seconds, staking_token } => {
+            to_binary(&query_reward_infos(deps, env, staker_addrs, time_seconds, staking_token)?)
+        },
+        QueryMsg::BondAt { staker_addr, time_seconds, staking_token } => {
+            to_binary(&query_bond_at(deps, staker_addr, time_seconds, staking_token)?)
+        },
+        QueryMsg::SimulateStake { amount, time_seconds, staking_token } => {
+            to_binary(&query_simulate_stake(deps, env, amount, time_seconds, staking_token)?)
         },
     }
 }
 
 pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &config.staking_token)?;
+    let staking_tokens = STATES.keys(deps.storage, None, None, Order::Ascending)
+        .map(|staking_token| staking_token.map(|it| it.to_string()))
+        .collect::<StdResult<Vec<String>>>()?;
     let resp = ConfigResponse {
         owner: config.owner.to_string(),
         reward_token: config.reward_token.to_string(),
         staking_token: config.staking_token.to_string(),
-        distribution_schedule: config.distribution_schedule,
+        distribution_schedule,
+        staking_tokens,
+        claim_fee: config.claim_fee,
+        fee_collector: config.fee_collector.to_string(),
+        reward_is_native: config.reward_is_native,
     };
 
     Ok(resp)
 }
 
-pub fn query_state(deps: Deps, _env: Env, time_seconds: Option<u64>) -> StdResult<StateResponse> {
-    let mut state: State = STATE.load(deps.storage)?;
+pub fn query_state(
+    deps: Deps,
+    _env: Env,
+    time_seconds: Option<u64>,
+    staking_token: Option<String>,
+) -> StdResult<StateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps, &config, staking_token)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state: State = STATES.load(deps.storage, &staking_token)?;
     if let Some(time_seconds) = time_seconds {
-        let config = CONFIG.load(deps.storage)?;
-        compute_reward(&config, &mut state, time_seconds);
+        compute_reward(config.paused, &distribution_schedule, &mut state, time_seconds);
     }
 
     Ok(StateResponse {
+        staking_token: staking_token.to_string(),
+        distribution_schedule,
+        paused: config.paused,
         last_distributed: state.last_distributed,
         total_bond_amount: state.total_bond_amount,
         global_reward_index: state.global_reward_index,
+        total_distributed: state.total_distributed,
     })
 }
 
@@ -347,44 +638,116 @@ pub fn query_reward_info(
     deps: Deps,
     _env: Env,
     staker_addr: String,
-    time_seconds: Option<u64>
+    time_seconds: Option<u64>,
+    staking_token: Option<String>,
 ) -> StdResult<RewardInfoResponse> {
     let staker_addr = deps.api.addr_validate(&staker_addr)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps, &config, staking_token)?;
 
-    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &staker_addr)?;
+    let mut reward_info: RewardInfo = read_reward_info(deps.storage, &staking_token, &staker_addr)?;
 
     if let Some(time_seconds) = time_seconds {
-        let config = CONFIG.load(deps.storage)?;
-        let mut state = STATE.load(deps.storage)?;
+        let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+        let mut state = STATES.load(deps.storage, &staking_token)?;
 
-        compute_reward(&config, &mut state, time_seconds);
+        compute_reward(config.paused, &distribution_schedule, &mut state, time_seconds);
         compute_staker_reward(&state, &mut reward_info)?;
     }
 
-    let config: Config = CONFIG.load(deps.storage)?;
-
     Ok(RewardInfoResponse {
         staker_addr: staker_addr.to_string(),
         reward_info: RewardInfoResponseItem {
             reward_index: reward_info.reward_index,
             bond_amount: reward_info.bond_amount,
             pending_reward: reward_info.pending_reward,
-            staking_token: config.staking_token.to_string(),
+            staking_token: staking_token.to_string(),
         }
     })
 }
 
+pub fn query_reward_infos(
+    deps: Deps,
+    env: Env,
+    staker_addrs: Vec<String>,
+    time_seconds: Option<u64>,
+    staking_token: Option<String>,
+) -> StdResult<Vec<RewardInfoResponse>> {
+    if staker_addrs.len() > MAX_STAKER_ADDRS {
+        return Err(StdError::generic_err(format!("staker_addrs cannot exceed {} addresses", MAX_STAKER_ADDRS)));
+    }
+
+    staker_addrs.into_iter()
+        .map(|staker_addr| query_reward_info(deps, env.clone(), staker_addr, time_seconds, staking_token.clone()))
+        .collect()
+}
+
+pub fn query_bond_at(
+    deps: Deps,
+    staker_addr: String,
+    time_seconds: u64,
+    staking_token: Option<String>,
+) -> StdResult<BondAtResponse> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+    let config: Config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps, &config, staking_token)?;
+    let bond_amount = read_bond_at(deps.storage, &staking_token, &staker_addr, time_seconds)?;
+
+    Ok(BondAtResponse {
+        staker_addr: staker_addr.to_string(),
+        time_seconds,
+        bond_amount,
+    })
+}
+
+pub fn query_simulate_stake(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+    time_seconds: u64,
+    staking_token: Option<String>,
+) -> StdResult<SimulateStakeResponse> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps, &config, staking_token)?;
+    let current_time = env.block.time.seconds();
+    if time_seconds < current_time {
+        return Err(StdError::generic_err("time_seconds cannot be earlier than the current time"));
+    }
+
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state = STATES.load(deps.storage, &staking_token)?;
+
+    // bond now, then distribute until time_seconds
+    compute_reward(config.paused, &distribution_schedule, &mut state, current_time);
+    state.total_bond_amount += amount;
+    let mut reward_info = RewardInfo {
+        reward_index: state.global_reward_index,
+        bond_amount: amount,
+        pending_reward: Uint128::zero(),
+    };
+    compute_reward(config.paused, &distribution_schedule, &mut state, time_seconds);
+    compute_staker_reward(&state, &mut reward_info)?;
+
+    Ok(SimulateStakeResponse {
+        staking_token: staking_token.to_string(),
+        amount,
+        time_seconds,
+        global_reward_index: state.global_reward_index,
+        pending_reward: reward_info.pending_reward,
+    })
+}
+
 pub fn assert_new_schedules(
-    config: &Config,
+    current_schedule: &[(u64, u64, Uint128)],
     state: &State,
     distribution_schedule: Vec<(u64, u64, Uint128)>,
 ) -> Result<(), ContractError> {
-    if distribution_schedule.len() < config.distribution_schedule.len() {
+    if distribution_schedule.len() < current_schedule.len() {
         return Err(ContractError::InvalidDistributionSchedule {});
     }
 
     let mut existing_counts: BTreeMap<(u64, u64, Uint128), u32> = BTreeMap::new();
-    for schedule in config.distribution_schedule.clone() {
+    for schedule in current_schedule.iter().cloned() {
         let counter = existing_counts.entry(schedule).or_insert(0);
         *counter += 1;
     }
@@ -418,26 +781,30 @@ pub fn query_all_reward_infos(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
-    time_seconds: Option<u64>
+    time_seconds: Option<u64>,
+    staking_token: Option<String>,
 ) -> StdResult<Vec<RewardInfoResponse>> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let staking_token = validate_staking_token(deps, &config, staking_token)?;
     let reward_infos = query_rewards(
         deps,
+        &staking_token,
         start_after,
         limit)?;
     let mut results: Vec<RewardInfoResponse> = vec![];
-    let config: Config = CONFIG.load(deps.storage)?;
-    let mut state = STATE.load(deps.storage)?;
+    let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+    let mut state = STATES.load(deps.storage, &staking_token)?;
 
     for (addr, mut reward_info) in reward_infos {
         if let Some(time_seconds) = time_seconds {
-            compute_reward(&config, &mut state, time_seconds);
+            compute_reward(config.paused, &distribution_schedule, &mut state, time_seconds);
             compute_staker_reward(&state, &mut reward_info)?;
         }
 
         results.push(RewardInfoResponse {
             staker_addr: addr.to_string(),
             reward_info: RewardInfoResponseItem {
-                staking_token: config.staking_token.to_string(),
+                staking_token: staking_token.to_string(),
                 bond_amount: reward_info.bond_amount,
                 reward_index: reward_info.reward_index,
                 pending_reward: reward_info.pending_reward
@@ -449,6 +816,58 @@ pub fn query_all_reward_infos(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // move single staking token storage under the staking token given at instantiate
+    if let Some(state) = LEGACY_STATE.may_load(deps.storage)? {
+        let legacy_config = LEGACY_CONFIG.load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
+        let staking_token = config.staking_token.clone();
+
+        DISTRIBUTION_SCHEDULES.save(deps.storage, &staking_token, &legacy_config.distribution_schedule)?;
+        STATES.save(deps.storage, &staking_token, &state)?;
+        LEGACY_STATE.remove(deps.storage);
+
+        let reward_infos = LEGACY_REWARD_INFOS.range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (staker_addr, reward_info) in reward_infos {
+            REWARD_INFOS.save(deps.storage, (&staking_token, &staker_addr), &reward_info)?;
+            LEGACY_REWARD_INFOS.remove(deps.storage, &staker_addr);
+        }
+
+        let bond_checkpoints = LEGACY_BOND_CHECKPOINTS.range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (staker_addr, checkpoints) in bond_checkpoints {
+            BOND_CHECKPOINTS.save(deps.storage, (&staking_token, &staker_addr), &checkpoints)?;
+            LEGACY_BOND_CHECKPOINTS.remove(deps.storage, &staker_addr);
+        }
+
+        // drops distribution_schedule from the stored config
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    // states stored before total_distributed was tracked are seeded with the amount scheduled
+    // before last_distributed, so the cap leaves only the rest of the schedule to distribute
+    let states = STATES.range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (staking_token, mut state) in states {
+        if !state.total_distributed.is_zero() {
+            continue;
+        }
+        let distribution_schedule = DISTRIBUTION_SCHEDULES.load(deps.storage, &staking_token)?;
+        state.total_distributed = scheduled_amount_before(&distribution_schedule, state.last_distributed);
+        STATES.save(deps.storage, &staking_token, &state)?;
+    }
+
     Ok(Response::default())
 }
+
+// amount of the distribution schedule elapsed before the given time
+fn scheduled_amount_before(distribution_schedule: &[(u64, u64, Uint128)], time_seconds: u64) -> Uint128 {
+    distribution_schedule.iter()
+        .filter(|s| s.0 < time_seconds)
+        .map(|s| {
+            let passed_time = std::cmp::min(s.1, time_seconds) - s.0;
+            Decimal::from_ratio(s.2, s.1 - s.0) * Uint128::from(passed_time as u128)
+        })
+        .sum()
+}
@@ -22,6 +22,9 @@ pub enum ContractError {
 
     #[error("New distribution schedule already started")]
     DistributionScheduleStarted {},
+
+    #[error("Cannot restake; reward token is not the staking token")]
+    RestakeTokenMismatch {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -2,26 +2,60 @@ use cw_storage_plus::{Item, Map, Bound};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Decimal, StdResult, Storage, Uint128, Addr, Deps, Order};
+use cosmwasm_std::{Decimal, StdError, StdResult, Storage, Uint128, Addr, Deps, Order};
 
 use crate::ownership::OwnershipProposal;
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const STATE: Item<State> = Item::new("state");
-pub const REWARD_INFOS: Map<&Addr, RewardInfo> = Map::new("reward_infos");
+/// key = staking token
+pub const STATES: Map<&Addr, State> = Map::new("states");
+/// key = staking token
+pub const DISTRIBUTION_SCHEDULES: Map<&Addr, Vec<(u64, u64, Uint128)>> = Map::new("distribution_schedules");
+/// key = staking token, staker
+pub const REWARD_INFOS: Map<(&Addr, &Addr), RewardInfo> = Map::new("staker_reward_infos");
+/// key = staking token, staker
+pub const BOND_CHECKPOINTS: Map<(&Addr, &Addr), Vec<(u64, Uint128)>> = Map::new("staker_bond_checkpoints");
+
+/// single staking token storage, only read by migrate
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+pub const LEGACY_STATE: Item<State> = Item::new("state");
+pub const LEGACY_REWARD_INFOS: Map<&Addr, RewardInfo> = Map::new("reward_infos");
+pub const LEGACY_BOND_CHECKPOINTS: Map<&Addr, Vec<(u64, Uint128)>> = Map::new("bond_checkpoints");
+
+/// maximum number of (time, bond_amount) checkpoints kept per staker
+pub const MAX_BOND_CHECKPOINTS: usize = 50;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub owner: Addr,
     pub reward_token: Addr,
+    /// staking token given at instantiate, used when a message does not specify one
     pub staking_token: Addr,
+    #[serde(default)] pub claim_fee: Decimal,
+    #[serde(default = "zero_address")] pub fee_collector: Addr,
+    /// stops reward distribution, the elapsed time is deferred until unpaused
+    #[serde(default)] pub paused: bool,
+    /// reward_token holds a native denom instead of a cw20 address
+    #[serde(default)] pub reward_is_native: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
 }
+
+pub fn zero_address() -> Addr {
+    Addr::unchecked("")
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    /// cumulative distributed amount, capped at the sum of the distribution schedule,
+    /// states stored before it was tracked are seeded by migrate with the amount scheduled before last_distributed
+    #[serde(default)] pub total_distributed: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -32,8 +66,8 @@ pub struct RewardInfo {
 }
 
 /// returns rewards owned by this owner
-pub fn read_reward_info(storage: &dyn Storage, owner: &Addr) -> StdResult<RewardInfo> {
-    match REWARD_INFOS.may_load(storage, owner)? {
+pub fn read_reward_info(storage: &dyn Storage, staking_token: &Addr, owner: &Addr) -> StdResult<RewardInfo> {
+    match REWARD_INFOS.may_load(storage, (staking_token, owner))? {
         Some(reward_info) => Ok(reward_info),
         None => Ok(RewardInfo {
             reward_index: Decimal::zero(),
@@ -43,29 +77,67 @@ pub fn read_reward_info(storage: &dyn Storage, owner: &Addr) -> StdResult<Reward
     }
 }
 
+/// records bond amount of the staker at the given time, oldest checkpoints are dropped
+/// on first touch, the bond amount before the change is seeded as the earliest checkpoint
+pub fn save_bond_checkpoint(
+    storage: &mut dyn Storage,
+    staking_token: &Addr,
+    owner: &Addr,
+    time_seconds: u64,
+    prev_bond_amount: Uint128,
+    bond_amount: Uint128,
+) -> StdResult<()> {
+    let mut checkpoints = BOND_CHECKPOINTS.may_load(storage, (staking_token, owner))?.unwrap_or_default();
+    if checkpoints.is_empty() {
+        // a staker without bond had none from the start, otherwise the bond is only known until now
+        let seed_time = if prev_bond_amount.is_zero() { 0 } else { time_seconds.saturating_sub(1) };
+        checkpoints.push((seed_time, prev_bond_amount));
+    }
+    match checkpoints.last_mut() {
+        Some(last) if last.0 == time_seconds => last.1 = bond_amount,
+        _ => checkpoints.push((time_seconds, bond_amount)),
+    }
+    if checkpoints.len() > MAX_BOND_CHECKPOINTS {
+        checkpoints.drain(0..checkpoints.len() - MAX_BOND_CHECKPOINTS);
+    }
+    BOND_CHECKPOINTS.save(storage, (staking_token, owner), &checkpoints)
+}
+
+/// returns bond amount of the staker as of the given time, fails if the time is older than the retained checkpoints
+pub fn read_bond_at(storage: &dyn Storage, staking_token: &Addr, owner: &Addr, time_seconds: u64) -> StdResult<Uint128> {
+    let checkpoints = BOND_CHECKPOINTS.may_load(storage, (staking_token, owner))?.unwrap_or_default();
+    let first_time = match checkpoints.first() {
+        Some((time, _)) => *time,
+        // bond is unchanged since checkpoints were added
+        None => return Ok(read_reward_info(storage, staking_token, owner)?.bond_amount),
+    };
+    checkpoints
+        .iter()
+        .rev()
+        .find(|(time, _)| *time <= time_seconds)
+        .map(|(_, amount)| *amount)
+        .ok_or_else(|| StdError::generic_err(format!("Bond history before {} is not retained", first_time)))
+}
+
 const DEFAULT_LIMIT: u32 = 10;
 pub fn query_rewards(
     deps: Deps,
+    staking_token: &Addr,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<Vec<(Addr, RewardInfo)>> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT) as usize;
-    let user_addr = if let Some(start_after) = start_after.clone() {
-        deps.api.addr_validate(&start_after)?
-    } else {
-        Addr::unchecked("")
-    };
-    let start = if start_after.is_some() {
-        Some(Bound::exclusive(&user_addr))
-    } else {
-        None
-    };
+    let start_after = start_after
+        .map(|start_after| deps.api.addr_validate(&start_after))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
 
     REWARD_INFOS
+        .prefix(staking_token)
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .collect::<StdResult<Vec<(Addr, RewardInfo)>>>()
 }
 
 /// Stores the latest proposal to change contract ownership
-pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
\ No newline at end of file
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -1,17 +1,17 @@
 use std::str::FromStr;
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
-use crate::mock_querier::mock_dependencies;
-use crate::state::Config;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::state::{Config, RewardInfo, State, STATES, LEGACY_BOND_CHECKPOINTS, LEGACY_REWARD_INFOS, LEGACY_STATE, MAX_BOND_CHECKPOINTS, REWARD_INFOS};
 use spectrum::lp_staking::ExecuteMsg::UpdateConfig;
 use spectrum::lp_staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    StateResponse, RewardInfoResponseItem,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RewardInfoResponse,
+    StateResponse, RewardInfoResponseItem, BondAtResponse, SimulateStakeResponse,
 };
-use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
 use cosmwasm_std::{
-    from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg, Timestamp, Response,
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, OwnedDeps, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg, Timestamp, Response,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
@@ -35,6 +35,7 @@ fn proper_initialization() {
         reward_token: "reward0000".to_string(),
         staking_token: "staking0000".to_string(),
         distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -52,6 +53,10 @@ fn proper_initialization() {
             reward_token: "reward0000".to_string(),
             staking_token: "staking0000".to_string(),
             distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+            staking_tokens: vec!["staking0000".to_string()],
+            claim_fee: Decimal::zero(),
+            fee_collector: "owner0000".to_string(),
+            reward_is_native: false,
         }
     );
 
@@ -59,7 +64,8 @@ fn proper_initialization() {
         deps.as_ref(),
         mock_env(),
         QueryMsg::State {
-            time_seconds: None
+            time_seconds: None,
+            staking_token: None,
         },
     )
     .unwrap();
@@ -67,9 +73,13 @@ fn proper_initialization() {
     assert_eq!(
         state,
         StateResponse {
+            staking_token: "staking0000".to_string(),
+            distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+            paused: false,
             last_distributed: mock_env().block.time.seconds(),
             total_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            total_distributed: Uint128::zero(),
         }
     );
 }
@@ -94,9 +104,11 @@ fn test_bond_tokens() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
+    let distribution_schedule = msg.distribution_schedule.clone();
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
@@ -116,7 +128,8 @@ fn test_bond_tokens() {
                 mock_env(),
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0000".to_string(),
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 },
             )
             .unwrap(),
@@ -139,15 +152,20 @@ fn test_bond_tokens() {
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::State {
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 }
             )
             .unwrap()
         )
         .unwrap(),
         StateResponse {
+            staking_token: "staking0000".to_string(),
+            distribution_schedule: distribution_schedule.clone(),
+            paused: false,
             total_bond_amount: Uint128::from(100u128),
             global_reward_index: Decimal::zero(),
+            total_distributed: Uint128::zero(),
             last_distributed: mock_env().block.time.seconds(),
         }
     );
@@ -169,7 +187,8 @@ fn test_bond_tokens() {
                 mock_env(),
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0000".to_string(),
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 },
             )
             .unwrap(),
@@ -192,15 +211,20 @@ fn test_bond_tokens() {
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::State {
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 }
             )
             .unwrap()
         )
         .unwrap(),
         StateResponse {
+            staking_token: "staking0000".to_string(),
+            distribution_schedule: distribution_schedule.clone(),
+            paused: false,
             total_bond_amount: Uint128::from(200u128),
             global_reward_index: Decimal::from_ratio(1000u128, 1u128),
+            total_distributed: Uint128::from(100_000u128),
             last_distributed: mock_env().block.time.seconds() + 10,
         }
     );
@@ -222,7 +246,8 @@ fn test_bond_tokens() {
                 mock_env(),
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0001".to_string(),
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 },
             )
             .unwrap(),
@@ -245,15 +270,20 @@ fn test_bond_tokens() {
                 deps.as_ref(),
                 mock_env(),
                 QueryMsg::State {
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 }
             )
             .unwrap()
         )
         .unwrap(),
         StateResponse {
+            staking_token: "staking0000".to_string(),
+            distribution_schedule: distribution_schedule.clone(),
+            paused: false,
             total_bond_amount: Uint128::from(300u128),
             global_reward_index: Decimal::from_ratio(1000u128, 1u128),
+            total_distributed: Uint128::from(100_000u128),
             last_distributed: mock_env().block.time.seconds() + 10,
         }
     );
@@ -261,6 +291,7 @@ fn test_bond_tokens() {
     // addr0001 unbond
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(100u128),
+        staking_token: None,
     };
 
     let info = mock_info("addr0001", &[]);
@@ -302,6 +333,7 @@ fn test_unbond() {
             (12345, 12345 + 100, Uint128::from(1000000u128)),
             (12345 + 100, 12345 + 200, Uint128::from(10000000u128)),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -319,6 +351,7 @@ fn test_unbond() {
     // unbond 150 tokens; failed
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(150u128),
+        staking_token: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -328,6 +361,7 @@ fn test_unbond() {
     // normal unbond
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(100u128),
+        staking_token: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -346,6 +380,146 @@ fn test_unbond() {
     );
 }
 
+#[test]
+fn test_bond_at() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let bond_time = env.block.time.seconds();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // bond 50 more tokens after 100 seconds
+    env.block.time = env.block.time.plus_seconds(100);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None }).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // unbond 120 tokens after 100 seconds
+    env.block.time = env.block.time.plus_seconds(100);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(120u128),
+        staking_token: None,
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let query_bond_at = |time_seconds: u64| -> Uint128 {
+        from_binary::<BondAtResponse>(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BondAt {
+                    staker_addr: "addr0000".to_string(),
+                    time_seconds,
+                    staking_token: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+        .bond_amount
+    };
+    assert_eq!(query_bond_at(bond_time - 1), Uint128::zero());
+    assert_eq!(query_bond_at(bond_time), Uint128::from(100u128));
+    assert_eq!(query_bond_at(bond_time + 150), Uint128::from(150u128));
+    assert_eq!(query_bond_at(bond_time + 200), Uint128::from(30u128));
+}
+
+#[test]
+fn test_bond_at_retained_history() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // staker bonded before checkpoints were added
+    let staking_token = Addr::unchecked("staking0000");
+    let staker_addr = Addr::unchecked("addr0000");
+    REWARD_INFOS.save(deps.as_mut().storage, (&staking_token, &staker_addr), &RewardInfo {
+        reward_index: Decimal::zero(),
+        bond_amount: Uint128::from(100u128),
+        pending_reward: Uint128::zero(),
+    }).unwrap();
+    STATES.update(deps.as_mut().storage, &staking_token, |it| -> StdResult<_> {
+        let mut state = it.unwrap();
+        state.total_bond_amount = Uint128::from(100u128);
+        Ok(state)
+    }).unwrap();
+
+    let query_bond_at = |deps: Deps, time_seconds: u64| -> StdResult<Uint128> {
+        let res: BondAtResponse = from_binary(&query(
+            deps,
+            mock_env(),
+            QueryMsg::BondAt {
+                staker_addr: "addr0000".to_string(),
+                time_seconds,
+                staking_token: None,
+            },
+        )?)?;
+        Ok(res.bond_amount)
+    };
+
+    // untouched staker reports the current bond
+    let mut env = mock_env();
+    let bond_time = env.block.time.seconds();
+    assert_eq!(query_bond_at(deps.as_ref(), bond_time - 100).unwrap(), Uint128::from(100u128));
+
+    // first bond seeds the previous amount
+    let info = mock_info("staking0000", &[]);
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None }).unwrap(),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), bond_msg.clone()).unwrap();
+    assert_eq!(query_bond_at(deps.as_ref(), bond_time - 1).unwrap(), Uint128::from(100u128));
+    assert_eq!(query_bond_at(deps.as_ref(), bond_time).unwrap(), Uint128::from(150u128));
+    assert_eq!(
+        query_bond_at(deps.as_ref(), bond_time - 2),
+        Err(StdError::generic_err(format!("Bond history before {} is not retained", bond_time - 1)))
+    );
+
+    // oldest checkpoints are dropped
+    for _ in 0..MAX_BOND_CHECKPOINTS {
+        env.block.time = env.block.time.plus_seconds(10);
+        execute(deps.as_mut(), env.clone(), info.clone(), bond_msg.clone()).unwrap();
+    }
+    assert_eq!(
+        query_bond_at(deps.as_ref(), bond_time),
+        Err(StdError::generic_err(format!("Bond history before {} is not retained", bond_time + 10)))
+    );
+    assert_eq!(query_bond_at(deps.as_ref(), bond_time + 10).unwrap(), Uint128::from(200u128));
+}
+
 #[test]
 fn test_compute_reward() {
     let mut deps = mock_dependencies(&[]);
@@ -366,6 +540,7 @@ fn test_compute_reward() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -400,7 +575,8 @@ fn test_compute_reward() {
                 mock_env(),
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0000".to_string(),
-                    time_seconds: None
+                    time_seconds: None,
+                    staking_token: None,
                 },
             ).unwrap()
         )
@@ -424,6 +600,7 @@ fn test_compute_reward() {
     // unbond
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(100u128),
+        staking_token: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
     assert_eq!(
@@ -434,6 +611,7 @@ fn test_compute_reward() {
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0000".to_string(),
                     time_seconds: None,
+                    staking_token: None,
                 },
             )
             .unwrap()
@@ -459,6 +637,7 @@ fn test_compute_reward() {
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0000".to_string(),
                     time_seconds: Some(mock_env().block.time.plus_seconds(120).seconds()),
+                    staking_token: None,
                 },
             )
             .unwrap()
@@ -476,6 +655,129 @@ fn test_compute_reward() {
     );
 }
 
+#[test]
+fn test_simulate_stake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let start = mock_env().block.time.seconds();
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![
+            (start, start + 100, Uint128::from(1000000u128)),
+            (start + 100, start + 200, Uint128::from(10000000u128)),
+        ],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let simulate = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, now: u64, time_seconds: u64| {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(now);
+        query(
+            deps.as_ref(),
+            env,
+            QueryMsg::SimulateStake {
+                amount: Uint128::from(100u128),
+                time_seconds,
+                staking_token: None,
+            },
+        ).and_then(|res| from_binary::<SimulateStakeResponse>(&res))
+    };
+
+    // bond 100 more tokens now
+    // 1,000,000 rewards distributed over 200 tokens in the first 100 seconds
+    assert_eq!(
+        simulate(&deps, start, start + 100).unwrap(),
+        SimulateStakeResponse {
+            staking_token: "staking0000".to_string(),
+            amount: Uint128::from(100u128),
+            time_seconds: start + 100,
+            global_reward_index: Decimal::from_ratio(5000u128, 1u128),
+            pending_reward: Uint128::from(500000u128),
+        }
+    );
+
+    // 10,000,000 rewards distributed over 200 tokens across the schedule boundary
+    let res = simulate(&deps, start, start + 150).unwrap();
+    assert_eq!(res.global_reward_index, Decimal::from_ratio(30000u128, 1u128));
+    assert_eq!(res.pending_reward, Uint128::from(3000000u128));
+
+    // bond after 100 seconds, the first schedule goes to the existing staker only
+    let res = simulate(&deps, start + 100, start + 200).unwrap();
+    assert_eq!(res.global_reward_index, Decimal::from_ratio(60000u128, 1u128));
+    assert_eq!(res.pending_reward, Uint128::from(5000000u128));
+
+    // nothing left to distribute after the schedule ends
+    let res = simulate(&deps, start + 200, start + 1000).unwrap();
+    assert_eq!(res.pending_reward, Uint128::zero());
+
+    let err = simulate(&deps, start + 100, start).unwrap_err();
+    assert_eq!(err, StdError::generic_err("time_seconds cannot be earlier than the current time"));
+}
+
+#[test]
+fn test_total_distributed() {
+    let mut deps = mock_dependencies(&[]);
+
+    let start = mock_env().block.time.seconds();
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(start, start + 100, Uint128::from(1_000_000u128))],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let query_state = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, time_seconds: u64| {
+        let res: StateResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::State {
+                time_seconds: Some(time_seconds),
+                staking_token: None,
+            },
+        ).unwrap()).unwrap();
+        (res.global_reward_index, res.total_distributed)
+    };
+
+    // index stops at the scheduled total long after the schedule ends
+    let max = (Decimal::from_ratio(10_000u128, 1u128), Uint128::from(1_000_000u128));
+    assert_eq!(query_state(&deps, start + 100), max);
+    assert_eq!(query_state(&deps, start + 10_000), max);
+    assert_eq!(query_state(&deps, start + 1_000_000), max);
+
+    // already distributed amount is deducted from what is left to distribute
+    let staking_token = Addr::unchecked("staking0000");
+    let mut state = STATES.load(deps.as_ref().storage, &staking_token).unwrap();
+    state.total_distributed = Uint128::from(900_000u128);
+    STATES.save(deps.as_mut().storage, &staking_token, &state).unwrap();
+    assert_eq!(query_state(&deps, start + 10_000), (Decimal::from_ratio(1_000u128, 1u128), Uint128::from(1_000_000u128)));
+}
+
 #[test]
 fn test_withdraw() {
     let mut deps = mock_dependencies(&[]);
@@ -496,6 +798,7 @@ fn test_withdraw() {
                 Uint128::from(10_000_000u128),
             ),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -518,7 +821,9 @@ fn test_withdraw() {
     let info = mock_info("addr0000", &[]);
 
     let msg = ExecuteMsg::Withdraw {
-        amount: None
+        amount: None,
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -553,13 +858,17 @@ fn test_withdraw() {
 
     // withdraw more than available must error
     let msg = ExecuteMsg::Withdraw {
-        amount: Some(Uint128::from(5_000_001u128))
+        amount: Some(Uint128::from(5_000_001u128)),
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_err());
 
     let msg = ExecuteMsg::Withdraw {
-        amount: Some(Uint128::from(100u128))
+        amount: Some(Uint128::from(100u128)),
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -589,6 +898,7 @@ fn test_withdraw() {
                 QueryMsg::RewardInfo {
                     staker_addr: "addr0001".to_string(),
                     time_seconds: Some(mock_env().block.time.plus_seconds(1000).seconds()),
+                    staking_token: None,
                 },
             )
             .unwrap()
@@ -605,6 +915,219 @@ fn test_withdraw() {
         }
     );
 
+    // withdraw to another recipient, still debited from addr0001
+    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::Withdraw {
+        amount: Some(Uint128::from(200u128)),
+        staking_token: None,
+        recipient: Some("treasury0000".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "treasury0000".to_string(),
+                amount: Uint128::from(200u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let res: RewardInfoResponse = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::RewardInfo {
+            staker_addr: "addr0001".to_string(),
+            time_seconds: None,
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(4_999_600u128));
+}
+
+#[test]
+fn test_claim_fee() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // only owner can set claim fee
+    let update_config = UpdateConfig {
+        distribution_schedule: None,
+        claim_fee: Some(Decimal::percent(5)),
+        fee_collector: Some("collector0000".to_string()),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, update_config);
+    assert_error(res, "Unauthorized");
+
+    // fee above cap
+    let update_config = UpdateConfig {
+        distribution_schedule: None,
+        claim_fee: Some(Decimal::percent(11)),
+        fee_collector: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), update_config);
+    assert_error(res, "claim_fee cannot be greater than 10%");
+
+    let update_config = UpdateConfig {
+        distribution_schedule: None,
+        claim_fee: Some(Decimal::percent(5)),
+        fee_collector: Some("collector0000".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), info, update_config).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.claim_fee, Decimal::percent(5));
+    assert_eq!(config.fee_collector, "collector0000".to_string());
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 100 seconds passed
+    // 1,000,000 rewards distributed, 5% to the fee collector
+    env.block.time = env.block.time.plus_seconds(100);
+
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw {
+        amount: None,
+        staking_token: None,
+        recipient: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "collector0000".to_string(),
+                    amount: Uint128::from(50_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(950_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+        ]
+    );
+}
+
+#[test]
+fn test_native_reward() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "ureward".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+        reward_is_native: true,
+    };
+
+    // only the reward denom can fund a native reward
+    let info = mock_info("addr0000", &[Coin {
+        denom: "uluna".to_string(),
+        amount: Uint128::from(1_000_000u128),
+    }]);
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "only the reward denom can be sent at instantiate");
+
+    let info = mock_info("addr0000", &[Coin {
+        denom: "ureward".to_string(),
+        amount: Uint128::from(1_000_000u128),
+    }]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.reward_token, "ureward".to_string());
+    assert!(config.reward_is_native);
+
+    let update_config = UpdateConfig {
+        distribution_schedule: None,
+        claim_fee: Some(Decimal::percent(5)),
+        fee_collector: Some("collector0000".to_string()),
+    };
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), mock_env(), info, update_config).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 100 seconds passed
+    // 1,000,000 rewards distributed as native coins, 5% to the fee collector
+    env.block.time = env.block.time.plus_seconds(100);
+
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw {
+        amount: None,
+        staking_token: None,
+        recipient: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "collector0000".to_string(),
+                amount: vec![Coin {
+                    denom: "ureward".to_string(),
+                    amount: Uint128::from(50_000u128),
+                }],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "ureward".to_string(),
+                    amount: Uint128::from(950_000u128),
+                }],
+            })),
+        ]
+    );
 }
 
 #[test]
@@ -642,6 +1165,7 @@ fn test_update_config() {
                 Uint128::from(10000000u128),
             ),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -653,6 +1177,8 @@ fn test_update_config() {
             mock_env().block.time.seconds() + 400,
             Uint128::from(10000000u128),
         )]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -676,7 +1202,9 @@ fn test_update_config() {
     let info = mock_info("addr0000", &[]);
 
     let msg = ExecuteMsg::Withdraw {
-        amount: None
+        amount: None,
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
     assert_eq!(
@@ -720,6 +1248,8 @@ fn test_update_config() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -743,7 +1273,9 @@ fn test_update_config() {
     let info = mock_info("addr0000", &[]);
 
     let msg = ExecuteMsg::Withdraw {
-        amount: None
+        amount: None,
+        staking_token: None,
+        recipient: None,
     };
     let _res = execute(deps.as_mut(), env, info, msg).unwrap();
 
@@ -776,6 +1308,8 @@ fn test_update_config() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -811,6 +1345,8 @@ fn test_update_config() {
                 Uint128::from(10000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
 
@@ -882,6 +1418,8 @@ fn test_update_config() {
                 Uint128::from(50000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
 
@@ -952,6 +1490,8 @@ fn test_update_config() {
                 Uint128::from(80000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -1026,6 +1566,8 @@ fn test_update_config() {
                 Uint128::from(60000000u128),
             ),
         ]),
+        claim_fee: None,
+        fee_collector: None,
     };
 
     let info = mock_info("owner0000", &[]);
@@ -1107,6 +1649,7 @@ fn test_query_all_rewards() {
                 Uint128::from(10_000_000u128),
             ),
         ],
+        reward_is_native: false,
     };
 
     let info = mock_info(owner0000, &[]);
@@ -1131,7 +1674,9 @@ fn test_query_all_rewards() {
     // addr4 withdraw reward
     let info = mock_info(addr4, &[]);
     let msg = ExecuteMsg::Withdraw {
-        amount: Some(Uint128::from(100u128))
+        amount: Some(Uint128::from(100u128)),
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
     assert_eq!(
@@ -1146,14 +1691,14 @@ fn test_query_all_rewards() {
         }))]
     );
 
-    // TODO start_after is bugged, it does not affect anything.
     let res: Vec<RewardInfoResponse> = from_binary(&query(
         deps.as_ref(),
         env.clone(),
         QueryMsg::AllRewardInfos {
             start_after: None,
             limit: Some(5),
-            time_seconds: None
+            time_seconds: None,
+            staking_token: None,
         },
     ).unwrap()).unwrap();
 
@@ -1215,7 +1760,9 @@ fn test_query_all_rewards() {
     // addr4 withdraw reward
     let info = mock_info(addr4, &[]);
     let msg = ExecuteMsg::Withdraw {
-        amount: Some(Uint128::from(100u128))
+        amount: Some(Uint128::from(100u128)),
+        staking_token: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
     assert_eq!(
@@ -1237,7 +1784,8 @@ fn test_query_all_rewards() {
         QueryMsg::AllRewardInfos {
             start_after: Some(addr3.to_string()),
             limit: Some(2),
-            time_seconds: Some(env.clone().block.time.seconds())
+            time_seconds: Some(env.clone().block.time.seconds()),
+            staking_token: None,
         },
     ).unwrap()).unwrap();
 
@@ -1264,6 +1812,493 @@ fn test_query_all_rewards() {
             },
         ]
     );
+
+    // two disjoint pages cover all stakers
+    let query_page = |start_after: Option<String>, limit: u32| -> Vec<String> {
+        let res: Vec<RewardInfoResponse> = from_binary(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::AllRewardInfos {
+                start_after,
+                limit: Some(limit),
+                time_seconds: None,
+                staking_token: None,
+            },
+        ).unwrap()).unwrap();
+        res.into_iter().map(|it| it.staker_addr).collect()
+    };
+    let first_page = query_page(None, 5);
+    let second_page = query_page(first_page.last().cloned(), 5);
+    assert_eq!(first_page, vec![addr0, addr1, addr2, addr3, addr4]);
+    assert_eq!(second_page, vec![addr5, addr6, addr7]);
+}
+
+#[test]
+fn test_query_reward_infos() {
+    let mut deps = mock_dependencies(&[]);
+    let all_addresses = ["addr0", "addr1", "addr2", "addr3"];
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+        ],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let env = mock_env();
+    for (i, addr) in all_addresses.iter().enumerate() {
+        // bond 100, 200, 300, 400 tokens
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: addr.to_string(),
+            amount: Uint128::from(100u128 * (i as u128 + 1)),
+            msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        });
+        let info = mock_info("staking0000", &[]);
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    }
+
+    // query three specific stakers in the given order, 100 seconds later
+    let res: Vec<RewardInfoResponse> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RewardInfos {
+            staker_addrs: vec!["addr3".to_string(), "addr0".to_string(), "addr2".to_string()],
+            time_seconds: Some(env.block.time.seconds() + 100),
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+
+    assert_eq!(
+        res,
+        vec![
+            RewardInfoResponse {
+                staker_addr: "addr3".to_string(),
+                reward_info: RewardInfoResponseItem {
+                    staking_token: "staking0000".to_string(),
+                    reward_index: Decimal::from_str("1000").unwrap(),
+                    pending_reward: Uint128::from(400000u128),
+                    bond_amount: Uint128::from(400u128),
+                }
+            },
+            RewardInfoResponse {
+                staker_addr: "addr0".to_string(),
+                reward_info: RewardInfoResponseItem {
+                    staking_token: "staking0000".to_string(),
+                    reward_index: Decimal::from_str("1000").unwrap(),
+                    pending_reward: Uint128::from(100000u128),
+                    bond_amount: Uint128::from(100u128),
+                }
+            },
+            RewardInfoResponse {
+                staker_addr: "addr2".to_string(),
+                reward_info: RewardInfoResponseItem {
+                    staking_token: "staking0000".to_string(),
+                    reward_index: Decimal::from_str("1000").unwrap(),
+                    pending_reward: Uint128::from(300000u128),
+                    bond_amount: Uint128::from(300u128),
+                }
+            },
+        ]
+    );
+
+    // too many stakers
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::RewardInfos {
+            staker_addrs: vec!["addr0".to_string(); 31],
+            time_seconds: None,
+            staking_token: None,
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("staker_addrs cannot exceed 30 addresses")));
+}
+
+#[test]
+fn test_register_staking_token() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+        ],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::RegisterStakingToken {
+        token: "staking0001".to_string(),
+        schedule: vec![
+            (
+                env.block.time.seconds(),
+                env.block.time.seconds() + 100,
+                Uint128::from(2_000_000u128),
+            ),
+        ],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.staking_tokens, vec!["staking0000".to_string(), "staking0001".to_string()]);
+
+    // only registered staking tokens can bond
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0002", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    for staking_token in ["staking0000", "staking0001"] {
+        let info = mock_info(staking_token, &[]);
+        execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+    }
+
+    // each staking token distributes its own schedule
+    env.block.time = env.block.time.plus_seconds(100);
+    for (staking_token, pending_reward) in [("staking0000", 1_000_000u128), ("staking0001", 2_000_000u128)] {
+        let res: RewardInfoResponse = from_binary(&query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0000".to_string(),
+                time_seconds: Some(env.block.time.seconds()),
+                staking_token: Some(staking_token.to_string()),
+            },
+        ).unwrap()).unwrap();
+        assert_eq!(res.reward_info, RewardInfoResponseItem {
+            staking_token: staking_token.to_string(),
+            bond_amount: Uint128::from(100u128),
+            reward_index: Decimal::from_ratio(pending_reward, 100u128),
+            pending_reward: Uint128::from(pending_reward),
+        });
+    }
+
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(100u128),
+        staking_token: Some("staking0001".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking0001".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(100u128),
+            }).unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let res: StateResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::State {
+            time_seconds: None,
+            staking_token: Some("staking0001".to_string()),
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.total_bond_amount, Uint128::zero());
+    assert_eq!(res.global_reward_index, Decimal::from_ratio(2_000_000u128, 100u128));
+
+    // unregistered staking token
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(100u128),
+        staking_token: Some("staking0002".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(ContractError::Std(StdError::generic_err("staking token not registered"))));
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::State {
+            time_seconds: None,
+            staking_token: Some("staking0002".to_string()),
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("staking token not registered")));
+
+    // started schedule cannot be removed
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::RegisterStakingToken {
+        token: "staking0001".to_string(),
+        schedule: vec![],
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(res, Err(ContractError::InvalidDistributionSchedule {}));
+}
+
+#[test]
+fn test_set_paused() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+        ],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let query_pending_reward = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, time_seconds: u64| {
+        let res: RewardInfoResponse = from_binary(&query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0000".to_string(),
+                time_seconds: Some(time_seconds),
+                staking_token: None,
+            },
+        ).unwrap()).unwrap();
+        res.reward_info.pending_reward
+    };
+
+    // pause after 50 seconds
+    env.block.time = env.block.time.plus_seconds(50);
+    let msg = ExecuteMsg::SetPaused { paused: true };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(query_pending_reward(&deps, env.block.time.seconds()), Uint128::from(500_000u128));
+
+    // pending reward does not grow while paused
+    env.block.time = env.block.time.plus_seconds(30);
+    assert_eq!(query_pending_reward(&deps, env.block.time.seconds()), Uint128::from(500_000u128));
+
+    let res: StateResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::State {
+            time_seconds: Some(env.block.time.seconds()),
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert!(res.paused);
+    assert_eq!(res.last_distributed, mock_env().block.time.seconds() + 50);
+
+    // unbond still works while paused
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(50u128),
+        staking_token: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(query_pending_reward(&deps, env.block.time.seconds()), Uint128::from(500_000u128));
+
+    // the paused period is distributed once resumed
+    let info = mock_info("owner0000", &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::SetPaused { paused: false }).unwrap();
+    env.block.time = env.block.time.plus_seconds(20);
+    assert_eq!(query_pending_reward(&deps, env.block.time.seconds()), Uint128::from(1_000_000u128));
+}
+
+#[test]
+fn test_restake() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "staking0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+        ],
+        reward_is_native: false,
+    };
+
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let mut env = mock_env();
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // accrue half of the schedule, then restake it
+    env.block.time = env.block.time.plus_seconds(50);
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Restake {}).unwrap();
+    assert!(res.messages.is_empty());
+
+    let res: RewardInfoResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RewardInfo {
+            staker_addr: "addr0000".to_string(),
+            time_seconds: None,
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.reward_info.bond_amount, Uint128::from(500_100u128));
+    assert_eq!(res.reward_info.pending_reward, Uint128::zero());
+
+    let res: StateResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::State {
+            time_seconds: None,
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.total_bond_amount, Uint128::from(500_100u128));
+
+    // restake is only allowed when rewards are paid in the staking token
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: "reward0000".to_string(),
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![],
+        reward_is_native: false,
+    };
+    let info = mock_info("owner0000", &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Restake {});
+    assert_error(res, "Cannot restake; reward token is not the staking token");
+}
+
+#[test]
+fn test_migrate() {
+    let mut deps = mock_dependencies(&[]);
+
+    // single staking token storage
+    deps.storage.set(
+        b"config",
+        br#"{"owner":"owner0000","reward_token":"reward0000","staking_token":"staking0000","distribution_schedule":[[100,200,"1000000"]],"claim_fee":"0","fee_collector":"owner0000"}"#,
+    );
+    LEGACY_STATE.save(deps.as_mut().storage, &State {
+        last_distributed: 150,
+        total_bond_amount: Uint128::from(100u128),
+        global_reward_index: Decimal::from_ratio(5000u128, 1u128),
+        total_distributed: Uint128::zero(),
+    }).unwrap();
+    let staker_addr = Addr::unchecked("addr0000");
+    LEGACY_REWARD_INFOS.save(deps.as_mut().storage, &staker_addr, &RewardInfo {
+        reward_index: Decimal::from_ratio(5000u128, 1u128),
+        bond_amount: Uint128::from(100u128),
+        pending_reward: Uint128::from(500_000u128),
+    }).unwrap();
+    LEGACY_BOND_CHECKPOINTS.save(deps.as_mut().storage, &staker_addr, &vec![(120u64, Uint128::from(100u128))]).unwrap();
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(LEGACY_STATE.may_load(deps.as_ref().storage).unwrap(), None);
+
+    // total_distributed is seeded with the amount scheduled before last_distributed
+    let staking_token = Addr::unchecked("staking0000");
+    let state = STATES.load(deps.as_ref().storage, &staking_token).unwrap();
+    assert_eq!(state.total_distributed, Uint128::from(500_000u128));
+
+    // migrating again keeps the tracked total
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    assert_eq!(STATES.load(deps.as_ref().storage, &staking_token).unwrap(), state);
+    assert_eq!(LEGACY_REWARD_INFOS.may_load(deps.as_ref().storage, &staker_addr).unwrap(), None);
+
+    let res: ConfigResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.distribution_schedule, vec![(100, 200, Uint128::from(1_000_000u128))]);
+    assert_eq!(res.staking_tokens, vec!["staking0000".to_string()]);
+
+    let res: StateResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::State {
+            time_seconds: Some(200),
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res, StateResponse {
+        staking_token: "staking0000".to_string(),
+        distribution_schedule: vec![(100, 200, Uint128::from(1_000_000u128))],
+        paused: false,
+        last_distributed: 200,
+        total_bond_amount: Uint128::from(100u128),
+        global_reward_index: Decimal::from_ratio(10000u128, 1u128),
+        total_distributed: Uint128::from(1_000_000u128),
+    });
+
+    let res: RewardInfoResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RewardInfo {
+            staker_addr: "addr0000".to_string(),
+            time_seconds: Some(200),
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(1_000_000u128));
+
+    let res: BondAtResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::BondAt {
+            staker_addr: "addr0000".to_string(),
+            time_seconds: 130,
+            staking_token: None,
+        },
+    ).unwrap()).unwrap();
+    assert_eq!(res.bond_amount, Uint128::from(100u128));
 }
 
 #[test]
@@ -1280,6 +2315,7 @@ fn owner() {
         reward_token: "reward0000".to_string(),
         staking_token: "staking0000".to_string(),
         distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+        reward_is_native: false,
     };
 
     let info = mock_info("addr0000", &[]);
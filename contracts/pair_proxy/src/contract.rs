@@ -5,17 +5,21 @@ use std::collections::HashSet;
 use astroport::factory::PairType;
 use astroport::pair::SimulationResponse;
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Fraction,
-    MessageInfo, Response, StdError, StdResult, Uint128,
+    attr, entry_point, from_binary, to_binary, Addr, Api, Binary, Coin,
+    ContractInfoResponse, CosmosMsg, Decimal, Deps, DepsMut, Env, Fraction, MessageInfo,
+    QuerierWrapper, QueryRequest, Response, StdError, StdResult, Uint128, WasmQuery,
 };
 use spectrum::pair_proxy::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, MAX_ASSETS,
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ReverseSimulationResponse, RouteComplexityResponse, RouteResponse, MAX_ASSETS,
 };
 
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::querier::query_token_precision;
-use cw20::Cw20ReceiveMsg;
-use spectrum::adapters::router::Router;
+use cw20::{Cw20ReceiveMsg, Expiration};
+use spectrum::adapters::asset::AssetEx;
+use spectrum::adapters::pair::Pair;
+use spectrum::adapters::router::{Router, SwapOperation};
 
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
@@ -24,26 +28,13 @@ use spectrum::adapters::router::Router;
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 
-    // Validate swap assets
-    let asset_len = msg.asset_infos.len();
-    if asset_len == 0 {
-        return Err(ContractError::MustProvideNAssets {});
-    }
-    if asset_len > MAX_ASSETS {
-        return Err(ContractError::SwapLimitExceeded {});
-    }
-
-    let mut uniq = HashSet::new();
-    for asset_info in msg.asset_infos.iter() {
-        asset_info.check(deps.api)?;
-        if !uniq.insert(asset_info.to_string()) {
-            return Err(ContractError::DuplicatedAssets {});
-        }
-    }
+    let max_hops = MAX_ASSETS - 1;
+    validate_route(deps.api, &msg.asset_infos, max_hops)?;
+    assert_price_params(None, msg.default_max_spread)?;
 
     let offer_precision = if let Some(offer_precision) = msg.offer_precision {
         offer_precision
@@ -56,14 +47,39 @@ pub fn instantiate(
         query_token_precision(&deps.querier, &msg.asset_infos[msg.asset_infos.len() - 1])?
     };
 
+    let pair_asset_infos = vec![
+        msg.asset_infos[0].clone(),
+        msg.asset_infos[msg.asset_infos.len() - 1].clone(),
+    ];
+
+    // the liquidity pair must hold the offer and ask assets of the route
+    let liquidity_pair = if let Some(liquidity_pair) = msg.liquidity_pair {
+        let liquidity_pair = Pair(deps.api.addr_validate(&liquidity_pair)?);
+        let pair_info = liquidity_pair.query_pair_info(&deps.querier)?;
+        if !pair_asset_infos.iter().all(|it| pair_info.asset_infos.contains(it)) {
+            return Err(ContractError::InvalidAsset {});
+        }
+        Some(liquidity_pair)
+    } else {
+        None
+    };
+
+    let fee_recipient = if let Some(fee_recipient) = msg.fee_recipient {
+        Some(deps.api.addr_validate(&fee_recipient)?)
+    } else {
+        None
+    };
+    if let Some(fee) = msg.fee {
+        if fee >= Decimal::one() || fee_recipient.is_none() {
+            return Err(ContractError::InvalidFee {});
+        }
+    }
+
     let config = Config {
         pair_info: PairInfo {
             contract_addr: env.contract.address,
             liquidity_token: Addr::unchecked(""),
-            asset_infos: vec![
-                msg.asset_infos[0].clone(),
-                msg.asset_infos[msg.asset_infos.len() - 1].clone(),
-            ],
+            asset_infos: pair_asset_infos,
             pair_type: PairType::Custom("pair_proxy".to_string()),
         },
         asset_infos: msg.asset_infos,
@@ -71,6 +87,13 @@ pub fn instantiate(
         router_type: msg.router_type,
         offer_precision,
         ask_precision,
+        label: msg.label,
+        default_max_spread: msg.default_max_spread,
+        liquidity_pair,
+        fee: msg.fee,
+        fee_recipient,
+        owner: info.sender,
+        max_hops,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -78,6 +101,39 @@ pub fn instantiate(
     Ok(Response::new())
 }
 
+/// ## Description
+/// Validates the swap route. Returns a [`ContractError`] when the route is empty, has more than
+/// `max_hops` swap operations, contains duplicated assets, or nets to a no-op swap from an asset
+/// back to itself.
+fn validate_route(
+    api: &dyn Api,
+    asset_infos: &[AssetInfo],
+    max_hops: usize,
+) -> Result<(), ContractError> {
+    let asset_len = asset_infos.len();
+    if asset_len == 0 {
+        return Err(ContractError::MustProvideNAssets {});
+    }
+    if asset_len - 1 > max_hops {
+        return Err(ContractError::SwapLimitExceeded {});
+    }
+
+    // head offer must differ from tail ask, otherwise the route is a loop that only pays fees
+    if asset_infos[0] == asset_infos[asset_len - 1] {
+        return Err(ContractError::InvalidRoute {});
+    }
+
+    let mut uniq = HashSet::new();
+    for asset_info in asset_infos.iter() {
+        asset_info.check(api)?;
+        if !uniq.insert(asset_info.to_string()) {
+            return Err(ContractError::DuplicatedAssets {});
+        }
+    }
+
+    Ok(())
+}
+
 /// ## Description
 /// Exposes execute functions available in the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -91,9 +147,11 @@ pub fn execute(
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Swap {
             offer_asset,
+            ask_asset_info,
             belief_price,
             max_spread,
             to,
+            to_msg,
         } => {
             offer_asset.info.check(deps.api)?;
             if !offer_asset.is_native_token() {
@@ -111,12 +169,141 @@ pub fn execute(
                 info.clone(),
                 info.sender,
                 offer_asset,
+                ask_asset_info,
                 belief_price,
                 max_spread,
                 to_addr,
+                to_msg,
             )
         }
+        ExecuteMsg::SplitSwap {
+            offer_asset,
+            routes,
+            belief_price,
+            max_spread,
+            to,
+        } => {
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+            split_swap(
+                deps,
+                env,
+                info.clone(),
+                info.sender,
+                offer_asset,
+                routes,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        ExecuteMsg::SwapBest {
+            offer_asset,
+            routes,
+            belief_price,
+            max_spread,
+            to,
+        } => {
+            offer_asset.info.check(deps.api)?;
+            if !offer_asset.is_native_token() {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+            swap_best(
+                deps,
+                env,
+                info.clone(),
+                info.sender,
+                offer_asset,
+                routes,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        ExecuteMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            receiver,
+        } => provide_liquidity(deps, env, info, assets, slippage_tolerance, receiver),
+        ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            max_hops,
+            fee,
+            fee_recipient,
+            default_max_spread,
+            label,
+        } => update_config(deps, info, max_hops, fee, fee_recipient, default_max_spread, label),
+    }
+}
+
+/// ## Description
+/// Updates the contract config. Only the owner can execute it.
+/// The stored route must stay within the new maximum number of hops.
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_hops: Option<usize>,
+    fee: Option<Decimal>,
+    fee_recipient: Option<String>,
+    default_max_spread: Option<Decimal>,
+    label: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_hops) = max_hops {
+        if max_hops == 0 {
+            return Err(StdError::generic_err("max_hops must be at least 1").into());
+        }
+        if config.asset_infos.len() - 1 > max_hops {
+            return Err(ContractError::SwapLimitExceeded {});
+        }
+        config.max_hops = max_hops;
+    }
+
+    if let Some(fee_recipient) = fee_recipient {
+        config.fee_recipient = Some(deps.api.addr_validate(&fee_recipient)?);
+    }
+
+    if let Some(fee) = fee {
+        config.fee = Some(fee);
+    }
+
+    if let Some(fee) = config.fee {
+        if fee >= Decimal::one() || (!fee.is_zero() && config.fee_recipient.is_none()) {
+            return Err(ContractError::InvalidFee {});
+        }
     }
+
+    if let Some(default_max_spread) = default_max_spread {
+        assert_price_params(None, Some(default_max_spread))?;
+        config.default_max_spread = Some(default_max_spread);
+    }
+
+    if let Some(label) = label {
+        config.label = if label.is_empty() { None } else { Some(label) };
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
 }
 
 /// ## Description
@@ -132,9 +319,11 @@ pub fn receive_cw20(
     let contract_addr = info.sender.clone();
     match from_binary(&cw20_msg.msg) {
         Ok(Cw20HookMsg::Swap {
+            ask_asset_info,
             belief_price,
             max_spread,
             to,
+            to_msg,
         }) => {
             let to_addr = if let Some(to_addr) = to {
                 Some(deps.api.addr_validate(&to_addr)?)
@@ -151,6 +340,62 @@ pub fn receive_cw20(
                     info: AssetInfo::Token { contract_addr },
                     amount: cw20_msg.amount,
                 },
+                ask_asset_info,
+                belief_price,
+                max_spread,
+                to_addr,
+                to_msg,
+            )
+        }
+        Ok(Cw20HookMsg::SplitSwap {
+            routes,
+            belief_price,
+            max_spread,
+            to,
+        }) => {
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            split_swap(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                Asset {
+                    info: AssetInfo::Token { contract_addr },
+                    amount: cw20_msg.amount,
+                },
+                routes,
+                belief_price,
+                max_spread,
+                to_addr,
+            )
+        }
+        Ok(Cw20HookMsg::SwapBest {
+            routes,
+            belief_price,
+            max_spread,
+            to,
+        }) => {
+            let to_addr = if let Some(to_addr) = to {
+                Some(deps.api.addr_validate(&to_addr)?)
+            } else {
+                None
+            };
+
+            swap_best(
+                deps,
+                env,
+                info,
+                Addr::unchecked(cw20_msg.sender),
+                Asset {
+                    info: AssetInfo::Token { contract_addr },
+                    amount: cw20_msg.amount,
+                },
+                routes,
                 belief_price,
                 max_spread,
                 to_addr,
@@ -162,70 +407,599 @@ pub fn receive_cw20(
 
 /// ## Description
 /// Performs an swap operation with the specified parameters. CONTRACT - a user must do token approval.
+/// The offer is checked by [`assert_swap_offer`] before loading the route.
 /// Returns an [`ContractError`] on failure, otherwise returns the [`Response`] with the specified attributes if the operation was successful.
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<Addr>,
+    to_msg: Option<Binary>,
 ) -> Result<Response, ContractError> {
+    assert_swap_offer(&offer_asset, ask_asset_info.as_ref())?;
     offer_asset.assert_sent_native_token_balance(&info)?;
+    assert_price_params(belief_price, max_spread)?;
+
+    let to = to.unwrap_or(sender);
+    if to_msg.is_some() {
+        deps.querier
+            .query::<ContractInfoResponse>(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                contract_addr: to.to_string(),
+            }))
+            .map_err(|_| {
+                StdError::generic_err(format!("receiver with to_msg must be a contract: {}", to))
+            })?;
+    }
 
     let config = CONFIG.load(deps.storage)?;
 
-    let (operations, offer_precision, ask_precision) =
-        if offer_asset.info.equal(&config.asset_infos[0]) {
-            (
-                config
-                    .router_type
-                    .create_swap_operations(&config.asset_infos)?,
-                config.offer_precision,
-                config.ask_precision,
-            )
-        } else if offer_asset
-            .info
-            .equal(&config.asset_infos[config.asset_infos.len() - 1])
-        {
-            let asset_infos: Vec<AssetInfo> = config.asset_infos.into_iter().rev().collect();
-            (
-                config.router_type.create_swap_operations(&asset_infos)?,
-                config.ask_precision,
-                config.offer_precision,
-            )
-        } else {
+    let (asset_infos, inverted) = resolve_route(&config, &offer_asset.info, ask_asset_info.as_ref())
+        .ok_or(ContractError::InvalidAsset {})?;
+    let (offer_precision, ask_precision) =
+        route_precisions(&deps.querier, &config, &asset_infos, inverted)?;
+    let max_spread = resolve_max_spread(&config, max_spread)?;
+    let operations = config.router_type.create_swap_operations(&asset_infos)?;
+
+    let minimum_receive = belief_price.map(|belief_price| {
+        compute_minimum_receive(
+            offer_asset.amount,
+            belief_price,
+            max_spread,
+            offer_precision,
+            ask_precision,
+        )
+    });
+
+    // the hops return here one by one to record their returns, then the route fee is taken
+    // before the minimum receive and the return is delivered to the receiver
+    let ask_asset_info = asset_infos[asset_infos.len() - 1].clone();
+    let prev_balance = ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+    let messages = vec![
+        CallbackMsg::SwapHop {
+            operations,
+            hop: 0,
+            offer_amount: offer_asset.amount,
+            max_spread,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+        CallbackMsg::AssertMinimumReceive {
+            asset_info: ask_asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver: to.to_string(),
+            receiver_msg: to_msg,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    ];
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "swap"))
+}
+
+/// ## Description
+/// Splits the offer asset across the given routes by weight. Every route must go from the offer
+/// asset to the other end of the stored route. The routes return to this contract, then
+/// [`CallbackMsg::AssertMinimumReceive`] checks the total return and sends it to the receiver.
+#[allow(clippy::too_many_arguments)]
+pub fn split_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    offer_asset: Asset,
+    routes: Vec<(Vec<AssetInfo>, Decimal)>,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    assert_swap_offer(&offer_asset, None)?;
+    offer_asset.assert_sent_native_token_balance(&info)?;
+    assert_price_params(belief_price, max_spread)?;
+
+    if routes.is_empty()
+        || routes.iter().any(|(_, weight)| weight.is_zero())
+        || routes.iter().map(|(_, weight)| *weight).sum::<Decimal>() != Decimal::one()
+    {
+        return Err(ContractError::InvalidSplitWeights {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let messages = route_messages(
+        deps.as_ref(),
+        &env,
+        &config,
+        offer_asset,
+        &routes,
+        belief_price,
+        max_spread,
+        to.unwrap_or(sender),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "split_swap")
+        .add_attribute("routes", routes.len().to_string()))
+}
+
+/// ## Description
+/// Simulates the stored route and the candidate routes from the offer asset, then swaps along
+/// the one with the highest return. The stored route is kept on a tie.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_best(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    sender: Addr,
+    offer_asset: Asset,
+    routes: Vec<Vec<AssetInfo>>,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Option<Addr>,
+) -> Result<Response, ContractError> {
+    assert_swap_offer(&offer_asset, None)?;
+    offer_asset.assert_sent_native_token_balance(&info)?;
+    assert_price_params(belief_price, max_spread)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let (asset_infos, _) = resolve_route(&config, &offer_asset.info, None)
+        .ok_or(ContractError::InvalidAsset {})?;
+    let ask_asset_info = asset_infos[asset_infos.len() - 1].clone();
+
+    let mut best: Option<(Vec<AssetInfo>, Uint128)> = None;
+    for route in std::iter::once(asset_infos).chain(routes) {
+        validate_route(deps.api, &route, config.max_hops)?;
+        if !route[0].equal(&offer_asset.info) || !route[route.len() - 1].equal(&ask_asset_info) {
+            return Err(ContractError::InvalidAsset {});
+        }
+
+        let operations = config.router_type.create_swap_operations(&route)?;
+        let return_amount = config
+            .router
+            .simulate(&deps.querier, offer_asset.amount, operations)?
+            .amount;
+        match &best {
+            Some((_, best_amount)) if return_amount <= *best_amount => {}
+            _ => best = Some((route, return_amount)),
+        }
+    }
+    let (route, return_amount) = best.ok_or(ContractError::InvalidAsset {})?;
+
+    let route_attr = route.iter().map(|it| it.to_string()).collect::<Vec<String>>().join(">");
+    let messages = route_messages(
+        deps.as_ref(),
+        &env,
+        &config,
+        offer_asset,
+        &[(route, Decimal::one())],
+        belief_price,
+        max_spread,
+        to.unwrap_or(sender),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "swap_best")
+        .add_attribute("route", route_attr)
+        .add_attribute("return_amount", return_amount))
+}
+
+/// ## Description
+/// Returns the messages swapping the offer asset across the given routes by weight back to this
+/// contract hop by hop, see [`CallbackMsg::SwapHop`], followed by
+/// [`CallbackMsg::AssertMinimumReceive`] to pay out the total return.
+#[allow(clippy::too_many_arguments)]
+fn route_messages(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    offer_asset: Asset,
+    routes: &[(Vec<AssetInfo>, Decimal)],
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    receiver: Addr,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let (asset_infos, inverted) = resolve_route(config, &offer_asset.info, None)
+        .ok_or(ContractError::InvalidAsset {})?;
+    let ask_asset_info = asset_infos[asset_infos.len() - 1].clone();
+    let (offer_precision, ask_precision) =
+        route_precisions(&deps.querier, config, &asset_infos, inverted)?;
+    let max_spread = resolve_max_spread(config, max_spread)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut remaining = offer_asset.amount;
+    for (index, (route, weight)) in routes.iter().enumerate() {
+        validate_route(deps.api, route, config.max_hops)?;
+        if !route[0].equal(&offer_asset.info) || !route[route.len() - 1].equal(&ask_asset_info) {
             return Err(ContractError::InvalidAsset {});
+        }
+
+        // the last route takes the remainder, so no offer asset is left behind
+        let amount = if index == routes.len() - 1 {
+            remaining
+        } else {
+            offer_asset.amount * *weight
         };
+        remaining = remaining.checked_sub(amount)?;
+        if amount.is_zero() {
+            continue;
+        }
 
-    let to = to.unwrap_or(sender);
-    let minimum_receive = match (belief_price, max_spread) {
-        (Some(belief_price), Some(max_spread)) => {
-            let minimum_receive = compute_minimum_receive(
-                offer_asset.amount,
-                belief_price,
+        let operations = config.router_type.create_swap_operations(route)?;
+        messages.push(
+            CallbackMsg::SwapHop {
+                operations,
+                hop: 0,
+                offer_amount: amount,
                 max_spread,
-                offer_precision,
-                ask_precision,
-            );
-            Some(minimum_receive)
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+    }
+
+    let minimum_receive = belief_price.map(|belief_price| {
+        compute_minimum_receive(
+            offer_asset.amount,
+            belief_price,
+            max_spread,
+            offer_precision,
+            ask_precision,
+        )
+    });
+    let prev_balance = ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+    messages.push(
+        CallbackMsg::AssertMinimumReceive {
+            asset_info: ask_asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver: receiver.to_string(),
+            receiver_msg: None,
         }
-        (_, _) => None,
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+
+    Ok(messages)
+}
+
+/// ## Description
+/// Provides liquidity of the offer and ask assets to the liquidity pair on behalf of the sender.
+/// The LP token is sent to the receiver, or the sender if not specified.
+pub fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: [Asset; 2],
+    slippage_tolerance: Option<Decimal>,
+    receiver: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let liquidity_pair = config
+        .liquidity_pair
+        .ok_or(ContractError::LiquidityPairNotSet {})?;
+
+    if assets[0].info.equal(&assets[1].info)
+        || !assets.iter().all(|asset| config.pair_info.asset_infos.contains(&asset.info))
+    {
+        return Err(ContractError::InvalidAsset {});
+    }
+
+    let receiver = if let Some(receiver) = receiver {
+        deps.api.addr_validate(&receiver)?
+    } else {
+        info.sender.clone()
     };
-    let message = config.router.execute_swap_operations_msg(
-        offer_asset,
-        operations,
-        minimum_receive,
-        Some(to),
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut funds: Vec<Coin> = vec![];
+    for asset in assets.iter() {
+        asset.deposit_asset(&info, &env.contract.address, &mut messages)?;
+        if !asset.amount.is_zero() {
+            if asset.is_native_token() {
+                funds.push(Coin {
+                    denom: asset.info.to_string(),
+                    amount: asset.amount,
+                });
+            } else {
+                messages.push(asset.increase_allowance_msg(
+                    liquidity_pair.0.to_string(),
+                    Some(Expiration::AtHeight(env.block.height + 1)),
+                )?);
+            }
+        }
+    }
+
+    messages.push(liquidity_pair.provide_liquidity_msg(
+        assets.to_vec(),
+        slippage_tolerance,
+        Some(receiver.to_string()),
+        funds,
+    )?);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "provide_liquidity")
+        .add_attribute("receiver", receiver))
+}
+
+/// ## Description
+/// Handles the callbacks. Callbacks can only be called by this contract itself.
+pub fn handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        CallbackMsg::AssertMinimumReceive {
+            asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver,
+            receiver_msg,
+        } => assert_minimum_receive(
+            deps,
+            env,
+            asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver,
+            receiver_msg,
+        ),
+        CallbackMsg::SwapHop {
+            operations,
+            hop,
+            offer_amount,
+            max_spread,
+        } => swap_hop(deps, env, operations, hop, offer_amount, max_spread),
+        CallbackMsg::RecordHop {
+            operations,
+            hop,
+            offer_amount,
+            prev_balance,
+            max_spread,
+        } => record_hop(deps, env, operations, hop, offer_amount, prev_balance, max_spread),
+    }
+}
+
+/// ## Description
+/// Swaps the offer amount along the first operation back to this contract, followed by
+/// [`CallbackMsg::RecordHop`] to record the return. The balance before the swap is taken here,
+/// so earlier routes of a split swap are settled.
+fn swap_hop(
+    deps: DepsMut,
+    env: Env,
+    operations: Vec<SwapOperation>,
+    hop: u32,
+    offer_amount: Uint128,
+    max_spread: Decimal,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let operation = operations[0].clone();
+    let prev_balance = operation
+        .get_ask_asset_info()
+        .query_pool(&deps.querier, &env.contract.address)?;
+
+    let messages = vec![
+        config.router.execute_swap_operations_msg(
+            operation.get_offer_asset_info().with_balance(offer_amount),
+            vec![operation],
+            None,
+            Some(env.contract.address.clone()),
+            Some(max_spread),
+        )?,
+        CallbackMsg::RecordHop {
+            operations,
+            hop,
+            offer_amount,
+            prev_balance,
+            max_spread,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    ];
+
+    Ok(Response::new().add_messages(messages))
+}
+
+/// ## Description
+/// Returns hop, offer_denom, ask_denom, offer_amount and return_amount attributes of the first
+/// operation from the balance received since the swap, so indexers can follow the route.
+/// The return is swapped along the remaining operations, or total_return is added after the last one.
+fn record_hop(
+    deps: DepsMut,
+    env: Env,
+    operations: Vec<SwapOperation>,
+    hop: u32,
+    offer_amount: Uint128,
+    prev_balance: Uint128,
+    max_spread: Decimal,
+) -> Result<Response, ContractError> {
+    let operation = &operations[0];
+    let ask_asset_info = operation.get_ask_asset_info();
+    let balance = ask_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+    let return_amount = balance.checked_sub(prev_balance)?;
+
+    let response = Response::new().add_attributes(vec![
+        attr("action", "record_hop"),
+        attr("hop", hop.to_string()),
+        attr("offer_denom", operation.get_offer_asset_info().to_string()),
+        attr("ask_denom", ask_asset_info.to_string()),
+        attr("offer_amount", offer_amount),
+        attr("return_amount", return_amount),
+    ]);
+    if operations.len() == 1 {
+        return Ok(response.add_attribute("total_return", return_amount));
+    }
+
+    let message = CallbackMsg::SwapHop {
+        operations: operations[1..].to_vec(),
+        hop: hop + 1,
+        offer_amount: return_amount,
         max_spread,
-    )?;
+    }
+    .into_cosmos_msg(&env.contract.address)?;
+
+    Ok(response.add_message(message))
+}
+
+/// ## Description
+/// Takes the route fee from the ask asset received by the swap, checks the net amount against
+/// the minimum receive, then sends it to the receiver, executing `receiver_msg` on it if set.
+fn assert_minimum_receive(
+    deps: DepsMut,
+    env: Env,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    minimum_receive: Option<Uint128>,
+    receiver: String,
+    receiver_msg: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let receiver = deps.api.addr_validate(&receiver)?;
+    let balance = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+    let received = balance.checked_sub(prev_balance)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut fee_amount = Uint128::zero();
+    if let Some((fee, fee_recipient)) = config.route_fee() {
+        fee_amount = received * fee;
+        if !fee_amount.is_zero() {
+            messages.push(asset_info.with_balance(fee_amount).transfer_msg(fee_recipient)?);
+        }
+    }
+    let amount = received.checked_sub(fee_amount)?;
+
+    if let Some(minimum_receive) = minimum_receive {
+        if amount < minimum_receive {
+            return Err(ContractError::AssertionMinimumReceive {
+                receive: minimum_receive,
+                amount,
+            });
+        }
+    }
+
+    if !amount.is_zero() {
+        let asset = asset_info.with_balance(amount);
+        messages.push(match receiver_msg {
+            Some(receiver_msg) => asset.send_msg(&receiver, receiver_msg)?,
+            None => asset.transfer_msg(&receiver)?,
+        });
+    }
 
     Ok(Response::new()
-        .add_message(message)
-        .add_attribute("action", "swap"))
+        .add_messages(messages)
+        .add_attribute("action", "assert_minimum_receive")
+        .add_attribute("amount", amount)
+        .add_attribute("fee_amount", fee_amount))
+}
+
+/// ## Description
+/// Rejects a zero offer amount, and an ask asset equal to the offer asset when it is given.
+/// Called by every swap before any storage load.
+fn assert_swap_offer(
+    offer_asset: &Asset,
+    ask_asset_info: Option<&AssetInfo>,
+) -> Result<(), ContractError> {
+    if offer_asset.amount.is_zero() {
+        return Err(ContractError::InvalidFunds {});
+    }
+    if let Some(ask_asset_info) = ask_asset_info {
+        if ask_asset_info.equal(&offer_asset.info) {
+            return Err(ContractError::SameDenom {});
+        }
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Validates max spread and belief price of a swap.
+fn assert_price_params(
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+) -> Result<(), ContractError> {
+    if let Some(max_spread) = max_spread {
+        if max_spread > Decimal::one() {
+            return Err(ContractError::IncorrectMaxSpread {});
+        }
+    }
+    if let Some(belief_price) = belief_price {
+        if belief_price.is_zero() {
+            return Err(ContractError::InvalidBeliefPrice {});
+        }
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Returns the max spread of a swap, falling back to the default max spread of the route.
+fn resolve_max_spread(config: &Config, max_spread: Option<Decimal>) -> Result<Decimal, ContractError> {
+    max_spread
+        .or(config.default_max_spread)
+        .ok_or(ContractError::MaxSpreadRequired {})
+}
+
+/// ## Description
+/// Returns the assets along the stored route from the offer asset to the ask asset, and whether
+/// the route is inverted. The offer asset must be an endpoint of the route. If the ask asset is an
+/// intermediate asset, the route is truncated there. Returns [`None`] if no such route exists.
+fn resolve_route(
+    config: &Config,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: Option<&AssetInfo>,
+) -> Option<(Vec<AssetInfo>, bool)> {
+    let (mut asset_infos, inverted) = if offer_asset_info.equal(&config.asset_infos[0]) {
+        (config.asset_infos.clone(), false)
+    } else if offer_asset_info.equal(&config.asset_infos[config.asset_infos.len() - 1]) {
+        (config.asset_infos.iter().rev().cloned().collect::<Vec<AssetInfo>>(), true)
+    } else {
+        return None;
+    };
+
+    if let Some(ask_asset_info) = ask_asset_info {
+        let index = asset_infos.iter().position(|it| it.equal(ask_asset_info))?;
+        asset_infos.truncate(index + 1);
+    }
+
+    // the route must have at least one swap operation
+    if asset_infos.len() < 2 {
+        return None;
+    }
+
+    Some((asset_infos, inverted))
+}
+
+/// ## Description
+/// Returns the offer and ask precisions of a route resolved by [`resolve_route`].
+fn route_precisions(
+    querier: &QuerierWrapper,
+    config: &Config,
+    asset_infos: &[AssetInfo],
+    inverted: bool,
+) -> StdResult<(u8, u8)> {
+    let (offer_precision, ask_precision) = if inverted {
+        (config.ask_precision, config.offer_precision)
+    } else {
+        (config.offer_precision, config.ask_precision)
+    };
+
+    // truncated route ends at an intermediate asset
+    let ask_precision = if asset_infos.len() < config.asset_infos.len() {
+        query_token_precision(querier, &asset_infos[asset_infos.len() - 1])?
+    } else {
+        ask_precision
+    };
+
+    Ok((offer_precision, ask_precision))
 }
 
 /// Computes minimum return amount from belief price and max spread
@@ -251,55 +1025,193 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Pair {} => to_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::Simulation { offer_asset, .. } => {
-            to_binary(&query_simulation(deps, offer_asset)?)
+        QueryMsg::Simulation { offer_asset, ask_asset_info } => {
+            to_binary(&query_simulation(deps, offer_asset, ask_asset_info)?)
+        }
+        QueryMsg::ReverseSimulation { offer_asset_info, ask_asset } => {
+            to_binary(&query_reverse_simulation(deps, offer_asset_info, ask_asset)?)
+        }
+        QueryMsg::Route { asset_infos } => to_binary(&query_route(deps, asset_infos)?),
+        QueryMsg::RouteByLabel { label } => to_binary(&query_route_by_label(deps, label)?),
+        QueryMsg::RoutesByLabel { label } => to_binary(&query_routes_by_label(deps, label)?),
+        QueryMsg::RouteComplexity { offer, ask } => {
+            to_binary(&query_route_complexity(deps, offer, ask)?)
         }
     }
 }
 
 /// ## Description
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
-pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationResponse> {
+pub fn query_simulation(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
+) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let (operations, _, _) = if offer_asset.info.equal(&config.asset_infos[0]) {
-        (
-            config
-                .router_type
-                .create_swap_operations(&config.asset_infos)?,
-            config.offer_precision,
-            config.ask_precision,
-        )
-    } else if offer_asset
-        .info
-        .equal(&config.asset_infos[config.asset_infos.len() - 1])
-    {
-        let asset_infos: Vec<AssetInfo> = config.asset_infos.into_iter().rev().collect();
-        (
-            config.router_type.create_swap_operations(&asset_infos)?,
-            config.ask_precision,
-            config.offer_precision,
-        )
-    } else {
-        return Err(StdError::generic_err("Invalid asset"));
-    };
+    let (asset_infos, _) = resolve_route(&config, &offer_asset.info, ask_asset_info.as_ref())
+        .ok_or_else(|| StdError::generic_err("Invalid asset"))?;
+    let operations = config.router_type.create_swap_operations(&asset_infos)?;
 
     let simulate_operations_response =
         config
             .router
             .simulate(&deps.querier, offer_asset.amount, operations)?;
 
+    // the route fee is reported as commission and taken from the return
+    let commission_amount = config
+        .route_fee()
+        .map(|(fee, _)| simulate_operations_response.amount * fee)
+        .unwrap_or_default();
+
     Ok(SimulationResponse {
-        return_amount: simulate_operations_response.amount,
+        return_amount: simulate_operations_response.amount.checked_sub(commission_amount)?,
         spread_amount: Uint128::zero(),
-        commission_amount: Uint128::zero(),
+        commission_amount,
+    })
+}
+
+/// ## Description
+/// Returns the offer amount required for the ask amount in a [`ReverseSimulationResponse`] object.
+/// Without an offer asset, the ask asset must be an endpoint of the route and the offer asset is
+/// the other endpoint. With a route fee, the route must return the ask amount before the fee.
+pub fn query_reverse_simulation(
+    deps: Deps,
+    offer_asset_info: Option<AssetInfo>,
+    ask_asset: Asset,
+) -> StdResult<ReverseSimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if ask_asset.amount.is_zero() {
+        return Err(StdError::generic_err("Ask amount must be greater than 0"));
+    }
+
+    let first = &config.asset_infos[0];
+    let last = &config.asset_infos[config.asset_infos.len() - 1];
+    let offer_asset_info = match offer_asset_info {
+        Some(offer_asset_info) => offer_asset_info,
+        None if ask_asset.info.equal(last) => first.clone(),
+        None if ask_asset.info.equal(first) => last.clone(),
+        None => return Err(StdError::generic_err("Invalid asset")),
+    };
+
+    let (asset_infos, inverted) = resolve_route(&config, &offer_asset_info, Some(&ask_asset.info))
+        .ok_or_else(|| StdError::generic_err("Invalid asset"))?;
+    let (offer_precision, ask_precision) =
+        route_precisions(&deps.querier, &config, &asset_infos, inverted)?;
+    let operations = config.router_type.create_swap_operations(&asset_infos)?;
+
+    // the route fee is taken from the return, so the route must return the amount before the fee,
+    // the fee is rounded down when taken so the rounded down amount still covers the ask amount
+    let mut amount = ask_asset.amount;
+    if let Some((fee, _)) = config.route_fee() {
+        amount = amount.multiply_ratio(Decimal::one().atomics(), (Decimal::one() - fee).atomics());
+    }
+
+    // walk the route backwards one hop at a time to keep the intermediate amounts
+    let mut amounts = vec![amount];
+    for operation in operations.into_iter().rev() {
+        amount = config
+            .router
+            .reverse_simulate(&deps.querier, amount, vec![operation])?
+            .amount;
+        amounts.push(amount);
+    }
+    amounts.reverse();
+
+    let dec_adj = Decimal::from_ratio(
+        10u128.pow(ask_precision as u32),
+        10u128.pow(offer_precision as u32),
+    );
+
+    Ok(ReverseSimulationResponse {
+        offer_amount: amount,
+        amounts,
+        belief_price: Decimal::from_ratio(amount, ask_asset.amount) * dec_adj,
+    })
+}
+
+/// ## Description
+/// Returns the list of asset in the route taken by a swap from the offer asset to the ask asset,
+/// oriented from the offer asset, with the label of the stored route in a [`RouteResponse`] object.
+pub fn query_route(deps: Deps, asset_infos: [AssetInfo; 2]) -> StdResult<RouteResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let [offer_asset_info, ask_asset_info] = asset_infos;
+    resolve_route(&config, &offer_asset_info, Some(&ask_asset_info))
+        .map(|(asset_infos, _)| RouteResponse {
+            asset_infos,
+            label: config.label.clone(),
+        })
+        .ok_or_else(|| {
+            StdError::not_found(format!("route from {} to {}", offer_asset_info, ask_asset_info))
+        })
+}
+
+/// ## Description
+/// Returns the list of asset in the stored route if the route has the given label.
+pub fn query_route_by_label(deps: Deps, label: String) -> StdResult<Vec<AssetInfo>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match config.label {
+        Some(route_label) if route_label == label => Ok(config.asset_infos),
+        _ => Err(StdError::not_found(format!("route with label {}", label))),
+    }
+}
+
+/// ## Description
+/// Returns the stored route in a list of [`RouteResponse`] objects if the route has the given label, or an empty list.
+pub fn query_routes_by_label(deps: Deps, label: String) -> StdResult<Vec<RouteResponse>> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(match config.label {
+        Some(route_label) if route_label == label => vec![RouteResponse {
+            asset_infos: config.asset_infos,
+            label: Some(route_label),
+        }],
+        _ => vec![],
     })
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Returns the number of hops and direction of the stored route in a [`RouteComplexityResponse`] object.
+pub fn query_route_complexity(
+    deps: Deps,
+    offer: AssetInfo,
+    ask: AssetInfo,
+) -> StdResult<RouteComplexityResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let first = &config.asset_infos[0];
+    let last = &config.asset_infos[config.asset_infos.len() - 1];
+    let inverted = if offer.equal(first) && ask.equal(last) {
+        false
+    } else if offer.equal(last) && ask.equal(first) {
+        true
+    } else {
+        return Err(StdError::generic_err("Invalid asset"));
+    };
+
+    Ok(RouteComplexityResponse {
+        hops: (config.asset_infos.len() - 1) as u32,
+        inverted,
+    })
+}
+
+/// ## Description
+/// Used for contract migration. Sets the owner if given. Returns a default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(owner) = msg.owner {
+        config.owner = deps.api.addr_validate(&owner)?;
+    }
+    if let Some(default_max_spread) = msg.default_max_spread {
+        assert_price_params(None, Some(default_max_spread))?;
+        config.default_max_spread = Some(default_max_spread);
+    }
+    CONFIG.save(deps.storage, &config)?;
+
     Ok(Response::default())
 }
 
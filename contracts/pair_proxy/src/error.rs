@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -14,6 +14,15 @@ pub enum ContractError {
     #[error("Invalid asset")]
     InvalidAsset {},
 
+    #[error("Invalid route; offer asset cannot be the same as ask asset")]
+    InvalidRoute {},
+
+    #[error("Invalid funds; offer amount must be greater than 0")]
+    InvalidFunds {},
+
+    #[error("Offer asset cannot be the same as ask asset")]
+    SameDenom {},
+
     #[error("Duplicated assets in asset infos")]
     DuplicatedAssets {},
 
@@ -23,6 +32,26 @@ pub enum ContractError {
     #[error("The limit exceeded of swap assets!")]
     SwapLimitExceeded {},
 
+    #[error("Incorrect max spread; must be 0 to 1")]
+    IncorrectMaxSpread {},
+
+    #[error("Belief price must be greater than 0")]
+    InvalidBeliefPrice {},
+
+    #[error("Max spread must be provided when the route has no default max spread")]
+    MaxSpreadRequired {},
+
+    #[error("Liquidity pair is not set")]
+    LiquidityPairNotSet {},
+
+    #[error("Split weights must be greater than 0 and sum to 1")]
+    InvalidSplitWeights {},
+
+    #[error("Invalid fee; must be less than 1 and have a fee recipient")]
+    InvalidFee {},
+
+    #[error("Assertion failed; minimum receive amount: {receive}, swap amount: {amount}")]
+    AssertionMinimumReceive { receive: Uint128, amount: Uint128 },
 }
 
 impl From<OverflowError> for ContractError {
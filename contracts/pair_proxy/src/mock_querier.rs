@@ -1,11 +1,12 @@
-use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
-use cosmwasm_std::testing::{MockApi, MockStorage};
+use std::collections::{HashMap, HashSet};
+use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractInfoResponse, ContractResult, Decimal, Empty, Fraction, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::testing::{MOCK_CONTRACT_ADDR, MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{token_asset, AssetInfo, PairInfo};
 use astroport::generator::{PendingTokenResponse};
+use spectrum::adapters::router::{SimulateSwapOperationsResponse, SwapOperation};
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -24,7 +25,9 @@ const REWARD_TOKEN: &str = "reward";
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
     raw: HashMap<(String, Binary), Binary>,
-    pairs: HashMap<Vec<u8>, PairInfo>
+    pairs: HashMap<Vec<u8>, PairInfo>,
+    prices: HashMap<(String, String), Decimal>,
+    contracts: HashSet<String>,
 }
 
 impl WasmMockQuerier {
@@ -33,10 +36,12 @@ impl WasmMockQuerier {
             balances: HashMap::new(),
             raw: HashMap::new(),
             pairs: HashMap::new(),
+            prices: HashMap::new(),
+            contracts: HashSet::new(),
         }
     }
 
-    pub fn _set_balance(&mut self, token: String, addr: String, amount: Uint128) {
+    pub fn set_balance(&mut self, token: String, addr: String, amount: Uint128) {
         self.balances.insert((token, addr), amount);
     }
 
@@ -44,7 +49,11 @@ impl WasmMockQuerier {
         *self.balances.get(&(token, addr)).unwrap_or(&Uint128::zero())
     }
 
-    pub fn _set_pair(&mut self, asset_infos: &[AssetInfo; 2], pair_info: PairInfo) {
+    pub fn set_contract(&mut self, contract_addr: String) {
+        self.contracts.insert(contract_addr);
+    }
+
+    pub fn set_pair(&mut self, asset_infos: &[AssetInfo; 2], pair_info: PairInfo) {
         self.pairs.insert(pair_key(&asset_infos), pair_info);
     }
 
@@ -52,6 +61,16 @@ impl WasmMockQuerier {
         self.pairs.get(&pair_key(&asset_infos))
     }
 
+    /// sets the amount of ask asset returned per offer asset by the router, 1 if not set
+    pub fn set_price(&mut self, offer: &AssetInfo, ask: &AssetInfo, price: Decimal) {
+        self.prices.insert((offer.to_string(), ask.to_string()), price);
+    }
+
+    fn get_price(&self, operation: &SwapOperation) -> Decimal {
+        let key = (operation.get_offer_asset_info().to_string(), operation.get_ask_asset_info().to_string());
+        self.prices.get(&key).copied().unwrap_or_else(Decimal::one)
+    }
+
     fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         let result = match request {
             QueryRequest::Bank(BankQuery::Balance {
@@ -81,6 +100,14 @@ impl WasmMockQuerier {
                     Ok(Binary::default())
                 }
             },
+            QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr }) => {
+                if !self.contracts.contains(contract_addr) {
+                    return QuerierResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.clone(),
+                    });
+                }
+                to_binary(&ContractInfoResponse::new(1, MOCK_CONTRACT_ADDR))
+            },
             _ => return QuerierResult::Err(SystemError::Unknown {}),
         };
         QuerierResult::Ok(ContractResult::from(result))
@@ -114,7 +141,15 @@ impl WasmMockQuerier {
                 })
             },
             MockQueryMsg::Pair {
-                asset_infos,
+                asset_infos: None,
+            } => {
+                let pair_info = self.pairs.values()
+                    .find(|pair_info| pair_info.contract_addr.as_str() == contract_addr)
+                    .expect("No pair info");
+                to_binary(pair_info)
+            },
+            MockQueryMsg::Pair {
+                asset_infos: Some(asset_infos),
             } => {
                 let pair_info: PairInfo =
                  match self.get_pair(&asset_infos) {
@@ -126,6 +161,30 @@ impl WasmMockQuerier {
 
              to_binary(&pair_info)
              },
+             MockQueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations,
+            } => {
+                let amount = operations.iter()
+                    .fold(offer_amount, |amount, operation| amount * self.get_price(operation));
+                to_binary(&SimulateSwapOperationsResponse {
+                    amount,
+                })
+            },
+            MockQueryMsg::ReverseSimulateSwapOperations {
+                ask_amount,
+                operations,
+            } => {
+                let amount = operations.iter()
+                    .rev()
+                    .fold(ask_amount, |amount, operation| {
+                        let price = self.get_price(operation);
+                        amount.multiply_ratio(price.denominator(), price.numerator())
+                    });
+                to_binary(&SimulateSwapOperationsResponse {
+                    amount,
+                })
+            },
              MockQueryMsg::TokenInfo {
             } => {
                 to_binary(&cw20::TokenInfoResponse {
@@ -154,9 +213,17 @@ enum MockQueryMsg {
         user: String
     },
     Pair {
-        asset_infos: [AssetInfo; 2],
+        asset_infos: Option<[AssetInfo; 2]>,
     },
     TokenInfo {},
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    ReverseSimulateSwapOperations {
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
 }
 
 impl Querier for WasmMockQuerier {
@@ -1,8 +1,11 @@
+use cosmwasm_std::{Addr, Decimal};
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{AssetInfo, PairInfo};
+use spectrum::adapters::pair::Pair;
 use spectrum::adapters::router::{Router, RouterType};
+use spectrum::pair_proxy::MAX_ASSETS;
 
 /// ## Description
 /// This structure describes the main control config of pair.
@@ -14,6 +17,39 @@ pub struct Config {
     pub router_type: RouterType,
     pub offer_precision: u8,
     pub ask_precision: u8,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub default_max_spread: Option<Decimal>,
+    #[serde(default)]
+    pub liquidity_pair: Option<Pair>,
+    #[serde(default)]
+    pub fee: Option<Decimal>,
+    #[serde(default)]
+    pub fee_recipient: Option<Addr>,
+    #[serde(default = "default_owner")]
+    pub owner: Addr,
+    #[serde(default = "default_max_hops")]
+    pub max_hops: usize,
+}
+
+fn default_owner() -> Addr {
+    Addr::unchecked("")
+}
+
+fn default_max_hops() -> usize {
+    MAX_ASSETS - 1
+}
+
+impl Config {
+    /// ## Description
+    /// Returns the fee and its recipient if the route charges a fee.
+    pub fn route_fee(&self) -> Option<(Decimal, &Addr)> {
+        match (self.fee, &self.fee_recipient) {
+            (Some(fee), Some(fee_recipient)) if !fee.is_zero() => Some((fee, fee_recipient)),
+            _ => None,
+        }
+    }
 }
 
 /// ## Description
@@ -1,17 +1,20 @@
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::factory::PairType;
-use astroport::router::{
-    Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
-};
+use astroport::pair::{ExecuteMsg as PairExecuteMsg, SimulationResponse};
+use astroport::router::{ExecuteMsg as RouterExecuteMsg, SwapOperation};
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
+    BankMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration};
+use spectrum::adapters::router::{Router, RouterType, SwapOperation as RouterSwapOperation};
+use spectrum::pair_proxy::{
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReverseSimulationResponse,
+    RouteComplexityResponse, RouteResponse,
 };
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use spectrum::adapters::router::{Router, RouterType};
-use spectrum::pair_proxy::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
 
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use crate::state::{Config, CONFIG};
@@ -21,13 +24,28 @@ const USER_2: &str = "user_2";
 const ROUTER: &str = "router";
 const TOKEN_1: &str = "token_1";
 const TOKEN_2: &str = "token_2";
+const TOKEN_3: &str = "token_3";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const FEE_COLLECTOR: &str = "fee_collector";
+const RECEIVER: &str = "receiver";
 
 #[test]
 fn test() -> Result<(), ContractError> {
     let mut deps = mock_dependencies();
     create(&mut deps)?;
     swap(&mut deps)?;
+    route_complexity(&mut deps)?;
+    route(&mut deps)?;
+    route_by_label(&mut deps)?;
+    reverse_simulation(&mut deps)?;
+    split_swap(&mut deps)?;
+    swap_best(&mut deps)?;
+    swap_truncated()?;
+    default_max_spread()?;
+    route_fee()?;
+    max_hops()?;
+    swap_to_msg()?;
+    provide_liquidity(&mut deps)?;
 
     Ok(())
 }
@@ -40,6 +58,36 @@ fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     }
 }
 
+/// Returns the messages of a swap along the asset infos, starting the first hop and then
+/// asserting the minimum receive of the last asset
+fn swap_messages(
+    asset_infos: &[AssetInfo],
+    offer_amount: u128,
+    max_spread: Decimal,
+    minimum_receive: Option<u128>,
+    receiver: &str,
+    receiver_msg: Option<Binary>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let contract_addr = Addr::unchecked(MOCK_CONTRACT_ADDR);
+    Ok(vec![
+        CallbackMsg::SwapHop {
+            operations: RouterType::AstroSwap.create_swap_operations(asset_infos)?,
+            hop: 0,
+            offer_amount: Uint128::from(offer_amount),
+            max_spread,
+        }
+        .into_cosmos_msg(&contract_addr)?,
+        CallbackMsg::AssertMinimumReceive {
+            asset_info: asset_infos[asset_infos.len() - 1].clone(),
+            prev_balance: Uint128::zero(),
+            minimum_receive: minimum_receive.map(Uint128::from),
+            receiver: receiver.to_string(),
+            receiver_msg,
+        }
+        .into_cosmos_msg(&contract_addr)?,
+    ])
+}
+
 fn create(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -52,6 +100,11 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
     assert_error(res, "Must provide at least 2 assets!");
@@ -69,6 +122,65 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Invalid route; offer asset cannot be the same as ask asset");
+
+    // degenerate route swapping back to the offer asset
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+        ],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Invalid route; offer asset cannot be the same as ask asset");
+
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_3),
+            },
+        ],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
     assert_error(res, "Duplicated assets in asset infos");
@@ -89,6 +201,11 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        label: Some("token1-token2-stablecoin".to_string()),
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg);
     assert!(res.is_ok());
@@ -124,7 +241,14 @@ fn create(
             router: Router(Addr::unchecked(ROUTER)),
             router_type: RouterType::AstroSwap,
             offer_precision: 6,
-            ask_precision: 6
+            ask_precision: 6,
+            label: Some("token1-token2-stablecoin".to_string()),
+            default_max_spread: None,
+            liquidity_pair: None,
+            fee: None,
+            fee_recipient: None,
+            owner: Addr::unchecked(USER_1),
+            max_hops: 49,
         }
     );
 
@@ -134,55 +258,236 @@ fn create(
 fn swap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
     let env = mock_env();
 
+    // max_spread above 1
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(150)),
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Incorrect max spread; must be 0 to 1");
+
+    // zero belief_price
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::zero()),
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Belief price must be greater than 0");
+
+    // zero offer amount
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::zero(),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(ContractError::InvalidFunds {}));
+
+    // ask asset is the offer asset
+    let info = mock_info(USER_1, &[Coin {
+        denom: IBC_TOKEN.to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: Some(AssetInfo::NativeToken {
+            denom: IBC_TOKEN.to_string(),
+        }),
+        belief_price: None,
+        max_spread: Some(Decimal::percent(1)),
+        to: None,
+        to_msg: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_eq!(res, Err(ContractError::SameDenom {}));
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    deps.querier.set_price(&token_1, &token_2, Decimal::percent(200));
+    deps.querier.set_price(&token_2, &ibc, Decimal::percent(50));
+
     let info = mock_info(TOKEN_1, &[]);
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100u128),
         msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
             belief_price: Some(Decimal::percent(100)),
             max_spread: Some(Decimal::percent(1)),
             to: Some(USER_2.to_string()),
+            to_msg: None,
         })?,
     });
 
     let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let operations = vec![
+        RouterSwapOperation::AstroSwap {
+            offer_asset_info: token_1.clone(),
+            ask_asset_info: token_2.clone(),
+        },
+        RouterSwapOperation::AstroSwap {
+            offer_asset_info: token_2.clone(),
+            ask_asset_info: ibc.clone(),
+        },
+    ];
+    let swap_hop = CallbackMsg::SwapHop {
+        operations: operations.clone(),
+        hop: 0,
+        offer_amount: Uint128::from(100u128),
+        max_spread: Decimal::percent(1),
+    };
+    let assert_minimum_receive = CallbackMsg::AssertMinimumReceive {
+        asset_info: ibc.clone(),
+        prev_balance: Uint128::zero(),
+        minimum_receive: Some(Uint128::from(99u128)),
+        receiver: USER_2.to_string(),
+        receiver_msg: None,
+    };
     assert_eq!(
         res.messages
             .into_iter()
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
-        [CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: TOKEN_1.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: ROUTER.to_string(),
+        [
+            swap_hop.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            assert_minimum_receive.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+    assert_eq!(res.attributes, vec![attr("action", "swap")]);
+
+    // only the contract can execute hops
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), ExecuteMsg::Callback(swap_hop.clone()));
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // each hop swaps back to the contract and records the executed return
+    let self_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    deps.querier.set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10u128));
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), ExecuteMsg::Callback(swap_hop))?;
+    let record_hop = CallbackMsg::RecordHop {
+        operations: operations.clone(),
+        hop: 0,
+        offer_amount: Uint128::from(100u128),
+        prev_balance: Uint128::from(10u128),
+        max_spread: Decimal::percent(1),
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            Router(Addr::unchecked(ROUTER)).execute_swap_operations_msg(
+                token_1.with_balance(100u128),
+                operations[..1].to_vec(),
+                None,
+                Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                Some(Decimal::percent(1)),
+            )?,
+            record_hop.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+
+    deps.querier.set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(210u128));
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), ExecuteMsg::Callback(record_hop))?;
+    let swap_hop = CallbackMsg::SwapHop {
+        operations: operations[1..].to_vec(),
+        hop: 1,
+        offer_amount: Uint128::from(200u128),
+        max_spread: Decimal::percent(1),
+    };
+    assert_eq!(
+        res.messages[0].msg,
+        swap_hop.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "record_hop"),
+            attr("hop", "0"),
+            attr("offer_denom", TOKEN_1),
+            attr("ask_denom", TOKEN_2),
+            attr("offer_amount", "100"),
+            attr("return_amount", "200"),
+        ]
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), ExecuteMsg::Callback(swap_hop))?;
+    let record_hop = CallbackMsg::RecordHop {
+        operations: operations[1..].to_vec(),
+        hop: 1,
+        offer_amount: Uint128::from(200u128),
+        prev_balance: Uint128::zero(),
+        max_spread: Decimal::percent(1),
+    };
+    assert_eq!(
+        res.messages[1].msg,
+        record_hop.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?
+    );
+
+    deps.querier.set_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(100u128));
+    let res = execute(deps.as_mut(), env.clone(), self_info.clone(), ExecuteMsg::Callback(record_hop))?;
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "record_hop"),
+            attr("hop", "1"),
+            attr("offer_denom", TOKEN_2),
+            attr("ask_denom", IBC_TOKEN),
+            attr("offer_amount", "200"),
+            attr("return_amount", "100"),
+            attr("total_return", "100"),
+        ]
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), self_info, ExecuteMsg::Callback(assert_minimum_receive))?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_2.to_string(),
+            amount: vec![Coin {
+                denom: IBC_TOKEN.to_string(),
                 amount: Uint128::from(100u128),
-                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
-                    operations: vec![
-                        SwapOperation::AstroSwap {
-                            offer_asset_info: AssetInfo::Token {
-                                contract_addr: Addr::unchecked(TOKEN_1.to_string())
-                            },
-                            ask_asset_info: AssetInfo::Token {
-                                contract_addr: Addr::unchecked(TOKEN_2.to_string())
-                            }
-                        },
-                        SwapOperation::AstroSwap {
-                            offer_asset_info: AssetInfo::Token {
-                                contract_addr: Addr::unchecked(TOKEN_2.to_string())
-                            },
-                            ask_asset_info: AssetInfo::NativeToken {
-                                denom: IBC_TOKEN.to_string(),
-                            },
-                        },
-                    ],
-                    minimum_receive: Some(Uint128::from(99u128)),
-                    to: Some(USER_2.to_string()),
-                    max_spread: Some(Decimal::percent(1))
-                })?,
-            })?,
-            funds: vec![],
-        }),]
+            }],
+        })
     );
+    deps.querier.set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+    deps.querier.set_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
 
     let info = mock_info(
         USER_1,
@@ -198,47 +503,1659 @@ fn swap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             },
             amount: Uint128::from(100u128),
         },
+        ask_asset_info: None,
         belief_price: Some(Decimal::percent(100)),
         max_spread: Some(Decimal::percent(1)),
         to: None,
+        to_msg: None,
     };
 
-    let res = execute(deps.as_mut(), env, info, msg)?;
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let operations = vec![
+        RouterSwapOperation::AstroSwap {
+            offer_asset_info: ibc.clone(),
+            ask_asset_info: token_2.clone(),
+        },
+        RouterSwapOperation::AstroSwap {
+            offer_asset_info: token_2,
+            ask_asset_info: token_1.clone(),
+        },
+    ];
+    let swap_hop = CallbackMsg::SwapHop {
+        operations: operations.clone(),
+        hop: 0,
+        offer_amount: Uint128::from(100u128),
+        max_spread: Decimal::percent(1),
+    };
     assert_eq!(
         res.messages
             .into_iter()
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
-        [CosmosMsg::Wasm(WasmMsg::Execute {
+        [
+            swap_hop.clone().into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::AssertMinimumReceive {
+                asset_info: token_1,
+                prev_balance: Uint128::zero(),
+                minimum_receive: Some(Uint128::from(99u128)),
+                receiver: USER_1.to_string(),
+                receiver_msg: None,
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+
+    // native offer asset is sent along with the first hop
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), ExecuteMsg::Callback(swap_hop))?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: ROUTER.to_string(),
             msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
-                operations: vec![
-                    SwapOperation::AstroSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: IBC_TOKEN.to_string(),
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: Addr::unchecked(TOKEN_2.to_string())
-                        },
-                    },
-                    SwapOperation::AstroSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: Addr::unchecked(TOKEN_2.to_string())
-                        },
-                        ask_asset_info: AssetInfo::Token {
-                            contract_addr: Addr::unchecked(TOKEN_1.to_string())
-                        }
+                operations: vec![SwapOperation::AstroSwap {
+                    offer_asset_info: ibc,
+                    ask_asset_info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked(TOKEN_2.to_string())
                     },
-                ],
-                minimum_receive: Some(Uint128::from(99u128)),
-                to: Some(USER_1.to_string()),
+                }],
+                minimum_receive: None,
+                to: Some(MOCK_CONTRACT_ADDR.to_string()),
                 max_spread: Some(Decimal::percent(1))
             })?,
             funds: vec![Coin {
                 denom: IBC_TOKEN.to_string(),
                 amount: Uint128::from(100u128),
             }],
-        }),]
+        })
+    );
+
+    Ok(())
+}
+
+fn route_complexity(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // two-hop route
+    let msg = QueryMsg::RouteComplexity {
+        offer: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_1),
+        },
+        ask: AssetInfo::NativeToken {
+            denom: IBC_TOKEN.to_string(),
+        },
+    };
+    let res: RouteComplexityResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        RouteComplexityResponse {
+            hops: 2,
+            inverted: false,
+        }
+    );
+
+    let msg = QueryMsg::RouteComplexity {
+        offer: AssetInfo::NativeToken {
+            denom: IBC_TOKEN.to_string(),
+        },
+        ask: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_1),
+        },
+    };
+    let res: RouteComplexityResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        RouteComplexityResponse {
+            hops: 2,
+            inverted: true,
+        }
+    );
+
+    // intermediate asset is not a route endpoint
+    let msg = QueryMsg::RouteComplexity {
+        offer: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_2),
+        },
+        ask: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_1),
+        },
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Invalid asset")));
+
+    // three-hop route
+    let mut deps = mock_dependencies();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_3),
+            },
+            AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+        ],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    let msg = QueryMsg::RouteComplexity {
+        offer: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_1),
+        },
+        ask: AssetInfo::NativeToken {
+            denom: IBC_TOKEN.to_string(),
+        },
+    };
+    let res: RouteComplexityResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(
+        res,
+        RouteComplexityResponse {
+            hops: 3,
+            inverted: false,
+        }
+    );
+
+    Ok(())
+}
+
+fn route(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+
+    let msg = QueryMsg::Route {
+        asset_infos: [token_1.clone(), ibc.clone()],
+    };
+    let res: RouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        RouteResponse {
+            asset_infos: vec![token_1.clone(), token_2.clone(), ibc.clone()],
+            label: Some("token1-token2-stablecoin".to_string()),
+        }
+    );
+
+    // inverted
+    let msg = QueryMsg::Route {
+        asset_infos: [ibc.clone(), token_1.clone()],
+    };
+    let res: RouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.asset_infos, vec![ibc.clone(), token_2.clone(), token_1.clone()]);
+
+    // truncated
+    let msg = QueryMsg::Route {
+        asset_infos: [ibc.clone(), token_2.clone()],
+    };
+    let res: RouteResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.asset_infos, vec![ibc, token_2.clone()]);
+
+    // offer asset is not an endpoint of the route
+    let msg = QueryMsg::Route {
+        asset_infos: [token_2, token_1],
+    };
+    let res = query(deps.as_ref(), env, msg);
+    assert_eq!(res, Err(StdError::not_found("route from token_2 to token_1")));
+
+    Ok(())
+}
+
+fn route_by_label(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = QueryMsg::RouteByLabel {
+        label: "token1-token2-stablecoin".to_string(),
+    };
+    let res: Vec<AssetInfo> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+        ]
+    );
+
+    let msg = QueryMsg::RouteByLabel {
+        label: "unknown".to_string(),
+    };
+    let res = query(deps.as_ref(), env.clone(), msg.clone());
+    assert_eq!(res, Err(StdError::not_found("route with label unknown")));
+
+    // labels need not be unique across routes
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let luna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let mut deps_2 = mock_dependencies();
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), luna.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: Some("token1-token2-stablecoin".to_string()),
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    instantiate(deps_2.as_mut(), env.clone(), mock_info(USER_1, &[]), instantiate_msg)?;
+    let by_label = QueryMsg::RoutesByLabel {
+        label: "token1-token2-stablecoin".to_string(),
+    };
+    let res: Vec<RouteResponse> = from_binary(&query(deps.as_ref(), env.clone(), by_label.clone())?)?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].asset_infos.len(), 3);
+    let res: Vec<RouteResponse> = from_binary(&query(deps_2.as_ref(), env.clone(), by_label)?)?;
+    assert_eq!(
+        res,
+        vec![RouteResponse {
+            asset_infos: vec![token_1, luna],
+            label: Some("token1-token2-stablecoin".to_string()),
+        }]
+    );
+    let res: Vec<RouteResponse> = from_binary(&query(
+        deps_2.as_ref(),
+        env.clone(),
+        QueryMsg::RoutesByLabel {
+            label: "unknown".to_string(),
+        },
+    )?)?;
+    assert!(res.is_empty());
+
+    // only owner can relabel the route
+    let update_msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: None,
+        fee_recipient: None,
+        default_max_spread: None,
+        label: Some("unknown".to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), update_msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), update_msg)?;
+    let res: Vec<AssetInfo> = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+    assert_eq!(res.len(), 3);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RouteByLabel {
+            label: "token1-token2-stablecoin".to_string(),
+        },
+    );
+    assert_eq!(res, Err(StdError::not_found("route with label token1-token2-stablecoin")));
+
+    // empty label removes the label
+    let update_msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: None,
+        fee_recipient: None,
+        default_max_spread: None,
+        label: Some("".to_string()),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), update_msg)?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.label, None);
+    let res = query(deps.as_ref(), env, msg);
+    assert_eq!(res, Err(StdError::not_found("route with label unknown")));
+
+    Ok(())
+}
+
+fn reverse_simulation(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    deps.querier.set_price(&token_1, &token_2, Decimal::percent(150));
+    deps.querier.set_price(&token_2, &ibc, Decimal::percent(40));
+
+    // zero ask amount
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: Asset {
+            info: ibc.clone(),
+            amount: Uint128::zero(),
+        },
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Ask amount must be greater than 0")));
+
+    // intermediate ask asset requires the offer asset
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: Asset {
+            info: token_2.clone(),
+            amount: Uint128::from(300u128),
+        },
+    };
+    let res = query(deps.as_ref(), env.clone(), msg);
+    assert_eq!(res, Err(StdError::generic_err("Invalid asset")));
+
+    // two-hop route
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: Asset {
+            info: ibc.clone(),
+            amount: Uint128::from(600000u128),
+        },
+    };
+    let res: ReverseSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::from(1000000u128),
+            amounts: vec![
+                Uint128::from(1000000u128),
+                Uint128::from(1500000u128),
+                Uint128::from(600000u128),
+            ],
+            belief_price: Decimal::from_ratio(5u128, 3u128),
+        }
+    );
+
+    // truncated route
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: Some(token_1.clone()),
+        ask_asset: Asset {
+            info: token_2,
+            amount: Uint128::from(300u128),
+        },
+    };
+    let res: ReverseSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.offer_amount, Uint128::from(200u128));
+    assert_eq!(res.amounts, vec![Uint128::from(200u128), Uint128::from(300u128)]);
+
+    // forward simulation of the reverse simulation returns the ask amount within rounding
+    let ask_amount = Uint128::from(1000u128);
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: Asset {
+            info: ibc,
+            amount: ask_amount,
+        },
+    };
+    let res: ReverseSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let msg = QueryMsg::Simulation {
+        offer_asset: Asset {
+            info: token_1,
+            amount: res.offer_amount,
+        },
+        ask_asset_info: None,
+    };
+    let res: SimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert!(res.return_amount <= ask_amount);
+    assert!(ask_amount - res.return_amount <= Uint128::from(1u128));
+
+    Ok(())
+}
+
+fn split_swap(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let token_3 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_3),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let route_a = vec![token_1.clone(), token_2.clone(), ibc.clone()];
+    let route_b = vec![token_1.clone(), token_3.clone(), ibc.clone()];
+
+    // weights do not sum to 1
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SplitSwap {
+            routes: vec![
+                (route_a.clone(), Decimal::percent(60)),
+                (route_b.clone(), Decimal::percent(60)),
+            ],
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Split weights must be greater than 0 and sum to 1");
+
+    // zero offer amount
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::zero(),
+        msg: to_binary(&Cw20HookMsg::SplitSwap {
+            routes: vec![
+                (route_a.clone(), Decimal::percent(60)),
+                (route_b.clone(), Decimal::percent(40)),
+            ],
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::InvalidFunds {}));
+
+    // route does not end at the ask asset
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SplitSwap {
+            routes: vec![
+                (route_a.clone(), Decimal::percent(60)),
+                (vec![token_1.clone(), token_3.clone()], Decimal::percent(40)),
+            ],
+            belief_price: None,
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid asset");
+
+    // 60/40 split, token_1 -> token_2 at 1.5, token_2 -> ibc at 0.4, token_3 route at 1
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SplitSwap {
+            routes: vec![
+                (route_a, Decimal::percent(60)),
+                (route_b, Decimal::percent(40)),
+            ],
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some(USER_2.to_string()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "split_swap"), attr("routes", "2")]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CallbackMsg::SwapHop {
+                operations: vec![
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: token_1.clone(),
+                        ask_asset_info: token_2,
+                    },
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: Addr::unchecked(TOKEN_2),
+                        },
+                        ask_asset_info: ibc.clone(),
+                    },
+                ],
+                hop: 0,
+                offer_amount: Uint128::from(600u128),
+                max_spread: Decimal::percent(1),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::SwapHop {
+                operations: vec![
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: token_1,
+                        ask_asset_info: token_3.clone(),
+                    },
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: token_3,
+                        ask_asset_info: ibc.clone(),
+                    },
+                ],
+                hop: 0,
+                offer_amount: Uint128::from(400u128),
+                max_spread: Decimal::percent(1),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::AssertMinimumReceive {
+                asset_info: ibc.clone(),
+                prev_balance: Uint128::zero(),
+                minimum_receive: Some(Uint128::from(990u128)),
+                receiver: USER_2.to_string(),
+                receiver_msg: None,
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+
+    // callback from other address
+    let callback = ExecuteMsg::Callback(CallbackMsg::AssertMinimumReceive {
+        asset_info: ibc,
+        prev_balance: Uint128::zero(),
+        minimum_receive: Some(Uint128::from(990u128)),
+        receiver: USER_2.to_string(),
+        receiver_msg: None,
+    });
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, callback.clone());
+    assert_error(res, "Unauthorized");
+
+    // total return of both routes is below the minimum
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(985u128),
+    );
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), callback.clone());
+    assert_error(res, "Assertion failed; minimum receive amount: 990, swap amount: 985");
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(995u128),
+    );
+    let res = execute(deps.as_mut(), env, info, callback)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_2.to_string(),
+            amount: vec![Coin {
+                denom: IBC_TOKEN.to_string(),
+                amount: Uint128::from(995u128),
+            }],
+        })]
+    );
+
+    Ok(())
+}
+
+fn swap_best(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_3 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_3),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+
+    // stored route returns 0.6, bridged route 1.0, direct route 0.5
+    deps.querier.set_price(&token_1, &token_3, Decimal::one());
+    deps.querier.set_price(&token_3, &ibc, Decimal::one());
+    deps.querier.set_price(&token_1, &ibc, Decimal::percent(50));
+    let route_bridged = vec![token_1.clone(), token_3.clone(), ibc.clone()];
+    let route_direct = vec![token_1.clone(), ibc.clone()];
+
+    // candidate route does not end at the ask asset
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SwapBest {
+            routes: vec![vec![token_1.clone(), token_3.clone()]],
+            belief_price: None,
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid asset");
+
+    // zero offer amount
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::zero(),
+        msg: to_binary(&Cw20HookMsg::SwapBest {
+            routes: vec![route_direct.clone()],
+            belief_price: None,
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(res, Err(ContractError::InvalidFunds {}));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SwapBest {
+            routes: vec![route_direct, route_bridged],
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some(USER_2.to_string()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "swap_best"),
+            attr("route", format!("{}>{}>{}", TOKEN_1, TOKEN_3, IBC_TOKEN)),
+            attr("return_amount", "1000"),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CallbackMsg::SwapHop {
+                operations: vec![
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: token_1,
+                        ask_asset_info: token_3.clone(),
+                    },
+                    RouterSwapOperation::AstroSwap {
+                        offer_asset_info: token_3,
+                        ask_asset_info: ibc.clone(),
+                    },
+                ],
+                hop: 0,
+                offer_amount: Uint128::from(1000u128),
+                max_spread: Decimal::percent(1),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::AssertMinimumReceive {
+                asset_info: ibc,
+                prev_balance: Uint128::from(995u128),
+                minimum_receive: Some(Uint128::from(990u128)),
+                receiver: USER_2.to_string(),
+                receiver_msg: None,
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+
+    Ok(())
+}
+
+fn swap_truncated() -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let token_3 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_3),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+
+    // three-hop route
+    let mut deps = mock_dependencies();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_3),
+            },
+            AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+        ],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: Some(Decimal::percent(1)),
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // ask asset is not in the route
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: Some(AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            }),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid asset");
+
+    // ask asset is the offer asset
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: Some(AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            }),
+            belief_price: None,
+            max_spread: None,
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Offer asset cannot be the same as ask asset");
+
+    // stop at the second asset after the offer asset
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: Some(AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_3),
+            }),
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(
+            &[token_1.clone(), token_2.clone(), token_3.clone()],
+            100,
+            Decimal::percent(1),
+            Some(99),
+            USER_1,
+            None,
+        )?
+    );
+
+    // inverted route stops at the first intermediate asset, with the default max spread
+    let info = mock_info(
+        USER_1,
+        &[Coin {
+            denom: IBC_TOKEN.to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let msg = ExecuteMsg::Swap {
+        offer_asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+            amount: Uint128::from(100u128),
+        },
+        ask_asset_info: Some(AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_3),
+        }),
+        belief_price: None,
+        max_spread: None,
+        to: None,
+        to_msg: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(&[ibc, token_3], 100, Decimal::percent(1), None, USER_1, None)?
+    );
+
+    Ok(())
+}
+
+fn default_max_spread() -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let mut instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), ibc.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: Some(Decimal::percent(150)),
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    let info = mock_info(USER_1, &[]);
+
+    // default max spread above 1
+    let mut deps = mock_dependencies();
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone());
+    assert_error(res, "Incorrect max spread; must be 0 to 1");
+
+    // no default max spread
+    instantiate_msg.default_max_spread = None;
+    instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone())?;
+    let swap_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: None,
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let token_info = mock_info(TOKEN_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), token_info.clone(), swap_msg.clone());
+    assert_error(res, "Max spread must be provided when the route has no default max spread");
+
+    // owner sets the default max spread
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: None,
+        fee_recipient: None,
+        default_max_spread: Some(Decimal::percent(150)),
+        label: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Incorrect max spread; must be 0 to 1");
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: None,
+        fee_recipient: None,
+        default_max_spread: Some(Decimal::percent(2)),
+        label: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.default_max_spread, Some(Decimal::percent(2)));
+    execute(deps.as_mut(), env.clone(), token_info.clone(), swap_msg.clone())?;
+
+    // default max spread set on migrate for routes instantiated without it
+    let mut deps = mock_dependencies();
+    instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone())?;
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {
+        owner: None,
+        default_max_spread: Some(Decimal::percent(150)),
+    });
+    assert_error(res, "Incorrect max spread; must be 0 to 1");
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {
+        owner: None,
+        default_max_spread: Some(Decimal::percent(2)),
+    })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.default_max_spread, Some(Decimal::percent(2)));
+    execute(deps.as_mut(), env.clone(), token_info.clone(), swap_msg.clone())?;
+
+    // swap without max spread uses the default of the route
+    let mut deps = mock_dependencies();
+    instantiate_msg.default_max_spread = Some(Decimal::percent(1));
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+    let res = execute(deps.as_mut(), env.clone(), token_info.clone(), swap_msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(
+            &[token_1.clone(), ibc.clone()],
+            1000,
+            Decimal::percent(1),
+            Some(990),
+            USER_1,
+            None,
+        )?
+    );
+
+    // caller max spread takes precedence over the default
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(5)),
+            to: None,
+            to_msg: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), token_info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(
+            &[token_1.clone(), ibc.clone()],
+            1000,
+            Decimal::percent(5),
+            Some(950),
+            USER_1,
+            None,
+        )?
+    );
+
+    // split swap without max spread is rejected below the default
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SplitSwap {
+            routes: vec![(vec![token_1, ibc.clone()], Decimal::one())],
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: None,
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), token_info, msg)?;
+    let callback = CallbackMsg::AssertMinimumReceive {
+        asset_info: ibc,
+        prev_balance: Uint128::zero(),
+        minimum_receive: Some(Uint128::from(990u128)),
+        receiver: USER_1.to_string(),
+        receiver_msg: None,
+    };
+    assert_eq!(
+        res.messages[1].msg,
+        callback.into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?
+    );
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(985u128),
+    );
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Callback(callback));
+    assert_error(res, "Assertion failed; minimum receive amount: 990, swap amount: 985");
+
+    Ok(())
+}
+
+fn route_fee() -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let mut instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), ibc.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: None,
+        fee: Some(Decimal::one()),
+        fee_recipient: Some(FEE_COLLECTOR.to_string()),
+    };
+    let info = mock_info(USER_1, &[]);
+
+    // fee of the whole return
+    let mut deps = mock_dependencies();
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone());
+    assert_error(res, "Invalid fee; must be less than 1 and have a fee recipient");
+
+    // fee without recipient
+    instantiate_msg.fee = Some(Decimal::permille(1));
+    instantiate_msg.fee_recipient = None;
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone());
+    assert_error(res, "Invalid fee; must be less than 1 and have a fee recipient");
+
+    instantiate_msg.fee_recipient = Some(FEE_COLLECTOR.to_string());
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // simulation reports the fee as commission
+    let msg = QueryMsg::Simulation {
+        offer_asset: token_1.with_balance(1000000u128),
+        ask_asset_info: None,
+    };
+    let res: SimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        SimulationResponse {
+            return_amount: Uint128::from(999000u128),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::from(1000u128),
+        }
+    );
+
+    // reverse simulation requires the return before the fee
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: ibc.with_balance(999000u128),
+    };
+    let res: ReverseSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::from(1000000u128),
+            amounts: vec![Uint128::from(1000000u128), Uint128::from(1000000u128)],
+            belief_price: Decimal::from_ratio(1000000u128, 999000u128),
+        }
+    );
+
+    // the return before the fee is rounded down, the fee of 1001 is 1 so the net return is 1000
+    let msg = QueryMsg::ReverseSimulation {
+        offer_asset_info: None,
+        ask_asset: ibc.with_balance(1000u128),
+    };
+    let res: ReverseSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.offer_amount, Uint128::from(1001u128));
+
+    // the return comes back to the contract, the minimum receive is checked by the callback
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some(USER_2.to_string()),
+            to_msg: None,
+        })?,
+    });
+    let token_info = mock_info(TOKEN_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), token_info, msg)?;
+    let callback = CallbackMsg::AssertMinimumReceive {
+        asset_info: ibc.clone(),
+        prev_balance: Uint128::zero(),
+        minimum_receive: Some(Uint128::from(990000u128)),
+        receiver: USER_2.to_string(),
+        receiver_msg: None,
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(
+            &[token_1.clone(), ibc],
+            1000000,
+            Decimal::percent(1),
+            Some(990000),
+            USER_2,
+            None,
+        )?
+    );
+
+    // return is above the minimum, but the net amount after fee is not
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(990500u128),
+    );
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Callback(callback.clone()),
+    );
+    assert_error(res, "Assertion failed; minimum receive amount: 990000, swap amount: 989510");
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000000u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Callback(callback))?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: FEE_COLLECTOR.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(1000u128),
+                }],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_2.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(999000u128),
+                }],
+            }),
+        ]
+    );
+
+    // only owner can update the fee
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: Some(Decimal::percent(1)),
+        fee_recipient: Some(USER_2.to_string()),
+        default_max_spread: None,
+        label: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    // fee of the whole return
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: Some(Decimal::one()),
+        fee_recipient: None,
+        default_max_spread: None,
+        label: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid fee; must be less than 1 and have a fee recipient");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: Some(Decimal::percent(1)),
+        fee_recipient: Some(USER_2.to_string()),
+        default_max_spread: None,
+        label: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(config.route_fee(), Some((Decimal::percent(1), &Addr::unchecked(USER_2))));
+
+    // zero fee disables the fee
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: Some(Decimal::zero()),
+        fee_recipient: None,
+        default_max_spread: None,
+        label: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    let msg = QueryMsg::Simulation {
+        offer_asset: token_1.with_balance(1000000u128),
+        ask_asset_info: None,
+    };
+    let res: SimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.commission_amount, Uint128::zero());
+
+    Ok(())
+}
+
+fn max_hops() -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let token_3 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_3),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let luna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    // three-hop route
+    let mut deps = mock_dependencies();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), token_2.clone(), token_3.clone(), ibc.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: Some(Decimal::percent(1)),
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // config stored before the owner was added has no owner until migrate sets it
+    let mut config = CONFIG.load(deps.as_ref().storage)?;
+    config.owner = Addr::unchecked("");
+    CONFIG.save(deps.as_mut().storage, &config)?;
+    let msg = ExecuteMsg::UpdateConfig { max_hops: Some(3), fee: None, fee_recipient: None, default_max_spread: None, label: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg);
+    assert_error(res, "Unauthorized");
+    migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: Some(USER_1.to_string()), default_max_spread: None })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked(USER_1));
+    migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None, default_max_spread: None })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked(USER_1));
+
+    // only owner can update
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::UpdateConfig { max_hops: Some(3), fee: None, fee_recipient: None, default_max_spread: None, label: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateConfig { max_hops: Some(0), fee: None, fee_recipient: None, default_max_spread: None, label: None };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "max_hops must be at least 1");
+
+    // fee without recipient
+    let msg = ExecuteMsg::UpdateConfig {
+        max_hops: None,
+        fee: Some(Decimal::permille(1)),
+        fee_recipient: None,
+        default_max_spread: None,
+        label: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid fee; must be less than 1 and have a fee recipient");
+
+    // stored route exceeds the limit
+    let msg = ExecuteMsg::UpdateConfig { max_hops: Some(2), fee: None, fee_recipient: None, default_max_spread: None, label: None };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "The limit exceeded of swap assets!");
+
+    let msg = ExecuteMsg::UpdateConfig { max_hops: Some(3), fee: None, fee_recipient: None, default_max_spread: None, label: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_config")]);
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.max_hops, 3);
+
+    // candidate route of max_hops + 1
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SwapBest {
+            routes: vec![vec![token_1.clone(), luna.clone(), token_2.clone(), token_3, ibc.clone()]],
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "The limit exceeded of swap assets!");
+
+    // candidate route of max_hops
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::SwapBest {
+            routes: vec![vec![token_1, luna, token_2, ibc]],
+            belief_price: None,
+            max_spread: None,
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(res.attributes[0], attr("action", "swap_best"));
+
+    Ok(())
+}
+
+fn swap_to_msg() -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+
+    let mut deps = mock_dependencies();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), token_2.clone(), ibc.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: Some(Decimal::percent(1)),
+        liquidity_pair: None,
+        fee: None,
+        fee_recipient: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    let to_msg = to_binary(&"deposit")?;
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread: None,
+            to: Some(RECEIVER.to_string()),
+            to_msg: Some(to_msg.clone()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "receiver with to_msg must be a contract: receiver");
+
+    // the return comes back here to be forwarded
+    deps.querier.set_contract(RECEIVER.to_string());
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        swap_messages(
+            &[token_1, token_2, ibc.clone()],
+            1000,
+            Decimal::percent(1),
+            None,
+            RECEIVER,
+            Some(to_msg.clone()),
+        )?
+    );
+
+    // the ask asset is delivered with the message instead of a bank send
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000u128),
+    );
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumReceive {
+        asset_info: ibc,
+        prev_balance: Uint128::zero(),
+        minimum_receive: None,
+        receiver: RECEIVER.to_string(),
+        receiver_msg: Some(to_msg.clone()),
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: RECEIVER.to_string(),
+            msg: to_msg,
+            funds: vec![Coin {
+                denom: IBC_TOKEN.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+        }),]
+    );
+
+    Ok(())
+}
+
+fn provide_liquidity(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    let assets = [token_1.with_balance(100u128), ibc.with_balance(100u128)];
+
+    // the route created without a liquidity pair
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: assets.clone(),
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Liquidity pair is not set");
+
+    let mut deps = mock_dependencies();
+    deps.querier.set_pair(
+        &[token_1.clone(), ibc.clone()],
+        PairInfo {
+            asset_infos: vec![token_1.clone(), ibc.clone()],
+            contract_addr: Addr::unchecked("pair0001"),
+            liquidity_token: Addr::unchecked("liquidity0001"),
+            pair_type: PairType::Xyk {},
+        },
+    );
+    deps.querier.set_pair(
+        &[token_2.clone(), ibc.clone()],
+        PairInfo {
+            asset_infos: vec![token_2.clone(), ibc.clone()],
+            contract_addr: Addr::unchecked("pair0002"),
+            liquidity_token: Addr::unchecked("liquidity0002"),
+            pair_type: PairType::Xyk {},
+        },
+    );
+    let mut instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), token_2.clone(), ibc.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        label: None,
+        default_max_spread: None,
+        liquidity_pair: Some("pair0002".to_string()),
+        fee: None,
+        fee_recipient: None,
+    };
+
+    // liquidity pair does not hold the offer asset
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg.clone());
+    assert_error(res, "Invalid asset");
+
+    instantiate_msg.liquidity_pair = Some("pair0001".to_string());
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // asset not in the pair
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [token_2.with_balance(100u128), ibc.with_balance(100u128)],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid asset");
+
+    // duplicated asset
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: [ibc.with_balance(100u128), ibc.with_balance(100u128)],
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid asset");
+
+    // native asset not sent
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: assets.clone(),
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Native token balance mismatch between the argument and the transferred");
+
+    // balanced deposit
+    let info = mock_info(
+        USER_1,
+        &[Coin {
+            denom: IBC_TOKEN.to_string(),
+            amount: Uint128::from(100u128),
+        }],
+    );
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: assets.clone(),
+        slippage_tolerance: None,
+        receiver: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: USER_1.to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(100u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "pair0001".to_string(),
+                    amount: Uint128::from(100u128),
+                    expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0001".to_string(),
+                msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                    assets: assets.to_vec(),
+                    slippage_tolerance: None,
+                    auto_stake: None,
+                    receiver: Some(USER_1.to_string()),
+                })?,
+                funds: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+            }),
+        ]
+    );
+
+    // imbalanced deposit to another receiver
+    let assets = [ibc.with_balance(50u128), token_1.with_balance(300u128)];
+    let info = mock_info(
+        USER_1,
+        &[Coin {
+            denom: IBC_TOKEN.to_string(),
+            amount: Uint128::from(50u128),
+        }],
+    );
+    let msg = ExecuteMsg::ProvideLiquidity {
+        assets: assets.clone(),
+        slippage_tolerance: Some(Decimal::percent(1)),
+        receiver: Some(USER_2.to_string()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: USER_1.to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(300u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "pair0001".to_string(),
+                    amount: Uint128::from(300u128),
+                    expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "pair0001".to_string(),
+                msg: to_binary(&PairExecuteMsg::ProvideLiquidity {
+                    assets: assets.to_vec(),
+                    slippage_tolerance: Some(Decimal::percent(1)),
+                    auto_stake: None,
+                    receiver: Some(USER_2.to_string()),
+                })?,
+                funds: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(50u128),
+                }],
+            }),
+        ]
     );
 
     Ok(())
@@ -73,6 +73,11 @@ pub enum ExecuteMsg {
         /// the LP token contract address
         lp_tokens: Vec<String>,
     },
+    /// Stake native LP tokens attached to the message in the Generator
+    Deposit {
+        /// The account that receives ownership of the staked tokens, the sender if not set
+        recipient: Option<String>,
+    },
     /// Withdraw LP tokens from the Generator
     Withdraw {
         /// The address of the LP token to withdraw
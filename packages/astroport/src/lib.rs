@@ -32,11 +32,16 @@ mod uints {
 }
 
 mod decimal_checked_ops {
-    use cosmwasm_std::{Decimal, Fraction, OverflowError, Uint128, Uint256};
+    use cosmwasm_std::{
+        Decimal, DivideByZeroError, Fraction, OverflowError, StdError, StdResult, Uint128, Uint256,
+    };
     use std::convert::TryInto;
     pub trait DecimalCheckedOps {
         fn checked_add(self, other: Decimal) -> Result<Decimal, OverflowError>;
         fn checked_mul_uint128(self, other: Uint128) -> Result<Uint128, OverflowError>;
+        fn checked_sub(self, other: Decimal) -> Result<Decimal, OverflowError>;
+        /// Divides `other` by this decimal, a zero decimal is a divide by zero error
+        fn checked_div_uint128(self, other: Uint128) -> StdResult<Uint128>;
     }
 
     impl DecimalCheckedOps for Decimal {
@@ -61,6 +66,29 @@ mod decimal_checked_ops {
                 Ok(multiply_ratio.try_into().unwrap())
             }
         }
+        fn checked_sub(self, other: Decimal) -> Result<Decimal, OverflowError> {
+            self.numerator()
+                .checked_sub(other.numerator())
+                .map(|_| self - other)
+        }
+        fn checked_div_uint128(self, other: Uint128) -> StdResult<Uint128> {
+            if self.is_zero() {
+                return Err(DivideByZeroError::new(other).into());
+            }
+            if other.is_zero() {
+                return Ok(Uint128::zero());
+            }
+            let divide_ratio =
+                other.full_mul(self.denominator()) / Uint256::from(self.numerator());
+            if divide_ratio > Uint256::from(Uint128::MAX) {
+                Err(StdError::generic_err(format!(
+                    "Division overflow: {} / {}",
+                    other, self
+                )))
+            } else {
+                Ok(divide_ratio.try_into().unwrap())
+            }
+        }
     }
 }
 
@@ -7,7 +7,10 @@ use crate::querier::{
 use crate::factory::PairType;
 use crate::DecimalCheckedOps;
 use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
-use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Uint128, WasmMsg};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, DivideByZeroError, StdError, Uint128,
+    WasmMsg,
+};
 use cw20::Cw20ExecuteMsg;
 
 #[test]
@@ -346,4 +349,46 @@ fn test_decimal_checked_ops() {
             .checked_mul(Decimal::new(Uint128::from(10u128.pow(18u32) + 1u128)))
             .is_err()
     );
+
+    for i in 0u32..100u32 {
+        let dec = Decimal::from_ratio(i, 1u32);
+        assert_eq!(Decimal::zero(), dec.checked_sub(dec).unwrap());
+        assert_eq!(dec, (dec + dec).checked_sub(dec).unwrap());
+    }
+    // reversed reward index ordering underflows instead of panicking
+    assert!(Decimal::from_ratio(1u128, 2u128)
+        .checked_sub(Decimal::one())
+        .is_err());
+
+    for i in 1u128..100u128 {
+        let dec = Decimal::from_ratio(i, 4u128);
+        assert_eq!(
+            Uint128::new(400u128),
+            dec.checked_div_uint128(Uint128::new(i * 100u128)).unwrap()
+        );
+    }
+    // amount divided by reward index, rounded down
+    assert_eq!(
+        Uint128::new(333u128),
+        Decimal::from_ratio(3u128, 1u128)
+            .checked_div_uint128(Uint128::new(1000u128))
+            .unwrap()
+    );
+    assert_eq!(
+        Uint128::zero(),
+        Decimal::one().checked_div_uint128(Uint128::zero()).unwrap()
+    );
+    // a zero reward index is a zero divisor
+    assert_eq!(
+        Decimal::zero().checked_div_uint128(Uint128::new(1u128)),
+        Err(StdError::divide_by_zero(DivideByZeroError::new(Uint128::new(1u128))))
+    );
+    assert_eq!(
+        Decimal::zero().checked_div_uint128(Uint128::zero()),
+        Err(StdError::divide_by_zero(DivideByZeroError::new(Uint128::zero())))
+    );
+    assert_eq!(
+        Decimal::from_ratio(1u128, 2u128).checked_div_uint128(Uint128::MAX),
+        Err(StdError::generic_err(format!("Division overflow: {} / 0.5", Uint128::MAX)))
+    );
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, MessageInfo, StdError, StdResult, to_binary, WasmMsg};
+use cosmwasm_std::{Addr, BankMsg, Binary, Coin, CosmosMsg, MessageInfo, StdError, StdResult, to_binary, WasmMsg};
 use cw20::{Cw20ExecuteMsg, Expiration};
 use astroport::asset::{Asset, AssetInfo};
 
@@ -6,6 +6,8 @@ pub trait AssetEx {
 
     fn transfer_msg(&self, to: &Addr) -> StdResult<CosmosMsg>;
     fn transfer_from_msg(&self, from: &Addr, to: &Addr) -> StdResult<CosmosMsg>;
+    /// Sends the asset to a contract with a message, as cw20 Send or as funds of the execution
+    fn send_msg(&self, contract: &Addr, msg: Binary) -> StdResult<CosmosMsg>;
     fn increase_allowance_msg(&self, spender: String, expires: Option<Expiration>) -> StdResult<CosmosMsg>;
 
     fn deposit_asset(
@@ -16,6 +18,38 @@ pub trait AssetEx {
     ) -> StdResult<()>;
 }
 
+/// Transfers the assets to the recipient, with one transfer per cw20 followed by a single
+/// bank send of all native coins sorted by denom. Zero amounts are skipped.
+pub fn transfer_multi_msg(assets: &[Asset], recipient: &Addr) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut coins: Vec<Coin> = vec![];
+    for asset in assets {
+        if asset.amount.is_zero() {
+            continue;
+        }
+        match &asset.info {
+            AssetInfo::Token { .. } => messages.push(asset.transfer_msg(recipient)?),
+            AssetInfo::NativeToken { denom } => {
+                match coins.iter_mut().find(|coin| &coin.denom == denom) {
+                    Some(coin) => coin.amount = coin.amount.checked_add(asset.amount)?,
+                    None => coins.push(Coin {
+                        denom: denom.to_string(),
+                        amount: asset.amount,
+                    }),
+                }
+            }
+        }
+    }
+    if !coins.is_empty() {
+        coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins,
+        }));
+    }
+    Ok(messages)
+}
+
 impl AssetEx for Asset {
 
     fn transfer_msg(
@@ -41,6 +75,28 @@ impl AssetEx for Asset {
         }
     }
 
+    fn send_msg(&self, contract: &Addr, msg: Binary) -> StdResult<CosmosMsg> {
+        match &self.info {
+            AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: contract.to_string(),
+                    amount: self.amount,
+                    msg,
+                })?,
+                funds: vec![],
+            })),
+            AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg,
+                funds: vec![Coin {
+                    denom: denom.to_string(),
+                    amount: self.amount,
+                }],
+            })),
+        }
+    }
+
     fn transfer_from_msg(&self, from: &Addr, to: &Addr) -> StdResult<CosmosMsg> {
         match &self.info {
             AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
@@ -93,3 +149,54 @@ impl AssetEx for Asset {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Uint128;
+    use astroport::asset::{native_asset, token_asset};
+
+    #[test]
+    fn transfer_multi() {
+        let recipient = Addr::unchecked("recipient");
+        let assets = vec![
+            native_asset("uluna".to_string(), Uint128::new(100)),
+            token_asset(Addr::unchecked("token"), Uint128::new(200)),
+            native_asset("uusd".to_string(), Uint128::new(300)),
+            native_asset("ibc/stablecoin".to_string(), Uint128::zero()),
+            token_asset(Addr::unchecked("token_zero"), Uint128::zero()),
+        ];
+
+        let messages = transfer_multi_msg(&assets, &recipient).unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "token".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: "recipient".to_string(),
+                        amount: Uint128::new(200),
+                    }).unwrap(),
+                    funds: vec![],
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "recipient".to_string(),
+                    amount: vec![
+                        Coin {
+                            denom: "uluna".to_string(),
+                            amount: Uint128::new(100),
+                        },
+                        Coin {
+                            denom: "uusd".to_string(),
+                            amount: Uint128::new(300),
+                        },
+                    ],
+                }),
+            ]
+        );
+
+        let assets = vec![native_asset("uluna".to_string(), Uint128::zero())];
+        let messages = transfer_multi_msg(&assets, &recipient).unwrap();
+        assert!(messages.is_empty());
+    }
+}
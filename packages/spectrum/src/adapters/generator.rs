@@ -1,8 +1,9 @@
-use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::generator::{Cw20HookMsg, ExecuteMsg, PendingTokenResponse, QueryMsg};
+use astroport::asset::{token_asset_info, AssetInfo};
+use astroport::generator::{Cw20HookMsg, ExecuteMsg, PendingTokenResponse, QueryMsg, RewardInfoResponse};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Generator(pub Addr);
@@ -32,6 +33,22 @@ impl Generator {
         })
     }
 
+    /// Returns the base reward token followed by the proxy reward token, if any
+    pub fn query_reward_tokens(
+        &self,
+        querier: &QuerierWrapper,
+        lp_token: &Addr,
+    ) -> StdResult<Vec<AssetInfo>> {
+        let reward_info: RewardInfoResponse = querier.query_wasm_smart(self.0.to_string(), &QueryMsg::RewardInfo {
+            lp_token: lp_token.to_string(),
+        })?;
+        let mut reward_tokens = vec![token_asset_info(reward_info.base_reward_token)];
+        if let Some(proxy_reward_token) = reward_info.proxy_reward_token {
+            reward_tokens.push(token_asset_info(proxy_reward_token));
+        }
+        Ok(reward_tokens)
+    }
+
     pub fn deposit_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: lp_token,
@@ -44,6 +61,17 @@ impl Generator {
         }))
     }
 
+    /// Deposits native LP tokens, such as a token factory denom, by attaching them as funds
+    pub fn deposit_native_msg(&self, denom: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![Coin { denom, amount }],
+            msg: to_binary(&ExecuteMsg::Deposit {
+                recipient: None,
+            })?,
+        }))
+    }
+
     pub fn withdraw_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: self.0.to_string(),
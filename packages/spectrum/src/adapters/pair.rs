@@ -3,6 +3,7 @@ use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
 use astroport::pair::{Cw20HookMsg, ExecuteMsg, QueryMsg, SimulationResponse, ConfigResponse, PoolResponse};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -13,6 +14,11 @@ impl Pair {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pair {})
     }
 
+    /// Returns the pool type, the parameters of the pool are returned by [`Pair::query_config`]
+    pub fn query_pair_type(&self, querier: &QuerierWrapper) -> StdResult<PairType> {
+        Ok(self.query_pair_info(querier)?.pair_type)
+    }
+
     pub fn query_pool_info(&self, querier: &QuerierWrapper) -> StdResult<PoolResponse> {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pool {})
     }
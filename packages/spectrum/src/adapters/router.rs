@@ -81,6 +81,20 @@ impl SwapOperation {
             } => offer_asset_info.clone(),
         }
     }
+
+    pub fn get_ask_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::AstroSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+            SwapOperation::TerraSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+            SwapOperation::TokenSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -112,6 +126,10 @@ pub enum QueryMsg {
         offer_amount: Uint128,
         operations: Vec<SwapOperation>,
     },
+    ReverseSimulateSwapOperations {
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -138,6 +156,36 @@ impl Router {
         )
     }
 
+    pub fn reverse_simulate(
+        &self,
+        querier: &QuerierWrapper,
+        ask_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    ) -> StdResult<SimulateSwapOperationsResponse> {
+        querier.query_wasm_smart(
+            self.0.to_string(),
+            &QueryMsg::ReverseSimulateSwapOperations {
+                ask_amount,
+                operations,
+            },
+        )
+    }
+
+    /// Returns the offer amount needed to receive `ask_amount` of `ask` from a single hop swap
+    pub fn try_reverse_swap_simulation(
+        &self,
+        querier: &QuerierWrapper,
+        offer: AssetInfo,
+        ask: AssetInfo,
+        ask_amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let operations = vec![SwapOperation::AstroSwap {
+            offer_asset_info: offer,
+            ask_asset_info: ask,
+        }];
+        Ok(self.reverse_simulate(querier, ask_amount, operations)?.amount)
+    }
+
     pub fn execute_swap_operations_msg(
         &self,
         offer_asset: Asset,
@@ -179,3 +227,50 @@ impl Router {
         Ok(CosmosMsg::Wasm(wasm_msg))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockQuerier;
+    use cosmwasm_std::{from_binary, ContractResult, SystemError, SystemResult, WasmQuery};
+
+    #[test]
+    fn try_reverse_swap_simulation() {
+        let offer = AssetInfo::NativeToken { denom: "uluna".to_string() };
+        let ask = AssetInfo::Token { contract_addr: Addr::unchecked("token") };
+
+        let mut querier: MockQuerier = MockQuerier::new(&[]);
+        querier.update_wasm(|request| match request {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "router" => {
+                match from_binary(msg).unwrap() {
+                    QueryMsg::ReverseSimulateSwapOperations { ask_amount, operations } => {
+                        assert_eq!(operations, vec![SwapOperation::AstroSwap {
+                            offer_asset_info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+                            ask_asset_info: AssetInfo::Token { contract_addr: Addr::unchecked("token") },
+                        }]);
+                        let amount = ask_amount * Uint128::new(2);
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&SimulateSwapOperationsResponse { amount }).unwrap(),
+                        ))
+                    }
+                    _ => SystemResult::Ok(ContractResult::Err("unsupported".to_string())),
+                }
+            }
+            WasmQuery::Smart { contract_addr, .. } => SystemResult::Err(SystemError::NoSuchContract {
+                addr: contract_addr.clone(),
+            }),
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+        let querier = QuerierWrapper::new(&querier);
+
+        let router = Router(Addr::unchecked("router"));
+        let amount = router
+            .try_reverse_swap_simulation(&querier, offer.clone(), ask.clone(), Uint128::new(100))
+            .unwrap();
+        assert_eq!(amount, Uint128::new(200));
+
+        let router = Router(Addr::unchecked("not_router"));
+        let res = router.try_reverse_swap_simulation(&querier, offer, ask, Uint128::new(100));
+        assert!(res.is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use astroport::asset::Asset;
+use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg, StdError, Binary};
 use cw20::{Cw20ReceiveMsg, Expiration, Logo};
 use schemars::JsonSchema;
@@ -19,8 +19,11 @@ pub struct InstantiateMsg {
     pub fee: Decimal,
     /// The fee collector contract address
     pub fee_collector: String,
-    /// The LP token contract address
+    /// The LP token contract address, or the LP denom when `native_lp` is set
     pub liquidity_token: String,
+    /// Whether the LP token is a native denom, such as a token factory LP token
+    #[serde(default)]
+    pub native_lp: bool,
     /// the base reward token contract address
     pub base_reward_token: String,
     /// The pair contract address
@@ -34,6 +37,7 @@ pub struct InstantiateMsg {
 /// This structure describes the execute messages available in the contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     /// Receives a message of type [`Cw20ReceiveMsg`]
     Receive(Cw20ReceiveMsg),
@@ -47,19 +51,59 @@ pub enum ExecuteMsg {
         fee: Option<Decimal>,
         /// The fee collector contract address
         fee_collector: Option<String>,
+        /// The maximum slippage tolerance enforced on compound, zero to disable
+        min_slippage_protection: Option<Decimal>,
+        /// The period over which a new deposit accrues compounded rewards
+        deposit_penalty_period: Option<u64>,
+        /// The minimum LP amount simulated from rewards to compound, zero to disable
+        min_compound_value: Option<Uint128>,
+        /// The fraction of rewards paid to the caller of public compound, zero to disable public compound.
+        /// The fee and bounty together must not exceed 1
+        bounty: Option<Decimal>,
+        /// The slippage tolerance used for bond assets and compound when not given, zero to use the compound proxy default
+        default_slippage: Option<Decimal>,
+        /// The maximum slippage tolerance accepted for bond assets and compound
+        max_slippage: Option<Decimal>,
+        /// Whether emergency unbond is enabled
+        emergency: Option<bool>,
+        /// The maximum bounty that can be set, 5% by default
+        max_bounty: Option<Decimal>,
+    },
+    /// Bond native LP token attached to the message, for a farm with a native LP denom
+    BondNative {
+        /// The address to bond on behalf of, the sender if not set
+        staker_addr: Option<String>,
+    },
+    /// Bond native LP token attached to the message on behalf of many stakers,
+    /// for a farm with a native LP denom
+    BatchBond {
+        /// The stakers and their LP amounts, summing to the attached amount
+        entries: Vec<(String, Uint128)>,
     },
     /// Unbond LP token
     Unbond {
         /// The LP amount to unbond
         amount: Uint128,
     },
+    /// Unbond LP token without deposit time penalty, for exit during compound outage.
+    /// Only allowed while the owner has enabled emergency mode
+    EmergencyUnbond {
+        /// The LP amount to unbond
+        amount: Uint128,
+    },
     /// Compound LP rewards
     Compound {
         /// The minimum expected amount of LP token
         minimum_receive: Option<Uint128>,
         /// Slippage tolerance when providing LP
         slippage_tolerance: Option<Decimal>,
+        /// The flag to only claim rewards and take fee, the rest is held for a later compound
+        skip_provide: Option<bool>,
+        /// The reward tokens to compound, others are left for a later call. None means all tokens
+        reward_assets: Option<Vec<AssetInfo>>,
     },
+    /// Compound LP rewards by anyone, the bounty fraction of rewards is sent to the caller
+    PublicCompound {},
     /// Bond asset with optimal swap
     BondAssets {
         /// The list of asset to bond
@@ -185,7 +229,16 @@ impl CallbackMsg {
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
     // Bond LP token
-    Bond { staker_addr: Option<String> },
+    Bond {
+        staker_addr: Option<String>,
+        /// The time in seconds until the position cannot be unbonded, a transferred share carries the lock. Only allowed when bonding to the sender
+        lock_until: Option<u64>,
+    },
+    /// Bond LP token on behalf of many stakers
+    BatchBond {
+        /// The stakers and their LP amounts, summing to the sent amount
+        entries: Vec<(String, Uint128)>,
+    },
 }
 
 /// This structure describes query messages available in the contract.
@@ -198,6 +251,27 @@ pub enum QueryMsg {
     RewardInfo { staker_addr: String },
     /// Returns the global state
     State {},
+    /// Returns the pending rewards to be compounded
+    PendingRewards {},
+    /// Returns the contract version
+    Version {},
+    /// Returns the realized yield from recent compounds
+    PerformanceStats {},
+    /// Returns the cumulative fee sent to the fee collector by reward asset
+    FeeStats {},
+    /// Returns the total share and LP token amount staked in the generator
+    PoolInfo {},
+    /// Returns the LP token amount staked per bond share as a decimal, one when no share is issued
+    ExchangeRate {},
+    /// Returns the LP amount and bond share expected from bonding the given assets
+    SimulateBondAssets {
+        /// The list of assets to bond
+        assets: Vec<Asset>,
+        /// Provide the assets without the optimal swap, same as BondAssets
+        no_swap: Option<bool>,
+        /// The slippage tolerance applied to the LP amount, same as BondAssets
+        slippage_tolerance: Option<Decimal>,
+    },
 
     /// cw20
     /// Returns the current balance of the given address, 0 if unset.
@@ -257,6 +331,10 @@ pub struct RewardInfoResponseItem {
     pub staking_token: String,
     /// The LP token amount bonded
     pub bond_amount: Uint128,
+    /// The LP token amount that can be unbonded now, equal to bond_amount
+    pub vested_bond_amount: Uint128,
+    /// The LP token amount withheld by the deposit lock, released over the lock period
+    pub unvested_bond_amount: Uint128,
     /// The share of total LP token bonded
     pub bond_share: Uint128,
     /// The deposit amount
@@ -265,6 +343,41 @@ pub struct RewardInfoResponseItem {
     pub deposit_time: u64,
     /// The deposit cost
     pub deposit_costs: Vec<Uint128>,
+    /// The time in seconds until the position cannot be unbonded
+    pub lock_until: Option<u64>,
+}
+
+/// This structure holds the parameters for performance stats query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PerformanceStatsResponse {
+    /// The time of the last compound
+    pub last_compound_time: u64,
+    /// The LP token amount compounded in the last 24 hours
+    pub compounded_amount_24h: Uint128,
+    /// The LP token amount bonded
+    pub total_bond_amount: Uint128,
+    /// The annualized rate from the most recent compound window
+    pub apr: Decimal,
+}
+
+/// This structure holds the parameters for pool info query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolInfoResponse {
+    /// The total share of LP token bonded, equal to the share token supply
+    pub total_bond_share: Uint128,
+    /// The LP token amount staked in the generator
+    pub total_bond_amount: Uint128,
+}
+
+/// This structure holds the parameters for simulate bond assets query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateBondAssetsResponse {
+    /// The LP amount expected from the compound proxy
+    pub lp_amount: Uint128,
+    /// The bond share expected for the LP amount
+    pub bond_share: Uint128,
+    /// The LP amount after slippage tolerance, to be used as the minimum receive of BondAssets
+    pub minimum_receive: Uint128,
 }
 
 /// This structure describes a migration message.
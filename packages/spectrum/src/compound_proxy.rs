@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg, Decimal, Uint128, Coin};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg, Decimal, Uint128, Coin, QuerierWrapper};
+use crate::adapters::router::SwapOperation;
 
 /// This structure describes the basic settings for creating a contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,6 +17,14 @@ pub struct InstantiateMsg {
     pub pair_proxies: Vec<(AssetInfo, String)>,
     /// The slippage tolerance when swapping
     pub slippage_tolerance: Decimal,
+    /// The maximum number of swap operations per compound
+    pub max_swaps: u64,
+    /// The owner address to update routes
+    pub owner: String,
+    /// The router contract address to execute multi-hop routes
+    pub router: Option<String>,
+    /// The only addresses allowed as the LP receiver of compound, any address if not set
+    pub allowed_recipients: Option<Vec<String>>,
 }
 
 /// This structure describes the execute messages of the contract.
@@ -32,6 +41,44 @@ pub enum ExecuteMsg {
         no_swap: Option<bool>, 
         /// slippage tolerance when providing LP
         slippage_tolerance: Option<Decimal>,
+        /// Pair asset index (0 or 1) receiving the remainder after optimal swap
+        dust_to: Option<u8>,
+        /// Max spread when swapping the given reward asset, checked against the pool price
+        slippage_overrides: Option<Vec<(AssetInfo, Decimal)>>,
+        /// Return the pair assets left after providing liquidity to the receiver
+        refund_dust: Option<bool>,
+        /// Multi-hop routes by reward asset used instead of the stored route or pair proxy
+        routes: Option<Vec<(AssetInfo, Vec<SwapOperation>)>>,
+    },
+    /// Swap rewards to an asset in the pair without providing liquidity
+    SwapTo {
+        /// List of reward asset send to swap
+        rewards: Vec<Asset>,
+        /// The asset in the pair to receive
+        target: AssetInfo,
+        /// Receiver address for the target asset
+        to: Option<String>,
+        /// Minimum amount of the target asset to receive
+        minimum_receive: Option<Uint128>,
+        /// Max spread when swapping the other pair asset to the target asset
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Update contract config
+    UpdateConfig {
+        /// The owner address
+        owner: Option<String>,
+        /// The router contract address
+        router: Option<String>,
+        /// The only addresses allowed as the LP receiver of compound, an empty list allows any address
+        allowed_recipients: Option<Vec<String>>,
+    },
+    /// Set the multi-hop route of a reward asset, which takes precedence over the pair proxy.
+    /// None removes the route.
+    UpdateRoute {
+        /// The reward asset
+        asset_info: AssetInfo,
+        /// The swap operations from the reward asset to an asset in the pair
+        operations: Option<Vec<SwapOperation>>,
     },
     /// The callback of type [`CallbackMsg`]
     Callback(CallbackMsg),
@@ -42,13 +89,32 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum CallbackMsg {
     /// Performs optimal swap
-    OptimalSwap {},
+    OptimalSwap {
+        dust_to: Option<u8>,
+    },
     /// Provides liquidity to the pair contract
     ProvideLiquidity {
         prev_balances: Vec<Asset>,
         receiver: String,
         slippage_tolerance: Option<Decimal>,
     },
+    /// Returns the pair assets left after providing liquidity
+    RefundDust {
+        prev_balances: Vec<Asset>,
+        receiver: String,
+    },
+    /// Swaps the other pair asset received to the target asset
+    SwapToTarget {
+        prev_balances: Vec<Asset>,
+        target: AssetInfo,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Transfers the target asset received to the receiver
+    TransferTarget {
+        prev_balance: Asset,
+        receiver: String,
+        minimum_receive: Option<Uint128>,
+    },
 }
 
 // Modified from
@@ -72,7 +138,49 @@ pub enum QueryMsg {
     /// Return LP token amount received after compound
     CompoundSimulation {
         rewards: Vec<Asset>,
+        /// Slippage tolerance applied to the LP amount, default to the slippage tolerance in config
+        slippage_tolerance: Option<Decimal>,
+        /// Provide the rewards without the optimal swap, same as compound
+        #[serde(default)]
+        no_swap: Option<bool>,
+    },
+    /// Returns the multi-hop routes by reward asset
+    Routes {
+        /// The reward asset to start after
+        start_after: Option<String>,
+        /// The maximum number of routes to return
+        limit: Option<u32>,
+    },
+    /// Returns the multi-hop route of a reward asset
+    Route {
+        /// The reward asset
+        asset_info: AssetInfo,
     },
+    /// Validates the swap operations as a route and returns the simulated amount of each hop
+    SimulateSwapOperations {
+        /// The amount of the first offer asset
+        offer_amount: Uint128,
+        /// The swap operations to simulate, not required to be stored
+        operations: Vec<SwapOperation>,
+    },
+}
+
+/// This structure holds a multi-hop route of a reward asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteResponse {
+    /// The reward asset
+    pub asset_info: String,
+    /// The swap operations from the reward asset to an asset in the pair
+    pub operations: Vec<SwapOperation>,
+}
+
+/// This structure holds the parameters that are returned from a swap operations simulation response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapOperationsSimulationResponse {
+    /// The amount of the last ask asset
+    pub amount: Uint128,
+    /// The amount received after each hop
+    pub hop_amounts: Vec<Uint128>,
 }
 
 /// This structure holds the parameters that are returned from a compound simulation response
@@ -88,12 +196,18 @@ pub struct CompoundSimulationResponse {
     pub return_a_amount: Uint128,
     /// The amount of asset B returned from swap
     pub return_b_amount: Uint128,
+    /// The LP amount after slippage tolerance, to be used as the minimum receive of compound
+    #[serde(default)]
+    pub minimum_receive: Uint128,
 }
 
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The owner address to set, required for contracts instantiated before the owner was added
+    #[serde(default)]
+    pub owner: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Compounder(pub Addr);
@@ -108,8 +222,16 @@ impl Compounder {
                 no_swap,
                 to: None,
                 slippage_tolerance,
+                dust_to: None,
+                slippage_overrides: None,
+                refund_dust: None,
+                routes: None,
             })?,
             funds,
         }))
     }
+
+    pub fn query_compound_simulation(&self, querier: &QuerierWrapper, rewards: Vec<Asset>, no_swap: Option<bool>, slippage_tolerance: Option<Decimal>) -> StdResult<CompoundSimulationResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::CompoundSimulation { rewards, slippage_tolerance, no_swap })
+    }
 }
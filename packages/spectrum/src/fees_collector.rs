@@ -1,5 +1,5 @@
 use astroport::asset::{Asset, AssetInfo};
-use cosmwasm_std::{Uint128};
+use cosmwasm_std::{Binary, Decimal, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -14,8 +14,13 @@ pub struct InstantiateMsg {
     pub factory_contract: String,
     /// The stablecoin asset info
     pub stablecoin: AssetInfo,
-    /// The beneficiary addresses to received fees in stablecoin
-    pub target_list: Vec<(String, u64)>,
+    /// The beneficiary (address, weight, msg) to received fees in stablecoin,
+    /// fees are sent along with msg when it is set, see [`ExecuteMsg::UpdateConfig`]
+    pub target_list: Vec<(String, u64, Option<Binary>)>,
+    /// The beneficiary (address, ratio) to received fees in stablecoin, used in place of target_list when set.
+    /// Ratios must sum to one
+    #[serde(default)]
+    pub target_ratios: Option<Vec<(String, Decimal)>>,
 }
 
 /// This structure describes the functions that can be executed in this contract.
@@ -28,6 +33,14 @@ pub enum ExecuteMsg {
         assets: Vec<AssetWithLimit>,
         /// The minimum expected amount of stablecoine
         minimum_receive: Option<Uint128>,
+        /// If true, a failed distribution does not revert the swaps
+        #[serde(default)]
+        best_effort: bool,
+    },
+    /// Collects and swaps all native balances and bridged tokens held by the contract to stablecoin
+    CollectAll {
+        /// The minimum expected amount of stablecoine
+        minimum_receive: Option<Uint128>,
     },
     /// Updates contract config
     UpdateConfig {
@@ -35,8 +48,21 @@ pub enum ExecuteMsg {
         operator: Option<String>,
         /// The factory contract address
         factory_contract: Option<String>,
-        /// The list of target address to receive fees in stablecoin
-        target_list: Option<Vec<(String, u64)>>,
+        /// The list of target (address, weight, msg) to receive fees in stablecoin.
+        /// When msg is set, the address must be a contract and receives the fees along with msg,
+        /// as funds of the execute message for native stablecoin, or as the hook of Cw20 Send for token stablecoin
+        target_list: Option<Vec<(String, u64, Option<Binary>)>>,
+        /// Swap execution depth limit, lowering it fails if a stored bridge no longer fits
+        max_bridge_depth: Option<u64>,
+        /// Minimum amount sent to a recipient, zero to disable
+        min_distribution: Option<Uint128>,
+        /// Replace minimum balance swapped per asset by collect
+        min_swap_amounts: Option<Vec<(AssetInfo, Uint128)>>,
+        /// The list of target (address, ratio) to receive fees in stablecoin, ratios must sum to one.
+        /// Setting target_ratios switches distribution to ratios, setting target_list switches back to weights
+        target_ratios: Option<Vec<(String, Decimal)>>,
+        /// The router contract address used by the reverse collect simulation
+        router: Option<String>,
     },
     /// Add bridge tokens used to swap specific fee tokens to stablecoin (effectively declaring a swap route)
     UpdateBridges {
@@ -52,17 +78,35 @@ pub enum ExecuteMsg {
         /// The minimum expected amount of stablecoine
         minimum_receive: Option<Uint128>,
     },
+    /// Checks the stablecoin balance after swaps, queued by best effort collect outside the caught distribution
+    AssertMinimumReceive {
+        /// The minimum expected amount of stablecoine
+        minimum_receive: Uint128,
+    },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
         /// The newly proposed owner
         owner: String,
         /// The validity period of the proposal to change the owner
         expires_in: u64,
+        /// The delay between claiming and finalizing the ownership change
+        timelock: Option<u64>,
     },
     /// Removes a request to change contract ownership
     DropOwnershipProposal {},
     /// Claims contract ownership
     ClaimOwnership {},
+    /// Creates a request to change the contract's operator
+    ProposeNewOperator {
+        /// The newly proposed operator
+        operator: String,
+        /// The validity period of the proposal to change the operator
+        expires_in: u64,
+    },
+    /// Removes a request to change contract operator
+    DropOperatorProposal {},
+    /// Claims contract operator
+    ClaimOperator {},
 }
 
 /// This structure describes the query functions available in the contract.
@@ -77,11 +121,27 @@ pub enum QueryMsg {
     },
     /// Returns list of bridge assets
     Bridges {},
+    /// Returns the assets the given asset is swapped through to reach stablecoin, starting with the asset itself
+    BridgePath {
+        asset: AssetInfo,
+    },
+    /// Validates that every registered bridge can still be swapped to stablecoin, without executing
+    ValidateBridges {},
     /// Simulate collects and swaps fee tokens to stablecoin
     CollectSimulation {
         /// The assets to swap to stablecoin
         assets: Vec<AssetWithLimit>,
-    }
+    },
+    /// Simulate the stablecoin amount each target receives from distributing the current balance,
+    /// the amount left in the contract is attributed to the contract at the end
+    DistributeSimulation {},
+    /// Simulate the amount of an asset to collect for a given amount of stablecoin
+    ReverseCollectSimulation {
+        /// The asset to swap to stablecoin
+        asset: AssetInfo,
+        /// The amount of stablecoin to receive
+        return_amount: Uint128,
+    },
 }
 
 /// A custom struct used to return multiple asset balances.
@@ -91,11 +151,47 @@ pub struct BalancesResponse {
     pub balances: Vec<Asset>,
 }
 
+/// This structure holds the validation result of a registered bridge
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BridgeValidationResponse {
+    /// The asset swapped through the bridge
+    pub asset: String,
+    /// The bridge asset
+    pub bridge: String,
+    /// The reason the bridge cannot reach stablecoin, none if it can
+    pub error: Option<String>,
+}
+
 /// This structure holds the parameters that are returned from a collect simulation response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollectSimulationResponse {
-    /// The amount of stablecoin returned from swap
+    /// The amount of stablecoin returned from swap, the sum of the breakdown
     pub return_amount: Uint128,
+    /// The first swap path taken by each input asset, sorted by asset
+    pub paths: Vec<(AssetInfo, SwapPath)>,
+    /// The stablecoin amount of each input asset simulated in isolation, sorted by asset
+    pub breakdown: Vec<(String, Uint128)>,
+}
+
+/// This structure holds the parameters that are returned from a reverse collect simulation response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseCollectSimulationResponse {
+    /// The amount of the asset required
+    pub offer_amount: Uint128,
+    /// The amount of each asset along the swap path, from the asset to stablecoin
+    pub amounts: Vec<Uint128>,
+}
+
+/// This enum describes how an asset is swapped toward stablecoin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapPath {
+    /// Swapped directly to stablecoin
+    Direct,
+    /// Swapped to the bridge asset set for it
+    Bridge,
+    /// Swapped to the intermediate denom (uluna) as a fallback
+    Intermediate,
 }
 
 /// This structure describes a migration message.
@@ -7,9 +7,13 @@ use cw20::Cw20ReceiveMsg;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
+    /// Reward token address, or the reward denom when reward_is_native is set
     pub reward_token: String,
     pub staking_token: String,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    /// Distributes the reward as native coins, the contract is funded through the bank module
+    #[serde(default)]
+    pub reward_is_native: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -18,11 +22,35 @@ pub enum ExecuteMsg {
     /// Receives a message of type [`Cw20ReceiveMsg`]
     Receive(Cw20ReceiveMsg),
     /// Unbond token
-    Unbond { amount: Uint128 },
+    Unbond {
+        amount: Uint128,
+        /// Staking token to unbond, defaults to the staking token given at instantiate
+        staking_token: Option<String>,
+    },
     /// Withdraw pending rewards
-    Withdraw { amount: Option<Uint128> },
+    Withdraw {
+        amount: Option<Uint128>,
+        /// Staking token to withdraw rewards from, defaults to the staking token given at instantiate
+        staking_token: Option<String>,
+        /// Address receiving the rewards, defaults to the sender
+        recipient: Option<String>,
+    },
+    /// Bonds pending rewards back into the staking token, requires reward_token to be the staking token
+    Restake {},
+    /// Registers a new staking token or replaces the schedule of a registered one
+    RegisterStakingToken {
+        token: String,
+        schedule: Vec<(u64, u64, Uint128)>,
+    },
+    /// Pauses or resumes reward distribution
+    SetPaused { paused: bool },
     UpdateConfig {
+        /// Schedule of the staking token given at instantiate
         distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+        /// Fee deducted from withdrawn rewards
+        claim_fee: Option<Decimal>,
+        /// Address receiving the claim fee
+        fee_collector: Option<String>,
     },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
@@ -49,15 +77,36 @@ pub enum QueryMsg {
     Config {},
     State {
         time_seconds: Option<u64>,
+        staking_token: Option<String>,
     },
     RewardInfo {
         time_seconds: Option<u64>,
         staker_addr: String,
+        staking_token: Option<String>,
     },
     AllRewardInfos {
         start_after: Option<String>,
         limit: Option<u32>,
         time_seconds: Option<u64>,
+        staking_token: Option<String>,
+    },
+    /// Returns reward info of the given stakers, in the same order
+    RewardInfos {
+        staker_addrs: Vec<String>,
+        time_seconds: Option<u64>,
+        staking_token: Option<String>,
+    },
+    /// Returns bonded amount of the staker as of the given time, fails if the time is older than the retained history
+    BondAt {
+        staker_addr: String,
+        time_seconds: u64,
+        staking_token: Option<String>,
+    },
+    /// Returns the projected pending reward at the given time of bonding the amount now
+    SimulateStake {
+        amount: Uint128,
+        time_seconds: u64,
+        staking_token: Option<String>,
     },
 }
 
@@ -67,13 +116,24 @@ pub struct ConfigResponse {
     pub reward_token: String,
     pub staking_token: String,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    /// All registered staking tokens
+    pub staking_tokens: Vec<String>,
+    pub claim_fee: Decimal,
+    pub fee_collector: String,
+    pub reward_is_native: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StateResponse {
+    pub staking_token: String,
+    pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub paused: bool,
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    /// Cumulative distributed amount, never exceeds the sum of the distribution schedule.
+    /// For states stored before the upgrade, it starts from the amount scheduled before the upgrade
+    pub total_distributed: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -90,6 +150,22 @@ pub struct RewardInfoResponseItem {
     pub pending_reward: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BondAtResponse {
+    pub staker_addr: String,
+    pub time_seconds: u64,
+    pub bond_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateStakeResponse {
+    pub staking_token: String,
+    pub amount: Uint128,
+    pub time_seconds: u64,
+    pub global_reward_index: Decimal,
+    pub pending_reward: Uint128,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -3,11 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{Decimal};
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
 use cw20::Cw20ReceiveMsg;
-use crate::adapters::router::RouterType;
+use crate::adapters::router::{RouterType, SwapOperation};
 
-/// Maximum assets in the swap route
+/// Maximum assets in the swap route, the initial limit of the number of hops is one less
 pub const MAX_ASSETS: usize = 50;
 
 /// This structure describes the basic settings for creating a contract.
@@ -23,6 +23,16 @@ pub struct InstantiateMsg {
     pub offer_precision: Option<u8>,
     /// The decimal precision of the ask asset
     pub ask_precision: Option<u8>,
+    /// Human-readable label of the swap route
+    pub label: Option<String>,
+    /// Maximum spread used when a swap does not specify one
+    pub default_max_spread: Option<Decimal>,
+    /// The pair contract of the offer and ask assets to provide liquidity
+    pub liquidity_pair: Option<String>,
+    /// Fee taken from the swap return, must be less than 1
+    pub fee: Option<Decimal>,
+    /// The address receiving the fee, required when the fee is set
+    pub fee_recipient: Option<String>,
 }
 
 /// This structure describes the execute messages of the contract.
@@ -35,6 +45,24 @@ pub enum ExecuteMsg {
     Swap {
         /// Offer asset
         offer_asset: Asset,
+        /// Ask asset info, an intermediate asset truncates the swap route
+        ask_asset_info: Option<AssetInfo>,
+        /// Belief price of the asset
+        belief_price: Option<Decimal>,
+        /// Maximum spread from the belief price
+        max_spread: Option<Decimal>,
+        /// Receiver address
+        to: Option<String>,
+        /// Message executed on the receiver contract with the ask asset
+        #[serde(default)]
+        to_msg: Option<Binary>,
+    },
+    /// Split a swap across several routes by weight, the minimum receive is checked on the total return
+    SplitSwap {
+        /// Offer asset
+        offer_asset: Asset,
+        /// The list of assets in each route with its weight, the weights must sum to 1
+        routes: Vec<(Vec<AssetInfo>, Decimal)>,
         /// Belief price of the asset
         belief_price: Option<Decimal>,
         /// Maximum spread from the belief price
@@ -42,6 +70,95 @@ pub enum ExecuteMsg {
         /// Receiver address
         to: Option<String>,
     },
+    /// Swap along the route with the highest simulated return, among the stored route and the candidates
+    SwapBest {
+        /// Offer asset
+        offer_asset: Asset,
+        /// Candidate routes from the offer asset to the other end of the stored route
+        routes: Vec<Vec<AssetInfo>>,
+        /// Belief price of the asset
+        belief_price: Option<Decimal>,
+        /// Maximum spread from the belief price
+        max_spread: Option<Decimal>,
+        /// Receiver address
+        to: Option<String>,
+    },
+    /// Provide liquidity of the offer and ask assets to the liquidity pair
+    ProvideLiquidity {
+        /// The assets to provide, must be the offer and ask assets
+        assets: [Asset; 2],
+        /// The slippage tolerance when providing liquidity
+        slippage_tolerance: Option<Decimal>,
+        /// Receiver address of the LP token
+        receiver: Option<String>,
+    },
+    /// The callback of type [`CallbackMsg`]
+    Callback(CallbackMsg),
+    /// Updates the contract config, only the owner can execute it
+    UpdateConfig {
+        /// Maximum number of swap operations in a route, must be at least 1
+        max_hops: Option<usize>,
+        /// Fee taken from the swap return, must be less than 1, zero disables the fee
+        #[serde(default)]
+        fee: Option<Decimal>,
+        /// The address receiving the fee, required when the fee is set
+        #[serde(default)]
+        fee_recipient: Option<String>,
+        /// Maximum spread used when a swap does not specify one
+        #[serde(default)]
+        default_max_spread: Option<Decimal>,
+        /// Human-readable label of the swap route, empty to remove the label
+        #[serde(default)]
+        label: Option<String>,
+    },
+}
+
+/// This structure describes the callback messages of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackMsg {
+    /// Checks the ask asset received since the split swap and sends it to the receiver
+    AssertMinimumReceive {
+        asset_info: AssetInfo,
+        prev_balance: Uint128,
+        minimum_receive: Option<Uint128>,
+        receiver: String,
+        /// Message executed on the receiver contract with the ask asset, instead of a transfer
+        #[serde(default)]
+        receiver_msg: Option<Binary>,
+    },
+    /// Swaps the offer amount along the first operation back to this contract, then records the return
+    SwapHop {
+        /// The remaining operations of the route
+        operations: Vec<SwapOperation>,
+        /// Index of the first operation in the route
+        hop: u32,
+        offer_amount: Uint128,
+        max_spread: Decimal,
+    },
+    /// Records the return of the first operation, then swaps it along the remaining operations
+    RecordHop {
+        /// The remaining operations of the route
+        operations: Vec<SwapOperation>,
+        /// Index of the first operation in the route
+        hop: u32,
+        offer_amount: Uint128,
+        /// Balance of the ask asset of the first operation before the swap
+        prev_balance: Uint128,
+        max_spread: Decimal,
+    },
+}
+
+// Modified from
+// https://github.com/CosmWasm/cw-plus/blob/v0.8.0/packages/cw20/src/receiver.rs#L23
+impl CallbackMsg {
+    pub fn into_cosmos_msg(&self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(contract_addr),
+            msg: to_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
 }
 
 /// ## Description
@@ -51,12 +168,39 @@ pub enum ExecuteMsg {
 pub enum Cw20HookMsg {
     /// Sell a given amount of asset
     Swap {
+        /// Ask asset info, an intermediate asset truncates the swap route
+        ask_asset_info: Option<AssetInfo>,
         /// Belief price of the asset
         belief_price: Option<Decimal>,
         /// Maximum spread from the belief price        
         max_spread: Option<Decimal>,
         /// Receiver address
         to: Option<String>,
+        /// Message executed on the receiver contract with the ask asset
+        #[serde(default)]
+        to_msg: Option<Binary>,
+    },
+    /// Split a given amount of asset across several routes by weight
+    SplitSwap {
+        /// The list of assets in each route with its weight, the weights must sum to 1
+        routes: Vec<(Vec<AssetInfo>, Decimal)>,
+        /// Belief price of the asset
+        belief_price: Option<Decimal>,
+        /// Maximum spread from the belief price
+        max_spread: Option<Decimal>,
+        /// Receiver address
+        to: Option<String>,
+    },
+    /// Swap a given amount of asset along the route with the highest simulated return
+    SwapBest {
+        /// Candidate routes from the offer asset to the other end of the stored route
+        routes: Vec<Vec<AssetInfo>>,
+        /// Belief price of the asset
+        belief_price: Option<Decimal>,
+        /// Maximum spread from the belief price
+        max_spread: Option<Decimal>,
+        /// Receiver address
+        to: Option<String>,
     },
 }
 
@@ -72,12 +216,76 @@ pub enum QueryMsg {
     Simulation {
         /// Offer asset
         offer_asset: Asset,
-        /// Ask asset info when there are more than two assets in the pool
+        /// Ask asset info, an intermediate asset truncates the swap route
         ask_asset_info: Option<AssetInfo>,
     },
+    /// Returns the offer amount required for the ask amount after the route fee in a [`ReverseSimulationResponse`] object.
+    ReverseSimulation {
+        /// Offer asset info, an intermediate asset truncates the swap route
+        offer_asset_info: Option<AssetInfo>,
+        /// Ask asset
+        ask_asset: Asset,
+    },
+    /// Returns the swap route from the offer asset to the ask asset in a [`RouteResponse`] object.
+    Route {
+        /// Offer asset info and ask asset info, an intermediate ask asset truncates the swap route
+        asset_infos: [AssetInfo; 2],
+    },
+    /// Returns the list of asset in the swap route with the given label
+    RouteByLabel {
+        /// Label of the swap route
+        label: String,
+    },
+    /// Returns the swap routes with the given label in a list of [`RouteResponse`] objects, labels need not be unique
+    RoutesByLabel {
+        /// Label of the swap routes
+        label: String,
+    },
+    /// Returns the number of hops of the swap route in a [`RouteComplexityResponse`] object.
+    RouteComplexity {
+        /// Offer asset info
+        offer: AssetInfo,
+        /// Ask asset info
+        ask: AssetInfo,
+    },
+}
+
+/// This structure describes the result of a reverse swap simulation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseSimulationResponse {
+    /// Offer amount required for the ask amount
+    pub offer_amount: Uint128,
+    /// Amount of each asset along the swap route, from the offer asset to the ask asset
+    pub amounts: Vec<Uint128>,
+    /// Price of the ask asset in offer asset, adjusted by the asset precisions
+    pub belief_price: Decimal,
+}
+
+/// This structure describes a swap route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteResponse {
+    /// List of asset in the swap route
+    pub asset_infos: Vec<AssetInfo>,
+    /// Human-readable label of the swap route
+    pub label: Option<String>,
+}
+
+/// This structure describes the complexity of a swap route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteComplexityResponse {
+    /// Number of swap operations in the route
+    pub hops: u32,
+    /// Whether the stored route is swapped in reverse order
+    pub inverted: bool,
 }
 
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The owner address to set, required for contracts instantiated before the owner was added
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Maximum spread used when a swap does not specify one, swaps without max spread fail until it is set
+    #[serde(default)]
+    pub default_max_spread: Option<Decimal>,
+}